@@ -0,0 +1,374 @@
+use crate::broadphase::{component_max, component_min, encloses, is_bounded, overlaps, Broadphase};
+use crate::vector::Vec3;
+
+// Dynamic bounding volume hierarchy over bodies' world bounds. Each leaf
+// stores its body's bounds fattened by a margin, so a body that moves a
+// little stays inside its leaf and the tree is left alone; one that leaves
+// it is removed and reinserted. Insertion picks the sibling that grows the
+// tree's surface area least, and nodes are rotated on the way back up to
+// keep the tree balanced. Unbounded bodies (planes) are kept out of the
+// tree and paired with everything.
+#[derive(Debug, Clone)]
+pub struct DynamicAabbTree {
+    nodes: Vec<TreeNode>,
+    free_nodes: Vec<usize>,
+    root: Option<usize>,
+    // Leaf node of each body, by body index
+    leaves: Vec<Option<usize>>,
+    unbounded: Vec<usize>,
+    margin: f32,
+}
+
+#[derive(Debug, Clone)]
+struct TreeNode {
+    min: Vec3,
+    max: Vec3,
+    parent: Option<usize>,
+    // None for leaves
+    children: Option<[usize; 2]>,
+    // Body of a leaf
+    body: usize,
+    // 0 for leaves
+    height: usize,
+}
+
+// Default distance each leaf's bounds are grown by
+const DEFAULT_MARGIN: f32 = 0.1;
+
+impl Default for DynamicAabbTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DynamicAabbTree {
+    pub fn new() -> Self {
+        Self::with_margin(DEFAULT_MARGIN)
+    }
+
+    pub fn with_margin(margin: f32) -> Self {
+        Self {
+            nodes: Vec::new(),
+            free_nodes: Vec::new(),
+            root: None,
+            leaves: Vec::new(),
+            unbounded: Vec::new(),
+            margin,
+        }
+    }
+
+    // Number of bodies in the tree, unbounded ones included
+    pub fn len(&self) -> usize {
+        self.leaves.iter().flatten().count() + self.unbounded.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Levels below the root; 0 for a single leaf or an empty tree
+    pub fn height(&self) -> usize {
+        self.root.map_or(0, |root| self.nodes[root].height)
+    }
+
+    // The fattened bounds stored for a body
+    pub fn fat_bounds(&self, body: usize) -> Option<(Vec3, Vec3)> {
+        let leaf = self.leaves.get(body).copied().flatten()?;
+        Some((self.nodes[leaf].min, self.nodes[leaf].max))
+    }
+
+    pub fn insert(&mut self, body: usize, min: Vec3, max: Vec3) {
+        self.remove(body);
+        if self.leaves.len() <= body {
+            self.leaves.resize(body + 1, None);
+        }
+        if !is_bounded(min, max) {
+            self.unbounded.push(body);
+            return;
+        }
+
+        let margin = Vec3::new(self.margin, self.margin, self.margin);
+        let leaf = self.allocate(TreeNode {
+            min: min - margin,
+            max: max + margin,
+            parent: None,
+            children: None,
+            body,
+            height: 0,
+        });
+        self.leaves[body] = Some(leaf);
+        self.insert_leaf(leaf);
+    }
+
+    pub fn remove(&mut self, body: usize) {
+        self.unbounded.retain(|&other| other != body);
+        if let Some(leaf) = self.leaves.get_mut(body).and_then(Option::take) {
+            self.remove_leaf(leaf);
+            self.free_nodes.push(leaf);
+        }
+    }
+
+    // Moves a body's bounds. The tree only changes when they leave the fat
+    // bounds of its leaf; returns whether it did.
+    pub fn update(&mut self, body: usize, min: Vec3, max: Vec3) -> bool {
+        if let Some(leaf) = self.leaves.get(body).copied().flatten() {
+            let node = &self.nodes[leaf];
+            if encloses(node.min, node.max, min, max) {
+                return false;
+            }
+        } else if self.unbounded.contains(&body) && !is_bounded(min, max) {
+            return false;
+        }
+        self.insert(body, min, max);
+        true
+    }
+
+    // Bodies whose fattened bounds overlap the box, unbounded ones included
+    pub fn query_aabb(&self, min: Vec3, max: Vec3) -> Vec<usize> {
+        let mut found = self.unbounded.clone();
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !overlaps(node.min, node.max, min, max) {
+                continue;
+            }
+            match node.children {
+                Some(children) => stack.extend(children),
+                None => found.push(node.body),
+            }
+        }
+        found
+    }
+
+    // Whether the tree still finds every body with these bounds: each lies
+    // within its fat leaf, or is unbounded and kept aside as such
+    fn holds(&self, bounds: &[(Vec3, Vec3)]) -> bool {
+        self.leaves.len() == bounds.len()
+            && bounds.iter().enumerate().all(|(body, &(min, max))| match self.fat_bounds(body) {
+                Some((fat_min, fat_max)) => encloses(fat_min, fat_max, min, max),
+                None => !is_bounded(min, max) && self.unbounded.contains(&body),
+            })
+    }
+
+    fn allocate(&mut self, node: TreeNode) -> usize {
+        match self.free_nodes.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn insert_leaf(&mut self, leaf: usize) {
+        let Some(root) = self.root else {
+            self.root = Some(leaf);
+            self.nodes[leaf].parent = None;
+            return;
+        };
+
+        // Walk down towards the sibling whose union with the leaf costs least
+        let (leaf_min, leaf_max) = (self.nodes[leaf].min, self.nodes[leaf].max);
+        let mut sibling = root;
+        while let Some([left, right]) = self.nodes[sibling].children {
+            let node = &self.nodes[sibling];
+            let area = surface_area(node.min, node.max);
+            let combined = surface_area(component_min(node.min, leaf_min), component_max(node.max, leaf_max));
+            // Pairing with this node makes a new parent here; descending
+            // grows this node by the leaf either way
+            let cost = 2.0 * combined;
+            let inheritance = 2.0 * (combined - area);
+            let descend_cost = |child: usize| {
+                let child = &self.nodes[child];
+                let grown = surface_area(component_min(child.min, leaf_min), component_max(child.max, leaf_max));
+                let growth = if child.children.is_some() { grown - surface_area(child.min, child.max) } else { grown };
+                growth + inheritance
+            };
+            let (left_cost, right_cost) = (descend_cost(left), descend_cost(right));
+            if cost < left_cost && cost < right_cost {
+                break;
+            }
+            sibling = if left_cost < right_cost { left } else { right };
+        }
+
+        let old_parent = self.nodes[sibling].parent;
+        let (sibling_min, sibling_max) = (self.nodes[sibling].min, self.nodes[sibling].max);
+        let parent = self.allocate(TreeNode {
+            min: component_min(sibling_min, leaf_min),
+            max: component_max(sibling_max, leaf_max),
+            parent: old_parent,
+            children: Some([sibling, leaf]),
+            body: 0,
+            height: self.nodes[sibling].height + 1,
+        });
+        self.nodes[sibling].parent = Some(parent);
+        self.nodes[leaf].parent = Some(parent);
+        match old_parent {
+            Some(old_parent) => self.replace_child(old_parent, sibling, parent),
+            None => self.root = Some(parent),
+        }
+
+        self.refit_from(Some(parent));
+    }
+
+    fn remove_leaf(&mut self, leaf: usize) {
+        let Some(parent) = self.nodes[leaf].parent else {
+            self.root = None;
+            return;
+        };
+        let Some([left, right]) = self.nodes[parent].children else {
+            return;
+        };
+        let sibling = if left == leaf { right } else { left };
+        let grandparent = self.nodes[parent].parent;
+
+        self.nodes[sibling].parent = grandparent;
+        match grandparent {
+            Some(grandparent) => {
+                self.replace_child(grandparent, parent, sibling);
+                self.refit_from(Some(grandparent));
+            }
+            None => self.root = Some(sibling),
+        }
+        self.free_nodes.push(parent);
+    }
+
+    // Rebalances and refits every node from `index` up to the root
+    fn refit_from(&mut self, mut index: Option<usize>) {
+        while let Some(current) = index {
+            let current = self.balance(current);
+            self.refit_node(current);
+            index = self.nodes[current].parent;
+        }
+    }
+
+    // If one child of `a` is more than a level taller than the other,
+    // rotates that child up into a's place and returns it; otherwise
+    // returns `a`
+    fn balance(&mut self, a: usize) -> usize {
+        let Some([b, c]) = self.nodes[a].children else {
+            return a;
+        };
+        if self.nodes[a].height < 2 {
+            return a;
+        }
+
+        let imbalance = self.nodes[c].height as isize - self.nodes[b].height as isize;
+        if imbalance > 1 {
+            self.rotate_up(a, c, 1)
+        } else if imbalance < -1 {
+            self.rotate_up(a, b, 0)
+        } else {
+            a
+        }
+    }
+
+    // Puts `child` (a's child on `side`) in a's place. `a` becomes one of its
+    // children and takes over its shorter child; it keeps the taller one.
+    fn rotate_up(&mut self, a: usize, child: usize, side: usize) -> usize {
+        let Some([first, second]) = self.nodes[child].children else {
+            return a;
+        };
+        let (taller, shorter) =
+            if self.nodes[first].height > self.nodes[second].height { (first, second) } else { (second, first) };
+
+        let parent = self.nodes[a].parent;
+        self.nodes[child].parent = parent;
+        match parent {
+            Some(parent) => self.replace_child(parent, a, child),
+            None => self.root = Some(child),
+        }
+        self.nodes[child].children = Some([a, taller]);
+        self.nodes[a].parent = Some(child);
+
+        if let Some(children) = &mut self.nodes[a].children {
+            children[side] = shorter;
+        }
+        self.nodes[shorter].parent = Some(a);
+
+        self.refit_node(a);
+        self.refit_node(child);
+        child
+    }
+
+    // Recomputes an internal node's bounds and height from its children
+    fn refit_node(&mut self, index: usize) {
+        let Some([left, right]) = self.nodes[index].children else {
+            return;
+        };
+        let (left, right) = (&self.nodes[left], &self.nodes[right]);
+        let (min, max) = (component_min(left.min, right.min), component_max(left.max, right.max));
+        let height = 1 + left.height.max(right.height);
+        let node = &mut self.nodes[index];
+        node.min = min;
+        node.max = max;
+        node.height = height;
+    }
+
+    fn replace_child(&mut self, parent: usize, old: usize, new: usize) {
+        if let Some(children) = &mut self.nodes[parent].children {
+            for child in children.iter_mut() {
+                if *child == old {
+                    *child = new;
+                }
+            }
+        }
+    }
+}
+
+impl Broadphase for DynamicAabbTree {
+    fn find_pairs(&mut self, bounds: &[(Vec3, Vec3)]) -> Vec<(usize, usize)> {
+        self.sync(bounds);
+
+        let mut pairs = Vec::new();
+        for (body, &(min, max)) in bounds.iter().enumerate() {
+            if !is_bounded(min, max) {
+                // Every other body overlaps it
+                let others = (0..bounds.len()).filter(|&other| other != body);
+                pairs.extend(others.map(|other| (body.min(other), body.max(other))));
+                continue;
+            }
+            // Fat leaves find a superset; keep the pairs whose own bounds overlap
+            for other in self.query_aabb(min, max) {
+                let (other_min, other_max) = bounds[other];
+                if other > body && is_bounded(other_min, other_max) && overlaps(min, max, other_min, other_max) {
+                    pairs.push((body, other));
+                }
+            }
+        }
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+
+    fn sync(&mut self, bounds: &[(Vec3, Vec3)]) {
+        for body in bounds.len()..self.leaves.len() {
+            self.remove(body);
+        }
+        self.leaves.truncate(bounds.len());
+        for (body, &(min, max)) in bounds.iter().enumerate() {
+            self.update(body, min, max);
+        }
+    }
+
+    fn query_bounds(&self, bounds: &[(Vec3, Vec3)], min: Vec3, max: Vec3) -> Vec<usize> {
+        if !self.holds(bounds) {
+            return (0..bounds.len()).filter(|&body| overlaps(bounds[body].0, bounds[body].1, min, max)).collect();
+        }
+        let mut found: Vec<usize> = self
+            .query_aabb(min, max)
+            .into_iter()
+            .filter(|&body| overlaps(bounds[body].0, bounds[body].1, min, max))
+            .collect();
+        found.sort_unstable();
+        found
+    }
+}
+
+fn surface_area(min: Vec3, max: Vec3) -> f32 {
+    let size = max - min;
+    2.0 * (size.x * size.y + size.y * size.z + size.z * size.x)
+}
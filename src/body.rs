@@ -1,84 +1,198 @@
 use crate::collision::CollisionShape;
-use crate::vector::Vec3;
+use crate::mass::MassProperties;
+use crate::vector::{Mat3, Quat, Vec3};
 
 pub struct StaticBody {
     pub position: Vec3,
+    pub orientation: Quat,
     pub shape: CollisionShape,
     pub friction: f32,
 }
 
 impl StaticBody {
     pub fn new(position: Vec3, shape: CollisionShape) -> Self {
-        Self { position, shape, friction: 0.25 }
+        Self { position, orientation: Quat::identity(), shape, friction: 0.25 }
     }
 }
 
 pub struct RigidBody {
     pub position: Vec3,
+    pub orientation: Quat,
     pub velocity: Vec3,
     pub acceleration: Vec3,
+    pub angular_velocity: Vec3,
     pub mass: f32,
+    // Inertia tensor about the centre of mass, in the body's local frame
+    pub inertia_tensor: Mat3,
+    // Centre of mass relative to `position`, in the body's local frame
+    pub center_of_mass: Vec3,
     pub shape: CollisionShape,
     pub friction: f32,
     force_accumulator: Vec3,
+    torque_accumulator: Vec3,
+    sleeping: bool,
+    // Seconds the body has stayed slower than the world's sleep thresholds
+    sleep_timer: f32,
 }
 
 impl RigidBody {
     pub fn new(position: Vec3) -> Self {
-        Self {
-            position,
-            velocity: Vec3::zero(),
-            acceleration: Vec3::zero(),
-            mass: 1.0,
-            shape: CollisionShape::Sphere { radius: 0.5 },
-            friction: 0.25,
-            force_accumulator: Vec3::zero(),
-        }
+        Self::with_shape(position, 1.0, CollisionShape::Sphere { radius: 0.5 })
     }
 
     pub fn with_mass(position: Vec3, mass: f32) -> Self {
-        Self {
-            position,
-            velocity: Vec3::zero(),
-            acceleration: Vec3::zero(),
-            mass,
-            shape: CollisionShape::Sphere { radius: 0.5 },
-            friction: 0.25,
-            force_accumulator: Vec3::zero(),
-        }
+        Self::with_shape(position, mass, CollisionShape::Sphere { radius: 0.5 })
     }
 
     pub fn with_shape(position: Vec3, mass: f32, shape: CollisionShape) -> Self {
+        let mass_properties = MassProperties::from_shape_with_mass(&shape, mass);
+        Self::with_mass_properties(position, &mass_properties, shape)
+    }
+
+    // Mass and inertia derived from the shape's volume, e.g. a large crate
+    // and a small pebble of the same material weigh what they should
+    pub fn with_density(position: Vec3, density: f32, shape: CollisionShape) -> Self {
+        let mass_properties = MassProperties::from_shape(&shape, density);
+        Self::with_mass_properties(position, &mass_properties, shape)
+    }
+
+    pub fn with_mass_properties(
+        position: Vec3,
+        mass_properties: &MassProperties,
+        shape: CollisionShape,
+    ) -> Self {
         Self {
             position,
+            orientation: Quat::identity(),
             velocity: Vec3::zero(),
             acceleration: Vec3::zero(),
-            mass,
+            angular_velocity: Vec3::zero(),
+            mass: mass_properties.mass,
+            inertia_tensor: mass_properties.local_inertia,
+            center_of_mass: mass_properties.center_of_mass,
             shape,
             friction: 0.25,
             force_accumulator: Vec3::zero(),
+            torque_accumulator: Vec3::zero(),
+            sleeping: false,
+            sleep_timer: 0.0,
         }
     }
 
+    // Wakes a sleeping body, as it will no longer stay put
     pub fn apply_force(&mut self, force: Vec3) {
+        if self.sleeping {
+            self.wake();
+        }
         self.force_accumulator += force;
     }
 
+    // 1 / mass, or 0 for bodies no force or contact can move: massless ones,
+    // and those of infinite mass, which every body given a plane, mesh or
+    // terrain shape has. They still move at whatever velocity they are given.
+    pub fn inverse_mass(&self) -> f32 {
+        if self.mass.is_finite() && self.mass > 0.0 { 1.0 / self.mass } else { 0.0 }
+    }
+
+    pub fn set_mass_properties(&mut self, mass_properties: &MassProperties) {
+        self.mass = mass_properties.mass;
+        self.inertia_tensor = mass_properties.local_inertia;
+        self.center_of_mass = mass_properties.center_of_mass;
+    }
+
+    pub fn center_of_mass_world(&self) -> Vec3 {
+        self.position + self.orientation.rotate(self.center_of_mass)
+    }
+
+    pub fn apply_torque(&mut self, torque: Vec3) {
+        if self.sleeping {
+            self.wake();
+        }
+        self.torque_accumulator += torque;
+    }
+
+    // Applies a force at a world-space point; anything off the centre of mass
+    // also produces a torque of r x F
+    pub fn apply_force_at_point(&mut self, force: Vec3, world_point: Vec3) {
+        self.apply_force(force);
+        self.apply_torque((world_point - self.center_of_mass_world()).cross(&force));
+    }
+
     pub fn clear_forces(&mut self) {
         self.force_accumulator = Vec3::zero();
+        self.torque_accumulator = Vec3::zero();
+    }
+
+    // Inverse inertia tensor rotated into world space: R * I^-1 * R^T
+    pub fn inverse_inertia_world(&self) -> Mat3 {
+        self.inertia_tensor
+            .inverse()
+            .rotate_tensor(&Mat3::from_quat(self.orientation))
+    }
+
+    pub fn apply_inverse_inertia(&self, v: Vec3) -> Vec3 {
+        self.inverse_inertia_world() * v
     }
 
     // New method: Integrates velocity based on acceleration
     pub fn integrate_velocity(&mut self, dt: f32) {
         if self.mass > 0.0 {
-            self.acceleration = self.force_accumulator * (1.0 / self.mass);
+            self.acceleration = self.force_accumulator * self.inverse_mass();
         }
         self.velocity += self.acceleration * dt;
+        if !self.keeps_orientation() {
+            self.angular_velocity += self.apply_inverse_inertia(self.torque_accumulator) * dt;
+        }
     }
 
     // New method: Integrates position based on velocity
     pub fn integrate_position(&mut self, dt: f32) {
-        self.position += self.velocity * dt;
+        // Rotate about the centre of mass, then place the origin back relative to it
+        let center_of_mass = self.center_of_mass_world() + self.velocity * dt;
+        if !self.keeps_orientation() {
+            self.orientation = self.orientation.integrate(self.angular_velocity, dt);
+        }
+        self.position = center_of_mass - self.orientation.rotate(self.center_of_mass);
+    }
+
+    // AABBs stay axis-aligned whatever the body's orientation, so spinning
+    // one would only turn a box the collision code never sees turn
+    fn keeps_orientation(&self) -> bool {
+        matches!(self.shape, CollisionShape::AABB { .. })
+    }
+
+    // Sleeping bodies are left out of integration and collision checks
+    // until something wakes them
+    pub fn is_sleeping(&self) -> bool {
+        self.sleeping
+    }
+
+    pub fn sleep_timer(&self) -> f32 {
+        self.sleep_timer
+    }
+
+    // Stops the body where it is until it is woken
+    pub fn sleep(&mut self) {
+        self.sleeping = true;
+        self.velocity = Vec3::zero();
+        self.angular_velocity = Vec3::zero();
+        self.clear_forces();
+    }
+
+    // Also restarts the timer, so a woken body gets the full sleep time to move
+    pub fn wake(&mut self) {
+        self.sleeping = false;
+        self.sleep_timer = 0.0;
+    }
+
+    // Advances the sleep timer by a step if the body was slow during it, and
+    // starts it over otherwise
+    pub(crate) fn track_rest(&mut self, dt: f32, linear_threshold: f32, angular_threshold: f32) {
+        if self.velocity.magnitude() < linear_threshold && self.angular_velocity.magnitude() < angular_threshold {
+            self.sleep_timer += dt;
+        } else {
+            self.sleep_timer = 0.0;
+        }
     }
 
     pub fn update(&mut self, dt: f32) {
@@ -129,6 +243,13 @@ impl Body {
         }
     }
 
+    pub fn orientation(&self) -> &Quat {
+        match self {
+            Body::Rigid(body) => &body.orientation,
+            Body::Static(body) => &body.orientation,
+        }
+    }
+
     pub fn shape(&self) -> &CollisionShape {
         match self {
             Body::Rigid(body) => &body.shape,
@@ -142,4 +263,15 @@ impl Body {
             Body::Static(body) => body.friction,
         }
     }
+
+    // Static bodies never sleep
+    pub fn is_sleeping(&self) -> bool {
+        self.as_rigid_body().is_some_and(RigidBody::is_sleeping)
+    }
+
+    pub fn wake(&mut self) {
+        if let Body::Rigid(body) = self {
+            body.wake();
+        }
+    }
 }
\ No newline at end of file
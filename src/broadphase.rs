@@ -0,0 +1,167 @@
+use crate::vector::Vec3;
+
+// Finds the pairs of bodies worth handing to the narrowphase. Each call gets
+// the world-space bounds of the rigid bodies only, as a compact list: entry k
+// belongs to the k-th rigid body in `PhysicsWorld::bodies`, static bodies
+// being left to the world's octree. The pairs `(i, j)` returned, with i < j,
+// index into that list; the world maps them back to body indices and sorts
+// them, so the solver sees them in the same order whichever broadphase found
+// them. Implementations may keep state between calls to speed up the next
+// one, bearing in mind that list positions shift as bodies come and go.
+pub trait Broadphase: Send + Sync {
+    fn find_pairs(&mut self, bounds: &[(Vec3, Vec3)]) -> Vec<(usize, usize)>;
+
+    // Brings any state kept between calls in line with the bounds without
+    // looking for pairs. The world calls it after each update, once bodies
+    // have stopped moving, so queries until the next one find it current.
+    fn sync(&mut self, _bounds: &[(Vec3, Vec3)]) {}
+
+    // Positions in the list whose bounds overlap the box, in order, which the
+    // world maps back to body indices like the pairs. Broadphases that keep a
+    // spatial structure answer from it while it still matches the bounds;
+    // the rest, and those whose bounds moved since, check every entry.
+    fn query_bounds(&self, bounds: &[(Vec3, Vec3)], min: Vec3, max: Vec3) -> Vec<usize> {
+        (0..bounds.len()).filter(|&body| overlaps(bounds[body].0, bounds[body].1, min, max)).collect()
+    }
+}
+
+// Tests every pair of bounds against each other
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BruteForce;
+
+impl Broadphase for BruteForce {
+    fn find_pairs(&mut self, bounds: &[(Vec3, Vec3)]) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for i in 0..bounds.len() {
+            for j in (i + 1)..bounds.len() {
+                if overlaps(bounds[i].0, bounds[i].1, bounds[j].0, bounds[j].1) {
+                    pairs.push((i, j));
+                }
+            }
+        }
+        pairs
+    }
+}
+
+// Sweep and prune: bodies are kept sorted by where their bounds start along
+// one axis, and a sweep along it only compares bodies whose intervals
+// overlap. The order is kept between calls, so when bodies have moved a
+// little an insertion sort puts it right again in close to linear time. The
+// axis is the one the bodies' centres are most spread along, so the fewest
+// intervals overlap.
+#[derive(Debug, Clone, Default)]
+pub struct SweepAndPrune {
+    order: Vec<usize>,
+    axis: usize,
+}
+
+impl SweepAndPrune {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Axis (0, 1, 2 for x, y, z) the last call swept along
+    pub fn axis(&self) -> usize {
+        self.axis
+    }
+}
+
+impl Broadphase for SweepAndPrune {
+    fn find_pairs(&mut self, bounds: &[(Vec3, Vec3)]) -> Vec<(usize, usize)> {
+        // Forget removed bodies and append new ones; the sort places them
+        self.order.retain(|&index| index < bounds.len());
+        for index in self.order.len()..bounds.len() {
+            self.order.push(index);
+        }
+
+        self.axis = largest_variance_axis(bounds);
+        let axis = self.axis;
+        let start = |index: usize| component(bounds[index].0, axis);
+        for sorted in 1..self.order.len() {
+            let mut position = sorted;
+            while position > 0 && start(self.order[position - 1]) > start(self.order[position]) {
+                self.order.swap(position - 1, position);
+                position -= 1;
+            }
+        }
+
+        let mut pairs = Vec::new();
+        let mut active: Vec<usize> = Vec::new();
+        for &index in &self.order {
+            let (min, max) = bounds[index];
+            // Intervals that ended before this one starts can overlap nothing after it
+            active.retain(|&other| component(bounds[other].1, axis) >= component(min, axis));
+            for &other in &active {
+                if overlaps(min, max, bounds[other].0, bounds[other].1) {
+                    pairs.push((index.min(other), index.max(other)));
+                }
+            }
+            active.push(index);
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+}
+
+// False for the infinite bounds of unbounded shapes such as planes
+pub(crate) fn is_bounded(min: Vec3, max: Vec3) -> bool {
+    [min.x, min.y, min.z, max.x, max.y, max.z].iter().all(|v| v.is_finite())
+}
+
+// Whether the outer box holds the inner one
+pub(crate) fn encloses(outer_min: Vec3, outer_max: Vec3, inner_min: Vec3, inner_max: Vec3) -> bool {
+    outer_min.x <= inner_min.x
+        && outer_min.y <= inner_min.y
+        && outer_min.z <= inner_min.z
+        && outer_max.x >= inner_max.x
+        && outer_max.y >= inner_max.y
+        && outer_max.z >= inner_max.z
+}
+
+pub(crate) fn component_min(a: Vec3, b: Vec3) -> Vec3 {
+    Vec3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z))
+}
+
+pub(crate) fn component_max(a: Vec3, b: Vec3) -> Vec3 {
+    Vec3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z))
+}
+
+// Whether two boxes overlap, touching included
+pub(crate) fn overlaps(min_a: Vec3, max_a: Vec3, min_b: Vec3, max_b: Vec3) -> bool {
+    min_a.x <= max_b.x
+        && max_a.x >= min_b.x
+        && min_a.y <= max_b.y
+        && max_a.y >= min_b.y
+        && min_a.z <= max_b.z
+        && max_a.z >= min_b.z
+}
+
+pub(crate) fn component(v: Vec3, axis: usize) -> f32 {
+    match axis {
+        0 => v.x,
+        1 => v.y,
+        _ => v.z,
+    }
+}
+
+// Axis along which the centres of the finite bounds vary the most. Unbounded
+// shapes such as planes overlap everything wherever the sweep runs, so they
+// are left out.
+fn largest_variance_axis(bounds: &[(Vec3, Vec3)]) -> usize {
+    let centers: Vec<Vec3> = bounds
+        .iter()
+        .filter(|(min, max)| is_bounded(*min, *max))
+        .map(|(min, max)| (*min + *max) * 0.5)
+        .collect();
+    if centers.is_empty() {
+        return 0;
+    }
+
+    let count = centers.len() as f32;
+    let mean = centers.iter().fold(Vec3::zero(), |sum, center| sum + *center) * (1.0 / count);
+    let variance = centers.iter().fold(Vec3::zero(), |sum, center| {
+        let offset = *center - mean;
+        sum + Vec3::new(offset.x * offset.x, offset.y * offset.y, offset.z * offset.z)
+    });
+    (0..3).fold(0, |best, axis| if component(variance, axis) > component(variance, best) { axis } else { best })
+}
@@ -1,10 +1,219 @@
-use crate::vector::Vec3;
+use crate::convex_hull::ConvexHull;
+use crate::gjk::{gjk_epa, SupportMap};
+use crate::heightfield::Heightfield;
+use crate::broadphase::{component_max, component_min};
+use crate::trimesh::{TriMesh, TriangleSource};
+use crate::vector::{Mat3, Quat, Vec3};
+use std::sync::Arc;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+// Shapes are `Clone` but no longer `Copy`: hulls, meshes, terrain and
+// compounds own their data, so code that copied shapes has to clone them.
+// Cloning a hull only bumps a reference count.
+#[derive(Debug, Clone, PartialEq)]
 pub enum CollisionShape {
     Sphere { radius: f32 },
     Plane { normal: Vec3, distance: f32 },
+    // `min`/`max` are local extents, offset by the owning body's position.
+    // AABBs never rotate: the body's orientation is ignored, and rigid
+    // bodies with this shape are not spun by torques or contacts.
     AABB { min: Vec3, max: Vec3 },
+    // Oriented box centred on the body, rotated by the body's orientation
+    Box { half_extents: Vec3 },
+    // Segment of length 2 * half_height along the body's local y axis, swept by `radius`
+    Capsule { half_height: f32, radius: f32 },
+    // Solid cylinder of height 2 * half_height along the body's local y axis
+    Cylinder { half_height: f32, radius: f32 },
+    // Solid cone along the body's local y axis, base at -half_height and apex
+    // at +half_height
+    Cone { half_height: f32, radius: f32 },
+    // Convex hull of a point cloud, in the body's local frame. Shared, so
+    // cloning a shape does not copy its vertices.
+    ConvexHull(Arc<ConvexHull>),
+    // Triangle mesh for static level geometry
+    TriMesh(TriMesh),
+    // Grid of heights for static terrain
+    Heightfield(Heightfield),
+    // Several shapes rigidly attached to one body, each placed by its
+    // transform relative to the body
+    Compound(Vec<(LocalTransform, CollisionShape)>),
+}
+
+// Placement of a child shape within its body's frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LocalTransform {
+    pub position: Vec3,
+    pub rotation: Quat,
+}
+
+impl LocalTransform {
+    pub fn new(position: Vec3, rotation: Quat) -> Self {
+        Self { position, rotation }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(Vec3::zero(), Quat::identity())
+    }
+
+    pub fn from_position(position: Vec3) -> Self {
+        Self::new(position, Quat::identity())
+    }
+
+    // World pose of the child of a body at `position` / `rotation`
+    pub fn apply(&self, position: Vec3, rotation: Quat) -> (Vec3, Quat) {
+        (position + rotation.rotate(self.position), rotation * self.rotation)
+    }
+}
+
+impl CollisionShape {
+    pub fn convex_hull(points: &[Vec3]) -> Self {
+        CollisionShape::ConvexHull(Arc::new(ConvexHull::from_points(points)))
+    }
+
+    // Furthest point along a local-space direction, for shapes with a finite
+    // convex extent. Planes have none.
+    pub fn local_support(&self, direction: Vec3) -> Option<Vec3> {
+        let sign = |v: f32| if v < 0.0 { -1.0 } else { 1.0 };
+        let support = match self {
+            CollisionShape::Sphere { radius } => unit_or_x(direction) * *radius,
+            CollisionShape::AABB { min, max } => Vec3::new(
+                if direction.x < 0.0 { min.x } else { max.x },
+                if direction.y < 0.0 { min.y } else { max.y },
+                if direction.z < 0.0 { min.z } else { max.z },
+            ),
+            CollisionShape::Box { half_extents } => Vec3::new(
+                sign(direction.x) * half_extents.x,
+                sign(direction.y) * half_extents.y,
+                sign(direction.z) * half_extents.z,
+            ),
+            CollisionShape::Capsule { half_height, radius } => {
+                Vec3::new(0.0, sign(direction.y) * half_height, 0.0) + unit_or_x(direction) * *radius
+            }
+            CollisionShape::Cylinder { half_height, radius } => {
+                Vec3::new(0.0, sign(direction.y) * half_height, 0.0) + radial_unit(direction) * *radius
+            }
+            CollisionShape::Cone { half_height, radius } => {
+                let apex = Vec3::new(0.0, *half_height, 0.0);
+                let rim = Vec3::new(0.0, -half_height, 0.0) + radial_unit(direction) * *radius;
+                if apex.dot(&direction) >= rim.dot(&direction) { apex } else { rim }
+            }
+            CollisionShape::ConvexHull(hull) => hull.support(direction),
+            CollisionShape::Plane { .. }
+            | CollisionShape::TriMesh(_)
+            | CollisionShape::Heightfield(_)
+            | CollisionShape::Compound(_) => return None,
+        };
+        Some(support)
+    }
+
+    // World-space bounding box of the shape placed at `position`. Planes are
+    // unbounded, so their box is infinite on every axis.
+    pub fn world_bounds(&self, position: Vec3, rotation: Quat) -> (Vec3, Vec3) {
+        let local_box = |(min, max): (Vec3, Vec3)| {
+            let corners = (0..8).map(|i| {
+                Vec3::new(
+                    if i & 1 == 0 { min.x } else { max.x },
+                    if i & 2 == 0 { min.y } else { max.y },
+                    if i & 4 == 0 { min.z } else { max.z },
+                )
+            });
+            bounds_of(corners.map(|corner| position + rotation.rotate(corner)))
+        };
+        match self {
+            CollisionShape::Plane { .. } => {
+                let infinite = Vec3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+                (-infinite, infinite)
+            }
+            CollisionShape::TriMesh(mesh) => mesh.bounds().map_or((position, position), local_box),
+            CollisionShape::Heightfield(terrain) => local_box(terrain.bounds()),
+            CollisionShape::Compound(children) => {
+                let mut bounds = (position, position);
+                for (index, (transform, child)) in children.iter().enumerate() {
+                    let (child_position, child_rotation) = transform.apply(position, rotation);
+                    let (min, max) = child.world_bounds(child_position, child_rotation);
+                    bounds = if index == 0 {
+                        (min, max)
+                    } else {
+                        (component_min(bounds.0, min), component_max(bounds.1, max))
+                    };
+                }
+                bounds
+            }
+            _ => {
+                let Some(support) = ShapeSupport::new(self, position, rotation) else {
+                    return (position, position);
+                };
+                let axes = [Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0)];
+                bounds_of(axes.iter().flat_map(|axis| [support.support(*axis), support.support(-*axis)]))
+            }
+        }
+    }
+
+    // A point inside the shape, in the local frame
+    pub fn local_center(&self) -> Vec3 {
+        match self {
+            CollisionShape::AABB { min, max } => (*min + *max) * 0.5,
+            CollisionShape::ConvexHull(hull) if !hull.vertices.is_empty() => {
+                hull.vertices.iter().fold(Vec3::zero(), |sum, v| sum + *v) * (1.0 / hull.vertices.len() as f32)
+            }
+            _ => Vec3::zero(),
+        }
+    }
+}
+
+// Smallest box holding all the points
+fn bounds_of(mut points: impl Iterator<Item = Vec3>) -> (Vec3, Vec3) {
+    let first = points.next().unwrap_or(Vec3::zero());
+    points.fold((first, first), |(min, max), point| (component_min(min, point), component_max(max, point)))
+}
+
+// Unit direction of the xz part of `direction`, or zero along the y axis
+fn radial_unit(direction: Vec3) -> Vec3 {
+    let radial = Vec3::new(direction.x, 0.0, direction.z);
+    if radial.magnitude_squared() < 1.0e-12 {
+        Vec3::zero()
+    } else {
+        radial.normalize()
+    }
+}
+
+fn unit_or_x(direction: Vec3) -> Vec3 {
+    if direction.magnitude_squared() == 0.0 {
+        Vec3::new(1.0, 0.0, 0.0)
+    } else {
+        direction.normalize()
+    }
+}
+
+// A convex shape placed in the world, for use with GJK/EPA
+pub struct ShapeSupport<'a> {
+    shape: &'a CollisionShape,
+    position: Vec3,
+    rotation: Quat,
+}
+
+impl<'a> ShapeSupport<'a> {
+    // None for shapes without a support function (planes)
+    pub fn new(shape: &'a CollisionShape, position: Vec3, rotation: Quat) -> Option<Self> {
+        shape.local_support(Vec3::new(1.0, 0.0, 0.0))?;
+        // AABBs never rotate with their body
+        let rotation = match shape {
+            CollisionShape::AABB { .. } => Quat::identity(),
+            _ => rotation,
+        };
+        Some(Self { shape, position, rotation })
+    }
+}
+
+impl SupportMap for ShapeSupport<'_> {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let local_direction = self.rotation.inverse().rotate(direction);
+        let local = self.shape.local_support(local_direction).unwrap_or(Vec3::zero());
+        self.position + self.rotation.rotate(local)
+    }
+
+    fn center(&self) -> Vec3 {
+        self.position + self.rotation.rotate(self.shape.local_center())
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -35,6 +244,445 @@ impl CollisionInfo {
     }
 }
 
+// Most points a contact manifold keeps; four well-spread points are enough to
+// hold a face flat against another
+pub const MAX_MANIFOLD_POINTS: usize = 4;
+
+// One point of a contact manifold with its own penetration depth. `id` names
+// the pair of features (corner, clipped edge, ...) that produced the point,
+// so the same contact can be recognised from one step to the next; points
+// from routines without features use 0.
+#[derive(Debug, Clone, Copy)]
+pub struct ContactPoint {
+    pub point: Vec3,
+    pub depth: f32,
+    pub id: u32,
+}
+
+// Contact points between two shapes that share one normal, pointing from A
+// to B as in CollisionInfo. A box lying on the ground gets a point under each
+// corner, so it is held flat instead of balancing on a single point.
+#[derive(Debug, Clone)]
+pub struct ContactManifold {
+    pub normal: Vec3,
+    pub points: Vec<ContactPoint>,
+}
+
+impl ContactManifold {
+    // Single-point manifold from a narrowphase result
+    pub fn from_info(info: &CollisionInfo) -> Self {
+        Self {
+            normal: info.normal,
+            points: vec![ContactPoint { point: info.contact_point, depth: info.penetration_depth, id: 0 }],
+        }
+    }
+
+    pub fn deepest(&self) -> Option<ContactPoint> {
+        self.points.iter().copied().max_by(|a, b| a.depth.total_cmp(&b.depth))
+    }
+
+    // Collapses the manifold to one contact: the deepest depth at the
+    // average of the points
+    pub fn to_info(&self) -> CollisionInfo {
+        let Some(deepest) = self.deepest() else {
+            return CollisionInfo::none();
+        };
+        let sum = self.points.iter().fold(Vec3::zero(), |sum, contact| sum + contact.point);
+        CollisionInfo::new(self.normal, deepest.depth, sum * (1.0 / self.points.len() as f32))
+    }
+}
+
+// Where a ray first meets a surface; the normal faces back along the ray
+#[derive(Debug, Clone, Copy)]
+pub struct RayHit {
+    pub distance: f32,
+    pub point: Vec3,
+    pub normal: Vec3,
+}
+
+// Narrowphase for two placed shapes; the normal points from A to B. Pairs with
+// an analytic routine use it (in either order, flipping the normal) and every
+// other pair of convex shapes goes through GJK/EPA. Returns None for pairs
+// that can never produce a contact, such as two planes. Compound shapes
+// report their deepest child contact; see collide_contacts for all of them.
+pub fn collide(
+    shape_a: &CollisionShape,
+    pos_a: Vec3,
+    rot_a: Quat,
+    shape_b: &CollisionShape,
+    pos_b: Vec3,
+    rot_b: Quat,
+) -> Option<CollisionInfo> {
+    if matches!(shape_a, CollisionShape::Compound(_)) || matches!(shape_b, CollisionShape::Compound(_)) {
+        let deepest = collide_contacts(shape_a, pos_a, rot_a, shape_b, pos_b, rot_b)
+            .into_iter()
+            .max_by(|a, b| a.penetration_depth.total_cmp(&b.penetration_depth));
+        return Some(deepest.unwrap_or(CollisionInfo::none()));
+    }
+
+    if let Some(result) = collide_analytic(shape_a, pos_a, rot_a, shape_b, pos_b, rot_b) {
+        return result;
+    }
+    if let Some(result) = collide_analytic(shape_b, pos_b, rot_b, shape_a, pos_a, rot_a) {
+        return result.map(|mut info| {
+            info.normal = -info.normal;
+            info
+        });
+    }
+
+    let support_a = ShapeSupport::new(shape_a, pos_a, rot_a)?;
+    let support_b = ShapeSupport::new(shape_b, pos_b, rot_b)?;
+    Some(gjk_epa(&support_a, &support_b))
+}
+
+// Every contact between two placed shapes, one per pair of touching children
+// when either is a compound
+pub fn collide_contacts(
+    shape_a: &CollisionShape,
+    pos_a: Vec3,
+    rot_a: Quat,
+    shape_b: &CollisionShape,
+    pos_b: Vec3,
+    rot_b: Quat,
+) -> Vec<CollisionInfo> {
+    let mut contacts = Vec::new();
+    push_contacts(shape_a, pos_a, rot_a, shape_b, pos_b, rot_b, &mut contacts);
+    contacts
+}
+
+#[allow(clippy::too_many_arguments)]
+fn push_contacts(
+    shape_a: &CollisionShape,
+    pos_a: Vec3,
+    rot_a: Quat,
+    shape_b: &CollisionShape,
+    pos_b: Vec3,
+    rot_b: Quat,
+    contacts: &mut Vec<CollisionInfo>,
+) {
+    if let CollisionShape::Compound(children) = shape_a {
+        for (transform, child) in children {
+            let (child_pos, child_rot) = transform.apply(pos_a, rot_a);
+            push_contacts(child, child_pos, child_rot, shape_b, pos_b, rot_b, contacts);
+        }
+        return;
+    }
+    if let CollisionShape::Compound(children) = shape_b {
+        for (transform, child) in children {
+            let (child_pos, child_rot) = transform.apply(pos_b, rot_b);
+            push_contacts(shape_a, pos_a, rot_a, child, child_pos, child_rot, contacts);
+        }
+        return;
+    }
+
+    if let Some(info) = collide(shape_a, pos_a, rot_a, shape_b, pos_b, rot_b)
+        && info.is_colliding
+    {
+        contacts.push(info);
+    }
+}
+
+// Contact manifolds between two placed shapes, one per pair of touching
+// children when either is a compound. Box and plane pairs are clipped into
+// several points; every other pair gives a single-point manifold.
+pub fn collide_manifolds(
+    shape_a: &CollisionShape,
+    pos_a: Vec3,
+    rot_a: Quat,
+    shape_b: &CollisionShape,
+    pos_b: Vec3,
+    rot_b: Quat,
+) -> Vec<ContactManifold> {
+    let mut manifolds = Vec::new();
+    push_manifolds(shape_a, pos_a, rot_a, shape_b, pos_b, rot_b, &mut manifolds);
+    manifolds
+}
+
+#[allow(clippy::too_many_arguments)]
+fn push_manifolds(
+    shape_a: &CollisionShape,
+    pos_a: Vec3,
+    rot_a: Quat,
+    shape_b: &CollisionShape,
+    pos_b: Vec3,
+    rot_b: Quat,
+    manifolds: &mut Vec<ContactManifold>,
+) {
+    // Point ids are tagged with the child they came from, so contacts of
+    // different children stay apart from one step to the next
+    let tag_child = |manifolds: &mut [ContactManifold], side: u32, index: usize| {
+        for contact in manifolds.iter_mut().flat_map(|manifold| manifold.points.iter_mut()) {
+            contact.id ^= (index as u32 + 1) << 24 | side << 23;
+        }
+    };
+
+    if let CollisionShape::Compound(children) = shape_a {
+        for (index, (transform, child)) in children.iter().enumerate() {
+            let (child_pos, child_rot) = transform.apply(pos_a, rot_a);
+            let first = manifolds.len();
+            push_manifolds(child, child_pos, child_rot, shape_b, pos_b, rot_b, manifolds);
+            tag_child(&mut manifolds[first..], 0, index);
+        }
+        return;
+    }
+    if let CollisionShape::Compound(children) = shape_b {
+        for (index, (transform, child)) in children.iter().enumerate() {
+            let (child_pos, child_rot) = transform.apply(pos_b, rot_b);
+            let first = manifolds.len();
+            push_manifolds(shape_a, pos_a, rot_a, child, child_pos, child_rot, manifolds);
+            tag_child(&mut manifolds[first..], 1, index);
+        }
+        return;
+    }
+
+    let manifold = if let Some(result) = manifold_analytic(shape_a, pos_a, rot_a, shape_b, pos_b, rot_b) {
+        result
+    } else if let Some(result) = manifold_analytic(shape_b, pos_b, rot_b, shape_a, pos_a, rot_a) {
+        result.map(|mut manifold| {
+            manifold.normal = -manifold.normal;
+            manifold
+        })
+    } else {
+        collide(shape_a, pos_a, rot_a, shape_b, pos_b, rot_b)
+            .filter(|info| info.is_colliding)
+            .map(|info| ContactManifold::from_info(&info))
+    };
+    if let Some(manifold) = manifold.filter(|manifold| !manifold.points.is_empty()) {
+        manifolds.push(manifold);
+    }
+}
+
+// Multi-point routines for specific shape pairs, in this argument order only.
+// The outer None means the pair only has a single-point routine.
+fn manifold_analytic(
+    shape_a: &CollisionShape,
+    pos_a: Vec3,
+    rot_a: Quat,
+    shape_b: &CollisionShape,
+    pos_b: Vec3,
+    rot_b: Quat,
+) -> Option<Option<ContactManifold>> {
+    // AABBs are boxes that never rotate
+    let as_box = |shape: &CollisionShape, position: Vec3, rotation: Quat| match shape {
+        CollisionShape::Box { half_extents } => Some((position, rotation, *half_extents)),
+        CollisionShape::AABB { min, max } => {
+            let (center, half_extents) = aabb_as_box(position, *min, *max);
+            Some((center, Quat::identity(), half_extents))
+        }
+        _ => None,
+    };
+
+    let world_plane = plane_in_world(shape_b, rot_b);
+    let shape_b = world_plane.as_ref().unwrap_or(shape_b);
+    let manifold = match (as_box(shape_a, pos_a, rot_a), shape_b) {
+        (Some((center, rotation, half_extents)), CollisionShape::Plane { normal, distance }) => {
+            box_vs_plane_manifold(center, rotation, half_extents, *normal, plane_world_distance(pos_b, *normal, *distance))
+        }
+        (Some((center_a, rotation_a, half_a)), _) => {
+            let (center_b, rotation_b, half_b) = as_box(shape_b, pos_b, rot_b)?;
+            box_vs_box_manifold(center_a, rotation_a, half_a, center_b, rotation_b, half_b)
+        }
+        _ => return None,
+    };
+    Some(manifold)
+}
+
+// Dedicated routines for specific shape pairs, in this argument order only.
+// The outer None means there is no such routine for the pair.
+fn collide_analytic(
+    shape_a: &CollisionShape,
+    pos_a: Vec3,
+    rot_a: Quat,
+    shape_b: &CollisionShape,
+    pos_b: Vec3,
+    rot_b: Quat,
+) -> Option<Option<CollisionInfo>> {
+    let world_plane = plane_in_world(shape_b, rot_b);
+    let shape_b = world_plane.as_ref().unwrap_or(shape_b);
+    let info = match (shape_a, shape_b) {
+        (CollisionShape::Sphere { radius: r1 }, CollisionShape::Sphere { radius: r2 }) => {
+            sphere_vs_sphere(pos_a, *r1, pos_b, *r2)
+        }
+        (CollisionShape::Sphere { radius }, CollisionShape::Plane { normal, distance }) => {
+            sphere_vs_plane(pos_a, *radius, *normal, plane_world_distance(pos_b, *normal, *distance))
+        }
+        (CollisionShape::Sphere { radius }, CollisionShape::AABB { min, max }) => {
+            sphere_vs_aabb(pos_a, *radius, *min + pos_b, *max + pos_b)
+        }
+        (CollisionShape::Sphere { radius }, CollisionShape::Box { half_extents }) => {
+            sphere_vs_box(pos_a, *radius, pos_b, rot_b, *half_extents)
+        }
+        (CollisionShape::Sphere { radius: r1 }, CollisionShape::Capsule { half_height, radius: r2 }) => {
+            sphere_vs_capsule(pos_a, *r1, pos_b, rot_b, *half_height, *r2)
+        }
+        (CollisionShape::Sphere { radius: r1 }, CollisionShape::Cylinder { half_height, radius: r2 }) => {
+            sphere_vs_cylinder(pos_a, *r1, pos_b, rot_b, *half_height, *r2)
+        }
+        (CollisionShape::Sphere { radius: r1 }, CollisionShape::Cone { half_height, radius: r2 }) => {
+            sphere_vs_cone(pos_a, *r1, pos_b, rot_b, *half_height, *r2)
+        }
+        (CollisionShape::AABB { min: min1, max: max1 }, CollisionShape::AABB { min: min2, max: max2 }) => {
+            aabb_vs_aabb(*min1 + pos_a, *max1 + pos_a, *min2 + pos_b, *max2 + pos_b)
+        }
+        (CollisionShape::AABB { min, max }, CollisionShape::Plane { normal, distance }) => aabb_vs_plane(
+            *min + pos_a,
+            *max + pos_a,
+            *normal,
+            plane_world_distance(pos_b, *normal, *distance),
+        ),
+        // An AABB is a box that never rotates
+        (CollisionShape::AABB { min, max }, CollisionShape::Box { half_extents }) => {
+            let (center, half) = aabb_as_box(pos_a, *min, *max);
+            box_vs_box(center, Quat::identity(), half, pos_b, rot_b, *half_extents)
+        }
+        (CollisionShape::Box { half_extents: half1 }, CollisionShape::Box { half_extents: half2 }) => {
+            box_vs_box(pos_a, rot_a, *half1, pos_b, rot_b, *half2)
+        }
+        (CollisionShape::Box { half_extents }, CollisionShape::Plane { normal, distance }) => box_vs_plane(
+            pos_a,
+            rot_a,
+            *half_extents,
+            *normal,
+            plane_world_distance(pos_b, *normal, *distance),
+        ),
+        (
+            CollisionShape::Capsule { half_height: h1, radius: r1 },
+            CollisionShape::Capsule { half_height: h2, radius: r2 },
+        ) => capsule_vs_capsule(pos_a, rot_a, *h1, *r1, pos_b, rot_b, *h2, *r2),
+        (CollisionShape::Capsule { half_height, radius }, CollisionShape::Plane { normal, distance }) => {
+            capsule_vs_plane(
+                pos_a,
+                rot_a,
+                *half_height,
+                *radius,
+                *normal,
+                plane_world_distance(pos_b, *normal, *distance),
+            )
+        }
+        (CollisionShape::Capsule { half_height, radius }, CollisionShape::Box { half_extents }) => {
+            capsule_vs_box(pos_a, rot_a, *half_height, *radius, pos_b, rot_b, *half_extents)
+        }
+        (CollisionShape::Capsule { half_height, radius }, CollisionShape::AABB { min, max }) => {
+            let (center, half) = aabb_as_box(pos_b, *min, *max);
+            capsule_vs_box(pos_a, rot_a, *half_height, *radius, center, Quat::identity(), half)
+        }
+        (CollisionShape::Cylinder { half_height, radius }, CollisionShape::Plane { normal, distance }) => {
+            cylinder_vs_plane(
+                pos_a,
+                rot_a,
+                *half_height,
+                *radius,
+                *normal,
+                plane_world_distance(pos_b, *normal, *distance),
+            )
+        }
+        (CollisionShape::Cone { half_height, radius }, CollisionShape::Plane { normal, distance }) => {
+            cone_vs_plane(
+                pos_a,
+                rot_a,
+                *half_height,
+                *radius,
+                *normal,
+                plane_world_distance(pos_b, *normal, *distance),
+            )
+        }
+        (CollisionShape::ConvexHull(hull), CollisionShape::Plane { normal, distance }) => {
+            let vertices: Vec<Vec3> = hull.vertices.iter().map(|v| pos_a + rot_a.rotate(*v)).collect();
+            points_vs_plane(&vertices, *normal, plane_world_distance(pos_b, *normal, *distance))
+        }
+        (CollisionShape::Sphere { radius }, CollisionShape::TriMesh(mesh)) => {
+            sphere_vs_trimesh(pos_a, *radius, pos_b, rot_b, mesh)
+        }
+        (CollisionShape::Capsule { half_height, radius }, CollisionShape::TriMesh(mesh)) => {
+            capsule_vs_trimesh(pos_a, rot_a, *half_height, *radius, pos_b, rot_b, mesh)
+        }
+        (CollisionShape::Box { half_extents }, CollisionShape::TriMesh(mesh)) => {
+            box_vs_trimesh(pos_a, rot_a, *half_extents, pos_b, rot_b, mesh)
+        }
+        (CollisionShape::AABB { min, max }, CollisionShape::TriMesh(mesh)) => {
+            let (center, half) = aabb_as_box(pos_a, *min, *max);
+            box_vs_trimesh(center, Quat::identity(), half, pos_b, rot_b, mesh)
+        }
+        (
+            CollisionShape::ConvexHull(_) | CollisionShape::Cylinder { .. } | CollisionShape::Cone { .. },
+            CollisionShape::TriMesh(mesh),
+        ) => {
+            let support = ShapeSupport::new(shape_a, pos_a, rot_a)?;
+            let vertices: Vec<Vec3> = contact_vertices(shape_a).iter().map(|v| pos_a + rot_a.rotate(*v)).collect();
+            convex_vs_trimesh(&support, &vertices, pos_b, rot_b, mesh)
+        }
+        (CollisionShape::Sphere { radius }, CollisionShape::Heightfield(terrain)) => {
+            sphere_vs_heightfield(pos_a, *radius, pos_b, rot_b, terrain)
+        }
+        (CollisionShape::Capsule { half_height, radius }, CollisionShape::Heightfield(terrain)) => {
+            capsule_vs_heightfield(pos_a, rot_a, *half_height, *radius, pos_b, rot_b, terrain)
+        }
+        (CollisionShape::Box { half_extents }, CollisionShape::Heightfield(terrain)) => {
+            box_vs_heightfield(pos_a, rot_a, *half_extents, pos_b, rot_b, terrain)
+        }
+        (CollisionShape::AABB { min, max }, CollisionShape::Heightfield(terrain)) => {
+            let (center, half) = aabb_as_box(pos_a, *min, *max);
+            box_vs_heightfield(center, Quat::identity(), half, pos_b, rot_b, terrain)
+        }
+        (
+            CollisionShape::ConvexHull(_) | CollisionShape::Cylinder { .. } | CollisionShape::Cone { .. },
+            CollisionShape::Heightfield(terrain),
+        ) => {
+            let support = ShapeSupport::new(shape_a, pos_a, rot_a)?;
+            let vertices: Vec<Vec3> = contact_vertices(shape_a).iter().map(|v| pos_a + rot_a.rotate(*v)).collect();
+            convex_vs_heightfield(&support, &vertices, pos_b, rot_b, terrain)
+        }
+        // Two half-spaces either always or never overlap, and meshes and
+        // terrain are static level geometry; there is nothing to resolve
+        (CollisionShape::Plane { .. }, CollisionShape::Plane { .. })
+        | (
+            CollisionShape::TriMesh(_) | CollisionShape::Heightfield(_),
+            CollisionShape::Plane { .. } | CollisionShape::TriMesh(_) | CollisionShape::Heightfield(_),
+        ) => {
+            return Some(None);
+        }
+        _ => return None,
+    };
+
+    Some(Some(info))
+}
+
+// Local points spread over a convex shape's surface, so face contacts against
+// meshes and terrain sit under the shape rather than at one support point
+fn contact_vertices(shape: &CollisionShape) -> Vec<Vec3> {
+    let rim = |y: f32, radius: f32| {
+        (0..8).map(move |i| {
+            let angle = i as f32 * std::f32::consts::FRAC_PI_4;
+            Vec3::new(angle.cos() * radius, y, angle.sin() * radius)
+        })
+    };
+    match shape {
+        CollisionShape::ConvexHull(hull) => hull.vertices.clone(),
+        CollisionShape::Cylinder { half_height, radius } => {
+            rim(-half_height, *radius).chain(rim(*half_height, *radius)).collect()
+        }
+        CollisionShape::Cone { half_height, radius } => {
+            rim(-half_height, *radius).chain([Vec3::new(0.0, *half_height, 0.0)]).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+// A plane's normal is in its body's frame. This is the same plane with the
+// normal rotated into world space, which is what the plane routines take.
+fn plane_in_world(shape: &CollisionShape, rotation: Quat) -> Option<CollisionShape> {
+    match shape {
+        CollisionShape::Plane { normal, distance } => {
+            Some(CollisionShape::Plane { normal: rotation.rotate(*normal), distance: *distance })
+        }
+        _ => None,
+    }
+}
+
+fn aabb_as_box(position: Vec3, min: Vec3, max: Vec3) -> (Vec3, Vec3) {
+    (position + (min + max) * 0.5, (max - min) * 0.5)
+}
+
 // Sphere vs Sphere collision
 pub fn sphere_vs_sphere(
     pos_a: Vec3,
@@ -62,30 +710,1407 @@ pub fn sphere_vs_sphere(
     }
 }
 
-// Sphere vs Plane collision - TODO: Needs implementation following research
+// Sphere vs Plane collision
+//
+// The plane is treated as a half-space: every point `p` with
+// `plane_normal.dot(p) < plane_distance` is solid. A sphere whose centre has
+// already tunnelled behind the surface is still pushed back out along the
+// plane normal instead of being dragged through it.
 pub fn sphere_vs_plane(
     sphere_pos: Vec3,
     sphere_radius: f32,
     plane_normal: Vec3,
     plane_distance: f32,
 ) -> CollisionInfo {
-    // Placeholder - returns no collision for now
-    CollisionInfo::none()
+    let plane_normal = plane_normal.normalize();
+    let signed_distance = plane_normal.dot(&sphere_pos) - plane_distance;
+
+    if signed_distance <= sphere_radius {
+        let penetration = sphere_radius - signed_distance;
+        // Normal points from the sphere towards the plane, matching sphere_vs_sphere (A -> B)
+        let normal = -plane_normal;
+        let contact_point = sphere_pos - plane_normal * signed_distance;
+
+        CollisionInfo::new(normal, penetration, contact_point)
+    } else {
+        CollisionInfo::none()
+    }
+}
+
+// Plane distance in world space for a plane shape attached to a body at `position`
+pub fn plane_world_distance(position: Vec3, plane_normal: Vec3, plane_distance: f32) -> f32 {
+    plane_distance + plane_normal.normalize().dot(&position)
 }
 
-// AABB vs AABB collision - TODO: Needs implementation following research
+// AABB vs AABB collision
+//
+// Both boxes are given in world space. The normal is the axis of minimum
+// overlap, pointing from A towards B, so pushing the boxes apart along it
+// by `penetration_depth` is the smallest move that separates them.
 pub fn aabb_vs_aabb(min_a: Vec3, max_a: Vec3, min_b: Vec3, max_b: Vec3) -> CollisionInfo {
-    // Placeholder - returns no collision for now
-    CollisionInfo::none()
+    let overlap_x = max_a.x.min(max_b.x) - min_a.x.max(min_b.x);
+    let overlap_y = max_a.y.min(max_b.y) - min_a.y.max(min_b.y);
+    let overlap_z = max_a.z.min(max_b.z) - min_a.z.max(min_b.z);
+
+    if overlap_x < 0.0 || overlap_y < 0.0 || overlap_z < 0.0 {
+        return CollisionInfo::none();
+    }
+
+    let center_a = (min_a + max_a) * 0.5;
+    let center_b = (min_b + max_b) * 0.5;
+    let delta = center_b - center_a;
+
+    let (normal, penetration) = if overlap_x <= overlap_y && overlap_x <= overlap_z {
+        let sign = if delta.x < 0.0 { -1.0 } else { 1.0 };
+        (Vec3::new(sign, 0.0, 0.0), overlap_x)
+    } else if overlap_y <= overlap_z {
+        let sign = if delta.y < 0.0 { -1.0 } else { 1.0 };
+        (Vec3::new(0.0, sign, 0.0), overlap_y)
+    } else {
+        let sign = if delta.z < 0.0 { -1.0 } else { 1.0 };
+        (Vec3::new(0.0, 0.0, sign), overlap_z)
+    };
+
+    // Centre of the overlapping region
+    let overlap_min = Vec3::new(min_a.x.max(min_b.x), min_a.y.max(min_b.y), min_a.z.max(min_b.z));
+    let overlap_max = Vec3::new(max_a.x.min(max_b.x), max_a.y.min(max_b.y), max_a.z.min(max_b.z));
+    let contact_point = (overlap_min + overlap_max) * 0.5;
+
+    CollisionInfo::new(normal, penetration, contact_point)
 }
 
-// Sphere vs AABB collision - TODO: Needs complete rewrite following research
+// Sphere vs AABB collision
+//
+// The box is given in world space. The normal points from the sphere towards
+// the box. When the sphere centre is inside the box the closest point is
+// degenerate, so the sphere is pushed out through the nearest face instead.
 pub fn sphere_vs_aabb(
     sphere_pos: Vec3,
     sphere_radius: f32,
     aabb_min: Vec3,
     aabb_max: Vec3,
 ) -> CollisionInfo {
-    // Placeholder - returns no collision for now
-    CollisionInfo::none()
+    let closest = Vec3::new(
+        sphere_pos.x.clamp(aabb_min.x, aabb_max.x),
+        sphere_pos.y.clamp(aabb_min.y, aabb_max.y),
+        sphere_pos.z.clamp(aabb_min.z, aabb_max.z),
+    );
+
+    let delta = closest - sphere_pos;
+    let distance_squared = delta.magnitude_squared();
+
+    if distance_squared > 0.0 {
+        if distance_squared > sphere_radius * sphere_radius {
+            return CollisionInfo::none();
+        }
+
+        let distance = distance_squared.sqrt();
+        let normal = delta * (1.0 / distance);
+        return CollisionInfo::new(normal, sphere_radius - distance, closest);
+    }
+
+    // Sphere centre is inside the box: find the face it is nearest to
+    let faces = [
+        (sphere_pos.x - aabb_min.x, Vec3::new(1.0, 0.0, 0.0)),
+        (aabb_max.x - sphere_pos.x, Vec3::new(-1.0, 0.0, 0.0)),
+        (sphere_pos.y - aabb_min.y, Vec3::new(0.0, 1.0, 0.0)),
+        (aabb_max.y - sphere_pos.y, Vec3::new(0.0, -1.0, 0.0)),
+        (sphere_pos.z - aabb_min.z, Vec3::new(0.0, 0.0, 1.0)),
+        (aabb_max.z - sphere_pos.z, Vec3::new(0.0, 0.0, -1.0)),
+    ];
+
+    let (face_distance, normal) = faces
+        .iter()
+        .copied()
+        .fold(faces[0], |best, face| if face.0 < best.0 { face } else { best });
+
+    let contact_point = sphere_pos - normal * face_distance;
+
+    CollisionInfo::new(normal, sphere_radius + face_distance, contact_point)
+}
+
+// AABB vs Plane collision
+//
+// The box is given in world space and the plane is a half-space as in
+// sphere_vs_plane. Every corner behind the plane is a contact; the reported
+// depth is that of the deepest corner and the contact point is the average of
+// the penetrating corners, so a box lying flat is supported at its centre.
+pub fn aabb_vs_plane(
+    aabb_min: Vec3,
+    aabb_max: Vec3,
+    plane_normal: Vec3,
+    plane_distance: f32,
+) -> CollisionInfo {
+    let center = (aabb_min + aabb_max) * 0.5;
+    let half_extents = (aabb_max - aabb_min) * 0.5;
+    let corners = OrientedBox::new(center, Quat::identity(), half_extents).corners();
+    points_vs_plane(&corners, plane_normal, plane_distance)
+}
+
+// Box vs Plane collision, with the same contact rules as aabb_vs_plane
+pub fn box_vs_plane(
+    box_center: Vec3,
+    box_rotation: Quat,
+    half_extents: Vec3,
+    plane_normal: Vec3,
+    plane_distance: f32,
+) -> CollisionInfo {
+    let corners = OrientedBox::new(box_center, box_rotation, half_extents).corners();
+    points_vs_plane(&corners, plane_normal, plane_distance)
+}
+
+// Box vs Plane collision as a manifold: one point per corner behind the
+// plane, at most four of them. The normal points from the box to the plane.
+pub fn box_vs_plane_manifold(
+    box_center: Vec3,
+    box_rotation: Quat,
+    half_extents: Vec3,
+    plane_normal: Vec3,
+    plane_distance: f32,
+) -> Option<ContactManifold> {
+    let corners = OrientedBox::new(box_center, box_rotation, half_extents).corners();
+    let plane_normal = plane_normal.normalize();
+    let points = points_behind_plane(&corners, plane_normal, plane_distance);
+    if points.is_empty() {
+        return None;
+    }
+    Some(ContactManifold { normal: -plane_normal, points: reduce_manifold(points, plane_normal) })
+}
+
+fn points_vs_plane(points: &[Vec3], plane_normal: Vec3, plane_distance: f32) -> CollisionInfo {
+    let plane_normal = plane_normal.normalize();
+    let contacts = points_behind_plane(points, plane_normal, plane_distance);
+    ContactManifold { normal: -plane_normal, points: contacts }.to_info()
+}
+
+// Points on or behind a plane, projected onto its surface, with their depths;
+// each point's id is its index in `points`
+fn points_behind_plane(points: &[Vec3], plane_normal: Vec3, plane_distance: f32) -> Vec<ContactPoint> {
+    points
+        .iter()
+        .enumerate()
+        .filter_map(|(index, point)| {
+            let signed_distance = plane_normal.dot(point) - plane_distance;
+            (signed_distance <= 0.0).then(|| ContactPoint {
+                point: *point - plane_normal * signed_distance,
+                depth: -signed_distance,
+                id: index as u32,
+            })
+        })
+        .collect()
+}
+
+// Keeps at most MAX_MANIFOLD_POINTS contacts that cover the contact area:
+// the deepest point, the point furthest from it, and then the two points
+// spanning the largest triangles with those on either side of their line
+fn reduce_manifold(points: Vec<ContactPoint>, normal: Vec3) -> Vec<ContactPoint> {
+    if points.len() <= MAX_MANIFOLD_POINTS {
+        return points;
+    }
+    let pick = |score: &dyn Fn(&ContactPoint) -> f32| {
+        (0..points.len()).max_by(|&i, &j| score(&points[i]).total_cmp(&score(&points[j]))).unwrap()
+    };
+
+    let first = pick(&|contact| contact.depth);
+    let a = points[first].point;
+    let second = pick(&|contact| (contact.point - a).magnitude_squared());
+    let b = points[second].point;
+    let signed_area = |contact: &ContactPoint| (a - contact.point).cross(&(b - contact.point)).dot(&normal);
+    let third = pick(&signed_area);
+    let fourth = pick(&|contact| -signed_area(contact));
+
+    let mut chosen = vec![first];
+    for index in [second, third, fourth] {
+        if !chosen.contains(&index) {
+            chosen.push(index);
+        }
+    }
+    chosen.into_iter().map(|index| points[index]).collect()
+}
+
+// Sphere vs Box collision
+//
+// The sphere is moved into the box's local frame, where the box is an AABB
+// centred on the origin, and the result is rotated back into world space.
+pub fn sphere_vs_box(
+    sphere_pos: Vec3,
+    sphere_radius: f32,
+    box_center: Vec3,
+    box_rotation: Quat,
+    half_extents: Vec3,
+) -> CollisionInfo {
+    let local_sphere = box_rotation.inverse().rotate(sphere_pos - box_center);
+    let info = sphere_vs_aabb(local_sphere, sphere_radius, -half_extents, half_extents);
+
+    if !info.is_colliding {
+        return info;
+    }
+
+    CollisionInfo::new(
+        box_rotation.rotate(info.normal),
+        info.penetration_depth,
+        box_center + box_rotation.rotate(info.contact_point),
+    )
+}
+
+// Box vs Box collision using the Separating Axis Theorem
+//
+// Two convex boxes are disjoint exactly when some axis separates their
+// projections. For boxes the candidates are the 3 face normals of each box
+// plus the 9 cross products of their edge directions. The axis with the
+// smallest overlap becomes the collision normal, pointing from A to B.
+pub fn box_vs_box(
+    center_a: Vec3,
+    rotation_a: Quat,
+    half_extents_a: Vec3,
+    center_b: Vec3,
+    rotation_b: Quat,
+    half_extents_b: Vec3,
+) -> CollisionInfo {
+    let box_a = OrientedBox::new(center_a, rotation_a, half_extents_a);
+    let box_b = OrientedBox::new(center_b, rotation_b, half_extents_b);
+    let Some((normal, overlap, feature)) = box_separating_axis(&box_a, &box_b) else {
+        return CollisionInfo::none();
+    };
+
+    let contact_point = match feature {
+        SatFeature::FaceA => face_contact_point(&box_a, &box_b, normal),
+        SatFeature::FaceB => face_contact_point(&box_b, &box_a, -normal),
+        SatFeature::Edge(i, j) => edge_contact_point(&box_a, &box_b, i, j, normal),
+    };
+
+    CollisionInfo::new(normal, overlap, contact_point)
+}
+
+// Box vs Box collision as a manifold. The SAT axis is the same as in
+// box_vs_box; for a face axis the incident box's most anti-parallel face is
+// clipped against the side planes of the reference face, and every clipped
+// vertex behind the reference face becomes a point. Edge-edge contacts have a
+// single point.
+pub fn box_vs_box_manifold(
+    center_a: Vec3,
+    rotation_a: Quat,
+    half_extents_a: Vec3,
+    center_b: Vec3,
+    rotation_b: Quat,
+    half_extents_b: Vec3,
+) -> Option<ContactManifold> {
+    let box_a = OrientedBox::new(center_a, rotation_a, half_extents_a);
+    let box_b = OrientedBox::new(center_b, rotation_b, half_extents_b);
+    let (normal, overlap, feature) = box_separating_axis(&box_a, &box_b)?;
+
+    let points = match feature {
+        SatFeature::FaceA => clip_box_faces(&box_a, &box_b, normal, 0),
+        SatFeature::FaceB => clip_box_faces(&box_b, &box_a, -normal, 1),
+        SatFeature::Edge(..) => Vec::new(),
+    };
+    let points = if points.is_empty() {
+        let (point, id) = match feature {
+            SatFeature::FaceA => (face_contact_point(&box_a, &box_b, normal), BOX_FALLBACK_ID),
+            SatFeature::FaceB => (face_contact_point(&box_b, &box_a, -normal), BOX_FALLBACK_ID + 1),
+            SatFeature::Edge(i, j) => {
+                (edge_contact_point(&box_a, &box_b, i, j, normal), BOX_EDGE_ID + (i * 3 + j) as u32)
+            }
+        };
+        vec![ContactPoint { point, depth: overlap, id }]
+    } else {
+        reduce_manifold(points, normal)
+    };
+    Some(ContactManifold { normal, points })
+}
+
+// The SAT axis of smallest overlap, pointing from A to B, with that overlap
+// and the features it came from, or None when the boxes are apart
+fn box_separating_axis(box_a: &OrientedBox, box_b: &OrientedBox) -> Option<(Vec3, f32, SatFeature)> {
+    let delta = box_b.center - box_a.center;
+
+    // Face axes are preferred over edge axes of nearly the same depth, which
+    // keeps resting contacts from flickering onto an edge-edge normal
+    const EDGE_AXIS_TOLERANCE: f32 = 1.0e-3;
+
+    let mut best_overlap = f32::INFINITY;
+    let mut best_axis = Vec3::zero();
+    let mut best_feature = SatFeature::FaceA;
+
+    let mut candidates: Vec<(Vec3, SatFeature)> = Vec::with_capacity(15);
+    for i in 0..3 {
+        candidates.push((box_a.axes[i], SatFeature::FaceA));
+    }
+    for i in 0..3 {
+        candidates.push((box_b.axes[i], SatFeature::FaceB));
+    }
+    for i in 0..3 {
+        for j in 0..3 {
+            candidates.push((box_a.axes[i].cross(&box_b.axes[j]), SatFeature::Edge(i, j)));
+        }
+    }
+
+    for (axis, feature) in candidates {
+        // Cross products of (nearly) parallel edges carry no information
+        let length = axis.magnitude();
+        if length < 1.0e-6 {
+            continue;
+        }
+        let axis = axis * (1.0 / length);
+
+        let distance = axis.dot(&delta).abs();
+        let overlap = box_a.project_radius(axis) + box_b.project_radius(axis) - distance;
+
+        if overlap < 0.0 {
+            return None;
+        }
+
+        let is_edge = matches!(feature, SatFeature::Edge(..));
+        let better = if is_edge {
+            overlap + EDGE_AXIS_TOLERANCE < best_overlap
+        } else {
+            overlap < best_overlap
+        };
+
+        if better {
+            best_overlap = overlap;
+            best_axis = axis;
+            best_feature = feature;
+        }
+    }
+
+    // Orient the normal from A towards B
+    let normal = if best_axis.dot(&delta) < 0.0 { -best_axis } else { best_axis };
+    Some((normal, best_overlap, best_feature))
+}
+
+// Sphere vs Cylinder collision
+//
+// Both shapes are symmetric about the cylinder's axis, so the test happens in
+// the 2D (radial distance, height) half-plane through the sphere's centre,
+// where the cylinder is the rectangle [0, radius] x [-half_height, half_height]
+pub fn sphere_vs_cylinder(
+    sphere_pos: Vec3,
+    sphere_radius: f32,
+    cylinder_center: Vec3,
+    cylinder_rotation: Quat,
+    half_height: f32,
+    cylinder_radius: f32,
+) -> CollisionInfo {
+    let local = cylinder_rotation.inverse().rotate(sphere_pos - cylinder_center);
+    let (rho, height) = (Vec3::new(local.x, 0.0, local.z).magnitude(), local.y);
+
+    let closest = (rho.min(cylinder_radius), height.clamp(-half_height, half_height));
+    let info = if (rho, height) != closest {
+        profile_contact((rho, height), sphere_radius, closest)
+    } else {
+        // Centre inside: leave through the nearest of the side and the caps
+        let exits = [
+            (cylinder_radius - rho, (1.0, 0.0)),
+            (half_height - height, (0.0, 1.0)),
+            (height + half_height, (0.0, -1.0)),
+        ];
+        let (distance, outward) = exits
+            .into_iter()
+            .fold(exits[0], |best, exit| if exit.0 < best.0 { exit } else { best });
+        ProfileContact {
+            normal: (-outward.0, -outward.1),
+            depth: sphere_radius + distance,
+            point: (rho + outward.0 * distance, height + outward.1 * distance),
+        }
+    };
+    info.to_world(local, cylinder_center, cylinder_rotation)
+}
+
+// Sphere vs Cone collision, in the same 2D half-plane as sphere_vs_cylinder;
+// there the cone is the triangle (0, -h), (radius, -h), (0, h)
+pub fn sphere_vs_cone(
+    sphere_pos: Vec3,
+    sphere_radius: f32,
+    cone_center: Vec3,
+    cone_rotation: Quat,
+    half_height: f32,
+    cone_radius: f32,
+) -> CollisionInfo {
+    let local = cone_rotation.inverse().rotate(sphere_pos - cone_center);
+    let (rho, height) = (Vec3::new(local.x, 0.0, local.z).magnitude(), local.y);
+
+    let base_axis = Vec3::new(0.0, -half_height, 0.0);
+    let base_rim = Vec3::new(cone_radius, -half_height, 0.0);
+    let apex = Vec3::new(0.0, half_height, 0.0);
+    let point = Vec3::new(rho, height, 0.0);
+    let closest = closest_point_on_triangle(point, base_axis, base_rim, apex);
+
+    let info = if (closest - point).magnitude_squared() > 1.0e-12 {
+        profile_contact((rho, height), sphere_radius, (closest.x, closest.y))
+    } else {
+        // Centre inside: leave through the nearer of the base and the slant
+        let slant = Vec3::new(2.0 * half_height, cone_radius, 0.0).normalize();
+        let to_slant = slant.dot(&(base_rim - point));
+        let to_base = height + half_height;
+        let (distance, outward) = if to_base < to_slant { (to_base, (0.0, -1.0)) } else { (to_slant, (slant.x, slant.y)) };
+        ProfileContact {
+            normal: (-outward.0, -outward.1),
+            depth: sphere_radius + distance,
+            point: (rho + outward.0 * distance, height + outward.1 * distance),
+        }
+    };
+    info.to_world(local, cone_center, cone_rotation)
+}
+
+// A contact found in the (radial distance, height) half-plane of a solid of
+// revolution. The normal points from the sphere towards the solid.
+struct ProfileContact {
+    normal: (f32, f32),
+    depth: f32,
+    point: (f32, f32),
+}
+
+impl ProfileContact {
+    // Maps back into the 3D half-plane containing the sphere's local centre
+    fn to_world(&self, local_sphere: Vec3, center: Vec3, rotation: Quat) -> CollisionInfo {
+        if self.depth < 0.0 {
+            return CollisionInfo::none();
+        }
+        let radial = {
+            let flat = radial_unit(local_sphere);
+            if flat.magnitude_squared() == 0.0 { Vec3::new(1.0, 0.0, 0.0) } else { flat }
+        };
+        let up = Vec3::new(0.0, 1.0, 0.0);
+        let normal = radial * self.normal.0 + up * self.normal.1;
+        let point = radial * self.point.0 + up * self.point.1;
+        CollisionInfo::new(rotation.rotate(normal), self.depth, center + rotation.rotate(point))
+    }
+}
+
+// Contact for a sphere centre outside the profile, given the closest profile point
+fn profile_contact(center: (f32, f32), sphere_radius: f32, closest: (f32, f32)) -> ProfileContact {
+    let delta = (closest.0 - center.0, closest.1 - center.1);
+    let distance = (delta.0 * delta.0 + delta.1 * delta.1).sqrt();
+    ProfileContact {
+        normal: (delta.0 / distance, delta.1 / distance),
+        depth: sphere_radius - distance,
+        point: closest,
+    }
+}
+
+// Cylinder vs Plane collision. Each cap contributes its lowest rim point and
+// the three rim points a quarter turn apart from it, so a cylinder standing
+// on a cap or lying on its side is supported under its middle, like a box's
+// corners in box_vs_plane.
+pub fn cylinder_vs_plane(
+    cylinder_center: Vec3,
+    cylinder_rotation: Quat,
+    half_height: f32,
+    cylinder_radius: f32,
+    plane_normal: Vec3,
+    plane_distance: f32,
+) -> CollisionInfo {
+    let plane_normal = plane_normal.normalize();
+    let axis = cylinder_rotation.rotate(Vec3::new(0.0, half_height, 0.0));
+    let bottom = rim_towards(cylinder_center - axis, axis, cylinder_radius, -plane_normal);
+    let top = rim_towards(cylinder_center + axis, axis, cylinder_radius, -plane_normal);
+    let points: Vec<Vec3> = bottom.into_iter().chain(top).collect();
+    points_vs_plane(&points, plane_normal, plane_distance)
+}
+
+// Cone vs Plane collision: the apex plus rim points as in cylinder_vs_plane
+pub fn cone_vs_plane(
+    cone_center: Vec3,
+    cone_rotation: Quat,
+    half_height: f32,
+    cone_radius: f32,
+    plane_normal: Vec3,
+    plane_distance: f32,
+) -> CollisionInfo {
+    let plane_normal = plane_normal.normalize();
+    let axis = cone_rotation.rotate(Vec3::new(0.0, half_height, 0.0));
+    let rim = rim_towards(cone_center - axis, axis, cone_radius, -plane_normal);
+    let points: Vec<Vec3> = rim.into_iter().chain([cone_center + axis]).collect();
+    points_vs_plane(&points, plane_normal, plane_distance)
+}
+
+// Four points a quarter turn apart on a circle around `axis`, starting with
+// the one furthest along `direction`
+fn rim_towards(center: Vec3, axis: Vec3, radius: f32, direction: Vec3) -> [Vec3; 4] {
+    let axis = axis.normalize();
+    let mut u = direction - axis * direction.dot(&axis);
+    if u.magnitude_squared() < 1.0e-12 {
+        // Circle parallel to the direction's plane: any spoke will do
+        u = axis.cross(&Vec3::new(1.0, 0.0, 0.0));
+        if u.magnitude_squared() < 1.0e-12 {
+            u = axis.cross(&Vec3::new(0.0, 0.0, 1.0));
+        }
+    }
+    let u = u.normalize() * radius;
+    let v = axis.cross(&u);
+    [center + u, center + v, center - u, center - v]
+}
+
+// World-space end points of a capsule's core segment
+pub fn capsule_segment(center: Vec3, rotation: Quat, half_height: f32) -> (Vec3, Vec3) {
+    let axis = rotation.rotate(Vec3::new(0.0, half_height, 0.0));
+    (center - axis, center + axis)
+}
+
+// Sphere vs Capsule collision: a sphere against the closest point on the
+// capsule's core segment
+pub fn sphere_vs_capsule(
+    sphere_pos: Vec3,
+    sphere_radius: f32,
+    capsule_center: Vec3,
+    capsule_rotation: Quat,
+    half_height: f32,
+    capsule_radius: f32,
+) -> CollisionInfo {
+    let (p0, p1) = capsule_segment(capsule_center, capsule_rotation, half_height);
+    let closest = closest_point_on_segment(sphere_pos, p0, p1);
+    sphere_vs_sphere(sphere_pos, sphere_radius, closest, capsule_radius)
+}
+
+// Capsule vs Capsule collision: two spheres placed at the closest points of
+// the core segments
+#[allow(clippy::too_many_arguments)]
+pub fn capsule_vs_capsule(
+    center_a: Vec3,
+    rotation_a: Quat,
+    half_height_a: f32,
+    radius_a: f32,
+    center_b: Vec3,
+    rotation_b: Quat,
+    half_height_b: f32,
+    radius_b: f32,
+) -> CollisionInfo {
+    let (a0, a1) = capsule_segment(center_a, rotation_a, half_height_a);
+    let (b0, b1) = capsule_segment(center_b, rotation_b, half_height_b);
+    let (on_a, on_b) = closest_points_segment_segment(a0, a1, b0, b1);
+    sphere_vs_sphere(on_a, radius_a, on_b, radius_b)
+}
+
+// Capsule vs Plane collision: each end of the core segment is a sphere, and
+// both ends touching (a capsule lying flat) are averaged into one contact
+pub fn capsule_vs_plane(
+    capsule_center: Vec3,
+    capsule_rotation: Quat,
+    half_height: f32,
+    capsule_radius: f32,
+    plane_normal: Vec3,
+    plane_distance: f32,
+) -> CollisionInfo {
+    let (p0, p1) = capsule_segment(capsule_center, capsule_rotation, half_height);
+    let end_0 = sphere_vs_plane(p0, capsule_radius, plane_normal, plane_distance);
+    let end_1 = sphere_vs_plane(p1, capsule_radius, plane_normal, plane_distance);
+
+    match (end_0.is_colliding, end_1.is_colliding) {
+        (true, true) => CollisionInfo::new(
+            end_0.normal,
+            end_0.penetration_depth.max(end_1.penetration_depth),
+            (end_0.contact_point + end_1.contact_point) * 0.5,
+        ),
+        (true, false) => end_0,
+        _ => end_1,
+    }
+}
+
+// Capsule vs Box collision: the deepest (or closest) point of the core
+// segment is treated as a sphere against the box
+pub fn capsule_vs_box(
+    capsule_center: Vec3,
+    capsule_rotation: Quat,
+    half_height: f32,
+    capsule_radius: f32,
+    box_center: Vec3,
+    box_rotation: Quat,
+    half_extents: Vec3,
+) -> CollisionInfo {
+    let (p0, p1) = capsule_segment(capsule_center, capsule_rotation, half_height);
+    let to_local = box_rotation.inverse();
+    let local_0 = to_local.rotate(p0 - box_center);
+    let local_1 = to_local.rotate(p1 - box_center);
+
+    let local = closest_point_on_segment_to(local_0, local_1, |point| box_signed_distance(point, half_extents));
+    let closest = box_center + box_rotation.rotate(local);
+    sphere_vs_box(closest, capsule_radius, box_center, box_rotation, half_extents)
+}
+
+// Sphere vs Triangle collision, treating every edge as a real edge
+pub fn sphere_vs_triangle(sphere_pos: Vec3, sphere_radius: f32, a: Vec3, b: Vec3, c: Vec3) -> CollisionInfo {
+    sphere_vs_triangle_edges(sphere_pos, sphere_radius, [a, b, c], [true; 3])
+}
+
+// Capsule vs Triangle collision, treating every edge as a real edge
+pub fn capsule_vs_triangle(
+    capsule_center: Vec3,
+    capsule_rotation: Quat,
+    half_height: f32,
+    capsule_radius: f32,
+    triangle: [Vec3; 3],
+) -> CollisionInfo {
+    let (p0, p1) = capsule_segment(capsule_center, capsule_rotation, half_height);
+    capsule_vs_triangle_edges(p0, p1, capsule_radius, triangle, [true; 3])
+}
+
+// Box vs Triangle collision, treating every edge as a real edge
+pub fn box_vs_triangle(
+    box_center: Vec3,
+    box_rotation: Quat,
+    half_extents: Vec3,
+    triangle: [Vec3; 3],
+) -> CollisionInfo {
+    let shape = CollisionShape::Box { half_extents };
+    let support = ShapeSupport { shape: &shape, position: box_center, rotation: box_rotation };
+    let corners = OrientedBox::new(box_center, box_rotation, half_extents).corners();
+    let mut contacts = Vec::new();
+    convex_vs_triangle_edges(&support, &corners, triangle, [true; 3], &mut contacts);
+    merge_contacts(&contacts)
+}
+
+// Sphere vs TriMesh collision
+pub fn sphere_vs_trimesh(
+    sphere_pos: Vec3,
+    sphere_radius: f32,
+    mesh_position: Vec3,
+    mesh_rotation: Quat,
+    mesh: &TriMesh,
+) -> CollisionInfo {
+    sphere_vs_triangles(sphere_pos, sphere_radius, mesh_position, mesh_rotation, mesh)
+}
+
+// Capsule vs TriMesh collision
+pub fn capsule_vs_trimesh(
+    capsule_center: Vec3,
+    capsule_rotation: Quat,
+    half_height: f32,
+    capsule_radius: f32,
+    mesh_position: Vec3,
+    mesh_rotation: Quat,
+    mesh: &TriMesh,
+) -> CollisionInfo {
+    let (p0, p1) = capsule_segment(capsule_center, capsule_rotation, half_height);
+    capsule_vs_triangles(p0, p1, capsule_radius, mesh_position, mesh_rotation, mesh)
+}
+
+// Box vs TriMesh collision
+pub fn box_vs_trimesh(
+    box_center: Vec3,
+    box_rotation: Quat,
+    half_extents: Vec3,
+    mesh_position: Vec3,
+    mesh_rotation: Quat,
+    mesh: &TriMesh,
+) -> CollisionInfo {
+    let shape = CollisionShape::Box { half_extents };
+    let support = ShapeSupport { shape: &shape, position: box_center, rotation: box_rotation };
+    let corners = OrientedBox::new(box_center, box_rotation, half_extents).corners();
+    convex_vs_triangles(&support, &corners, mesh_position, mesh_rotation, mesh)
+}
+
+// Any convex shape vs TriMesh collision. `vertices` are the shape's world-space
+// corners, used to spread face contacts over everything resting on the face;
+// round shapes can pass none.
+pub fn convex_vs_trimesh(
+    shape: &dyn SupportMap,
+    vertices: &[Vec3],
+    mesh_position: Vec3,
+    mesh_rotation: Quat,
+    mesh: &TriMesh,
+) -> CollisionInfo {
+    convex_vs_triangles(shape, vertices, mesh_position, mesh_rotation, mesh)
+}
+
+// Sphere vs Heightfield collision
+pub fn sphere_vs_heightfield(
+    sphere_pos: Vec3,
+    sphere_radius: f32,
+    terrain_position: Vec3,
+    terrain_rotation: Quat,
+    terrain: &Heightfield,
+) -> CollisionInfo {
+    sphere_vs_triangles(sphere_pos, sphere_radius, terrain_position, terrain_rotation, terrain)
+}
+
+// Capsule vs Heightfield collision
+pub fn capsule_vs_heightfield(
+    capsule_center: Vec3,
+    capsule_rotation: Quat,
+    half_height: f32,
+    capsule_radius: f32,
+    terrain_position: Vec3,
+    terrain_rotation: Quat,
+    terrain: &Heightfield,
+) -> CollisionInfo {
+    let (p0, p1) = capsule_segment(capsule_center, capsule_rotation, half_height);
+    capsule_vs_triangles(p0, p1, capsule_radius, terrain_position, terrain_rotation, terrain)
+}
+
+// Box vs Heightfield collision
+pub fn box_vs_heightfield(
+    box_center: Vec3,
+    box_rotation: Quat,
+    half_extents: Vec3,
+    terrain_position: Vec3,
+    terrain_rotation: Quat,
+    terrain: &Heightfield,
+) -> CollisionInfo {
+    let shape = CollisionShape::Box { half_extents };
+    let support = ShapeSupport { shape: &shape, position: box_center, rotation: box_rotation };
+    let corners = OrientedBox::new(box_center, box_rotation, half_extents).corners();
+    convex_vs_triangles(&support, &corners, terrain_position, terrain_rotation, terrain)
+}
+
+// Any convex shape vs Heightfield collision, as convex_vs_trimesh
+pub fn convex_vs_heightfield(
+    shape: &dyn SupportMap,
+    vertices: &[Vec3],
+    terrain_position: Vec3,
+    terrain_rotation: Quat,
+    terrain: &Heightfield,
+) -> CollisionInfo {
+    convex_vs_triangles(shape, vertices, terrain_position, terrain_rotation, terrain)
+}
+
+// World-space raycast against a heightfield placed at `terrain_position`
+pub fn raycast_heightfield(
+    origin: Vec3,
+    direction: Vec3,
+    max_distance: f32,
+    terrain_position: Vec3,
+    terrain_rotation: Quat,
+    terrain: &Heightfield,
+) -> Option<RayHit> {
+    let to_local = terrain_rotation.inverse();
+    let hit = terrain.raycast(
+        to_local.rotate(origin - terrain_position),
+        to_local.rotate(direction),
+        max_distance,
+    )?;
+    Some(RayHit {
+        distance: hit.distance,
+        point: terrain_position + terrain_rotation.rotate(hit.point),
+        normal: terrain_rotation.rotate(hit.normal),
+    })
+}
+
+fn sphere_vs_triangles(
+    sphere_pos: Vec3,
+    sphere_radius: f32,
+    mesh_position: Vec3,
+    mesh_rotation: Quat,
+    mesh: &dyn TriangleSource,
+) -> CollisionInfo {
+    let extent = Vec3::new(sphere_radius, sphere_radius, sphere_radius);
+    let (min, max) = (sphere_pos - extent, sphere_pos + extent);
+    triangle_contacts(min, max, mesh_position, mesh_rotation, mesh, |triangle, active, contacts| {
+        let info = sphere_vs_triangle_edges(sphere_pos, sphere_radius, triangle, active);
+        if info.is_colliding {
+            contacts.push(info);
+        }
+    })
+}
+
+fn capsule_vs_triangles(
+    p0: Vec3,
+    p1: Vec3,
+    capsule_radius: f32,
+    mesh_position: Vec3,
+    mesh_rotation: Quat,
+    mesh: &dyn TriangleSource,
+) -> CollisionInfo {
+    let extent = Vec3::new(capsule_radius, capsule_radius, capsule_radius);
+    let min = Vec3::new(p0.x.min(p1.x), p0.y.min(p1.y), p0.z.min(p1.z)) - extent;
+    let max = Vec3::new(p0.x.max(p1.x), p0.y.max(p1.y), p0.z.max(p1.z)) + extent;
+    triangle_contacts(min, max, mesh_position, mesh_rotation, mesh, |triangle, active, contacts| {
+        let info = capsule_vs_triangle_edges(p0, p1, capsule_radius, triangle, active);
+        if info.is_colliding {
+            contacts.push(info);
+        }
+    })
+}
+
+fn convex_vs_triangles(
+    shape: &dyn SupportMap,
+    vertices: &[Vec3],
+    mesh_position: Vec3,
+    mesh_rotation: Quat,
+    mesh: &dyn TriangleSource,
+) -> CollisionInfo {
+    let (min, max) = support_bounds(shape);
+    triangle_contacts(min, max, mesh_position, mesh_rotation, mesh, |triangle, active, contacts| {
+        convex_vs_triangle_edges(shape, vertices, triangle, active, contacts);
+    })
+}
+
+// Runs `per_triangle` on every triangle of the static geometry (in world
+// space) near the world-space box and merges the contacts it produces
+fn triangle_contacts(
+    min: Vec3,
+    max: Vec3,
+    mesh_position: Vec3,
+    mesh_rotation: Quat,
+    mesh: &dyn TriangleSource,
+    mut per_triangle: impl FnMut([Vec3; 3], [bool; 3], &mut Vec<CollisionInfo>),
+) -> CollisionInfo {
+    // Bounds of the query box in the mesh's frame
+    let to_local = mesh_rotation.inverse();
+    let center = to_local.rotate((min + max) * 0.5 - mesh_position);
+    let half = (max - min) * 0.5;
+    let rotation = Mat3::from_quat(to_local);
+    let row_extent = |row: Vec3| row.x.abs() * half.x + row.y.abs() * half.y + row.z.abs() * half.z;
+    let local_extent = Vec3::new(
+        row_extent(rotation.rows[0]),
+        row_extent(rotation.rows[1]),
+        row_extent(rotation.rows[2]),
+    );
+
+    let mut contacts = Vec::new();
+    for (triangle, active) in mesh.triangles_near(center - local_extent, center + local_extent) {
+        let triangle = triangle.map(|v| mesh_position + mesh_rotation.rotate(v));
+        per_triangle(triangle, active, &mut contacts);
+    }
+    merge_contacts(&contacts)
+}
+
+// Combines the per-triangle contacts of one shape into a single contact: the
+// deepest one's normal and depth, at the average of every contact point
+// pushing the same way
+fn merge_contacts(contacts: &[CollisionInfo]) -> CollisionInfo {
+    let Some(deepest) = contacts
+        .iter()
+        .max_by(|a, b| a.penetration_depth.total_cmp(&b.penetration_depth))
+    else {
+        return CollisionInfo::none();
+    };
+
+    let mut points: Vec<Vec3> = Vec::new();
+    for contact in contacts {
+        if contact.normal.dot(&deepest.normal) < 0.99 {
+            continue;
+        }
+        // Points on shared edges are reported by both triangles
+        if points.iter().all(|p| (*p - contact.contact_point).magnitude_squared() > 1.0e-10) {
+            points.push(contact.contact_point);
+        }
+    }
+    let average = points.iter().fold(Vec3::zero(), |sum, p| sum + *p) * (1.0 / points.len() as f32);
+
+    CollisionInfo::new(deepest.normal, deepest.penetration_depth, average)
+}
+
+// Which part of a triangle a closest point landed on
+#[derive(Debug, Clone, Copy)]
+enum TriangleFeature {
+    Face,
+    // Edge k runs from vertex k to vertex k + 1
+    Edge(usize),
+    Vertex(usize),
+}
+
+impl TriangleFeature {
+    // Contacts on an inactive edge, or on a vertex whose edges are both
+    // inactive, belong to a neighbouring face and are handled as face contacts
+    fn is_internal(self, active: [bool; 3]) -> bool {
+        match self {
+            TriangleFeature::Face => false,
+            TriangleFeature::Edge(k) => !active[k],
+            TriangleFeature::Vertex(i) => !active[i] && !active[(i + 2) % 3],
+        }
+    }
+}
+
+// Unit normal of the triangle's plane, facing the side `point` is on
+fn triangle_normal_towards(triangle: [Vec3; 3], point: Vec3) -> Option<Vec3> {
+    let [a, b, c] = triangle;
+    let normal = (b - a).cross(&(c - a));
+    if normal.magnitude_squared() < 1.0e-12 {
+        return None;
+    }
+    let normal = normal.normalize();
+    Some(if normal.dot(&(point - a)) < 0.0 { -normal } else { normal })
+}
+
+fn sphere_vs_triangle_edges(center: Vec3, radius: f32, triangle: [Vec3; 3], active: [bool; 3]) -> CollisionInfo {
+    let Some(face_normal) = triangle_normal_towards(triangle, center) else {
+        return CollisionInfo::none();
+    };
+    let (closest, feature) = closest_point_on_triangle_feature(center, triangle);
+    let delta = center - closest;
+    let distance = delta.magnitude();
+    if distance > radius {
+        return CollisionInfo::none();
+    }
+
+    if matches!(feature, TriangleFeature::Face) || feature.is_internal(active) || distance < 1.0e-6 {
+        // Measure against the face's plane instead
+        let height = face_normal.dot(&(center - triangle[0]));
+        if height > radius {
+            return CollisionInfo::none();
+        }
+        return CollisionInfo::new(-face_normal, radius - height, center - face_normal * height);
+    }
+
+    CollisionInfo::new(-(delta * (1.0 / distance)), radius - distance, closest)
+}
+
+// The capsule is a sphere at the point of its core segment closest to the
+// triangle, found as in capsule_vs_box
+fn capsule_vs_triangle_edges(p0: Vec3, p1: Vec3, radius: f32, triangle: [Vec3; 3], active: [bool; 3]) -> CollisionInfo {
+    let closest = closest_point_on_segment_to(p0, p1, |point| {
+        (point - closest_point_on_triangle(point, triangle[0], triangle[1], triangle[2])).magnitude()
+    });
+    sphere_vs_triangle_edges(closest, radius, triangle, active)
+}
+
+// Convex shape vs triangle through GJK/EPA. Face contacts report every shape
+// vertex resting on the triangle so the merged contact sits under the shape
+// rather than at one corner.
+fn convex_vs_triangle_edges(
+    shape: &dyn SupportMap,
+    vertices: &[Vec3],
+    triangle: [Vec3; 3],
+    active: [bool; 3],
+    contacts: &mut Vec<CollisionInfo>,
+) {
+    let Some(face_normal) = triangle_normal_towards(triangle, shape.center()) else {
+        return;
+    };
+    let info = gjk_epa(shape, &TriangleSupport(triangle));
+    if !info.is_colliding {
+        return;
+    }
+
+    let (_, feature) = closest_point_on_triangle_feature(info.contact_point, triangle);
+    let face_contact = info.normal.dot(&face_normal) < -0.999;
+    if !face_contact && !feature.is_internal(active) {
+        contacts.push(info);
+        return;
+    }
+
+    let [a, b, c] = triangle;
+    let mut found = false;
+    for vertex in vertices {
+        let height = face_normal.dot(&(*vertex - a));
+        if height > 0.0 {
+            continue;
+        }
+        let projected = *vertex - face_normal * height;
+        if (closest_point_on_triangle(projected, a, b, c) - projected).magnitude_squared() < 1.0e-8 {
+            contacts.push(CollisionInfo::new(-face_normal, -height, projected));
+            found = true;
+        }
+    }
+    if found {
+        return;
+    }
+
+    // Depth along the face normal from the shape's deepest point
+    let deepest = shape.support(-face_normal);
+    let depth = face_normal.dot(&(a - deepest));
+    if depth > 0.0 {
+        contacts.push(CollisionInfo::new(-face_normal, depth, deepest + face_normal * depth));
+    }
+}
+
+// A triangle as a (flat) convex shape for GJK/EPA
+struct TriangleSupport([Vec3; 3]);
+
+impl SupportMap for TriangleSupport {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let [a, b, c] = self.0;
+        let (da, db, dc) = (a.dot(&direction), b.dot(&direction), c.dot(&direction));
+        if da >= db && da >= dc {
+            a
+        } else if db >= dc {
+            b
+        } else {
+            c
+        }
+    }
+
+    fn center(&self) -> Vec3 {
+        (self.0[0] + self.0[1] + self.0[2]) * (1.0 / 3.0)
+    }
+}
+
+// World-space bounds of a convex shape from its supports along the axes
+fn support_bounds(shape: &dyn SupportMap) -> (Vec3, Vec3) {
+    let x = Vec3::new(1.0, 0.0, 0.0);
+    let y = Vec3::new(0.0, 1.0, 0.0);
+    let z = Vec3::new(0.0, 0.0, 1.0);
+    (
+        Vec3::new(shape.support(-x).x, shape.support(-y).y, shape.support(-z).z),
+        Vec3::new(shape.support(x).x, shape.support(y).y, shape.support(z).z),
+    )
+}
+
+// Distance along a unit-length ray to a triangle, hit from either side
+// (Moller-Trumbore)
+pub fn ray_vs_triangle(origin: Vec3, direction: Vec3, a: Vec3, b: Vec3, c: Vec3) -> Option<f32> {
+    let edge_1 = b - a;
+    let edge_2 = c - a;
+    let p = direction.cross(&edge_2);
+    let det = edge_1.dot(&p);
+    if det.abs() < 1.0e-12 {
+        return None;
+    }
+    let inverse_det = 1.0 / det;
+    let offset = origin - a;
+    let u = offset.dot(&p) * inverse_det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = offset.cross(&edge_1);
+    let v = direction.dot(&q) * inverse_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let distance = edge_2.dot(&q) * inverse_det;
+    if distance < 0.0 { None } else { Some(distance) }
+}
+
+pub fn closest_point_on_triangle(point: Vec3, a: Vec3, b: Vec3, c: Vec3) -> Vec3 {
+    closest_point_on_triangle_feature(point, [a, b, c]).0
+}
+
+// Closest point on a triangle by Voronoi region (Ericson, Real-Time Collision
+// Detection 5.1.5), along with the feature it lies on
+fn closest_point_on_triangle_feature(point: Vec3, triangle: [Vec3; 3]) -> (Vec3, TriangleFeature) {
+    let [a, b, c] = triangle;
+    let ab = b - a;
+    let ac = c - a;
+    let ap = point - a;
+    let d1 = ab.dot(&ap);
+    let d2 = ac.dot(&ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return (a, TriangleFeature::Vertex(0));
+    }
+
+    let bp = point - b;
+    let d3 = ab.dot(&bp);
+    let d4 = ac.dot(&bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return (b, TriangleFeature::Vertex(1));
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        let v = d1 / (d1 - d3);
+        return (a + ab * v, TriangleFeature::Edge(0));
+    }
+
+    let cp = point - c;
+    let d5 = ab.dot(&cp);
+    let d6 = ac.dot(&cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return (c, TriangleFeature::Vertex(2));
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        let w = d2 / (d2 - d6);
+        return (a + ac * w, TriangleFeature::Edge(2));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return (b + (c - b) * w, TriangleFeature::Edge(1));
+    }
+
+    let denom = 1.0 / (va + vb + vc);
+    let v = vb * denom;
+    let w = vc * denom;
+    (a + ab * v + ac * w, TriangleFeature::Face)
+}
+
+// Point of segment p0-p1 minimising `distance`, which must be convex along the
+// segment (as the signed distance to a convex shape is), found by ternary
+// search
+fn closest_point_on_segment_to(p0: Vec3, p1: Vec3, distance: impl Fn(Vec3) -> f32) -> Vec3 {
+    let distance_at = |t: f32| distance(p0 + (p1 - p0) * t);
+
+    let (mut lo, mut hi) = (0.0_f32, 1.0_f32);
+    for _ in 0..32 {
+        let m1 = lo + (hi - lo) / 3.0;
+        let m2 = hi - (hi - lo) / 3.0;
+        if distance_at(m1) <= distance_at(m2) {
+            hi = m2;
+        } else {
+            lo = m1;
+        }
+    }
+    let t_min = (lo + hi) * 0.5;
+
+    // A segment lying parallel to a face is equally close along a whole
+    // stretch; widen to that stretch and use its middle so a capsule lying on
+    // a surface is supported under its centre rather than at whichever end the
+    // search happened to settle on
+    let threshold = distance_at(t_min) + 1.0e-4;
+    let mut start = (0.0_f32, t_min);
+    let mut end = (t_min, 1.0_f32);
+    for _ in 0..24 {
+        let mid = (start.0 + start.1) * 0.5;
+        if distance_at(mid) <= threshold { start.1 = mid } else { start.0 = mid }
+        let mid = (end.0 + end.1) * 0.5;
+        if distance_at(mid) <= threshold { end.0 = mid } else { end.1 = mid }
+    }
+    let first = if distance_at(0.0) <= threshold { 0.0 } else { start.1 };
+    let last = if distance_at(1.0) <= threshold { 1.0 } else { end.0 };
+
+    p0 + (p1 - p0) * ((first + last) * 0.5)
+}
+
+pub fn closest_point_on_segment(point: Vec3, p0: Vec3, p1: Vec3) -> Vec3 {
+    let d = p1 - p0;
+    let length_squared = d.magnitude_squared();
+    if length_squared == 0.0 {
+        return p0;
+    }
+    let t = ((point - p0).dot(&d) / length_squared).clamp(0.0, 1.0);
+    p0 + d * t
+}
+
+// Signed distance from a point to a box centred on the origin; negative inside
+fn box_signed_distance(point: Vec3, half_extents: Vec3) -> f32 {
+    let q = Vec3::new(
+        point.x.abs() - half_extents.x,
+        point.y.abs() - half_extents.y,
+        point.z.abs() - half_extents.z,
+    );
+    let outside = Vec3::new(q.x.max(0.0), q.y.max(0.0), q.z.max(0.0)).magnitude();
+    let inside = q.x.max(q.y).max(q.z).min(0.0);
+    outside + inside
+}
+
+#[derive(Debug, Clone, Copy)]
+enum SatFeature {
+    FaceA,
+    FaceB,
+    Edge(usize, usize),
+}
+
+// Contact point for a face of `reference` against `incident`, where `normal`
+// points out of the reference face towards the incident box. It is the average
+// of the incident corners behind the reference face, each clamped onto the
+// reference box (so a large incident box still yields a point under the face)
+// and then projected onto the face itself.
+fn face_contact_point(reference: &OrientedBox, incident: &OrientedBox, normal: Vec3) -> Vec3 {
+    let face_offset = normal.dot(&reference.center) + reference.project_radius(normal);
+
+    let mut contact_sum = Vec3::zero();
+    let mut contact_count = 0;
+    for corner in incident.corners() {
+        if normal.dot(&corner) <= face_offset {
+            let clamped = reference.closest_point(corner);
+            contact_sum += clamped + normal * (face_offset - normal.dot(&clamped));
+            contact_count += 1;
+        }
+    }
+
+    if contact_count == 0 {
+        return (reference.support(normal) + incident.support(-normal)) * 0.5;
+    }
+
+    contact_sum * (1.0 / contact_count as f32)
+}
+
+// Midpoint of the closest points between the two boxes' support edges
+fn edge_contact_point(box_a: &OrientedBox, box_b: &OrientedBox, edge_a: usize, edge_b: usize, normal: Vec3) -> Vec3 {
+    let (a0, a1) = box_a.support_edge(edge_a, normal);
+    let (b0, b1) = box_b.support_edge(edge_b, -normal);
+    let (on_a, on_b) = closest_points_segment_segment(a0, a1, b0, b1);
+    (on_a + on_b) * 0.5
+}
+
+// Feature ids of box-box points that are not clipped face vertices
+const BOX_EDGE_ID: u32 = 1 << 16;
+const BOX_FALLBACK_ID: u32 = 1 << 17;
+
+// Manifold points for a face of `reference` against `incident`, where
+// `normal` points out of the reference face towards the incident box. The
+// incident face is clipped to the reference face's side planes and the
+// vertices behind the reference face are projected onto it. A point's id
+// packs which box is the reference (`side`), both faces, and the incident
+// vertex or the clip plane and edge that produced it.
+fn clip_box_faces(reference: &OrientedBox, incident: &OrientedBox, normal: Vec3, side: u32) -> Vec<ContactPoint> {
+    let (_, reference_code) = reference.face_towards(normal);
+    let face_offset = normal.dot(&reference.center) + reference.project_radius(normal);
+    let reference_axis = reference_code as usize / 2;
+
+    let (incident_face, incident_code) = incident.face_towards(-normal);
+    let mut polygon: Vec<(Vec3, u32)> = incident_face.into_iter().zip(0..).collect();
+    let mut plane = 0;
+    for axis in (0..3).filter(|&axis| axis != reference_axis) {
+        let side_normal = reference.axes[axis];
+        let offset = side_normal.dot(&reference.center);
+        let extent = reference.half_extents[axis];
+        polygon = clip_polygon(&polygon, side_normal, offset + extent, plane);
+        polygon = clip_polygon(&polygon, -side_normal, -offset + extent, plane + 1);
+        plane += 2;
+    }
+
+    let faces = (side * 6 + reference_code) * 6 + incident_code;
+    polygon
+        .into_iter()
+        .filter_map(|(vertex, vertex_id)| {
+            let depth = face_offset - normal.dot(&vertex);
+            (depth >= 0.0).then(|| ContactPoint { point: vertex + normal * depth, depth, id: faces << 8 | vertex_id })
+        })
+        .collect()
+}
+
+// Sutherland-Hodgman: the part of a convex polygon where normal . p <= offset.
+// Vertices carry ids; a vertex made by clipping the edge that starts at
+// vertex `id` against plane number `plane` gets (plane + 1) << 4 | (id & 0xf).
+fn clip_polygon(polygon: &[(Vec3, u32)], normal: Vec3, offset: f32, plane: u32) -> Vec<(Vec3, u32)> {
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+    for (i, &(start, start_id)) in polygon.iter().enumerate() {
+        let (end, _) = polygon[(i + 1) % polygon.len()];
+        let (d_start, d_end) = (normal.dot(&start) - offset, normal.dot(&end) - offset);
+        if d_start <= 0.0 {
+            clipped.push((start, start_id));
+        }
+        if (d_start < 0.0) != (d_end < 0.0) && (d_start - d_end).abs() > f32::EPSILON {
+            let crossing = start + (end - start) * (d_start / (d_start - d_end));
+            clipped.push((crossing, (plane + 1) << 4 | (start_id & 0xf)));
+        }
+    }
+    clipped
+}
+
+// Closest points between segments p0-p1 and q0-q1, including the parallel and
+// degenerate (zero-length) cases
+pub fn closest_points_segment_segment(p0: Vec3, p1: Vec3, q0: Vec3, q1: Vec3) -> (Vec3, Vec3) {
+    let d1 = p1 - p0;
+    let d2 = q1 - q0;
+    let r = p0 - q0;
+    let a = d1.dot(&d1);
+    let e = d2.dot(&d2);
+    let f = d2.dot(&r);
+
+    const EPSILON: f32 = 1.0e-8;
+
+    let (s, t) = if a <= EPSILON && e <= EPSILON {
+        (0.0, 0.0)
+    } else if a <= EPSILON {
+        (0.0, (f / e).clamp(0.0, 1.0))
+    } else {
+        let c = d1.dot(&r);
+        if e <= EPSILON {
+            ((-c / a).clamp(0.0, 1.0), 0.0)
+        } else {
+            let b = d1.dot(&d2);
+            let denom = a * e - b * b;
+            let mut s = if denom > 1.0e-6 * a * e {
+                ((b * f - c * e) / denom).clamp(0.0, 1.0)
+            } else {
+                // Parallel segments have a whole range of closest pairs. Take the
+                // middle of the overlapping stretch so contacts between
+                // side-by-side segments sit between them, not at one end.
+                let s0 = (q0 - p0).dot(&d1) / a;
+                let s1 = (q1 - p0).dot(&d1) / a;
+                let lo = s0.min(s1).max(0.0);
+                let hi = s0.max(s1).min(1.0);
+                if lo <= hi { (lo + hi) * 0.5 } else if hi < 0.0 { 0.0 } else { 1.0 }
+            };
+            let mut t = (b * s + f) / e;
+            if t < 0.0 {
+                t = 0.0;
+                s = (-c / a).clamp(0.0, 1.0);
+            } else if t > 1.0 {
+                t = 1.0;
+                s = ((b - c) / a).clamp(0.0, 1.0);
+            }
+            (s, t)
+        }
+    };
+
+    (p0 + d1 * s, q0 + d2 * t)
+}
+
+// A box in world space, described by its centre, unit axes and half extents
+struct OrientedBox {
+    center: Vec3,
+    axes: [Vec3; 3],
+    half_extents: [f32; 3],
+}
+
+impl OrientedBox {
+    fn new(center: Vec3, rotation: Quat, half_extents: Vec3) -> Self {
+        Self {
+            center,
+            axes: [
+                rotation.rotate(Vec3::new(1.0, 0.0, 0.0)),
+                rotation.rotate(Vec3::new(0.0, 1.0, 0.0)),
+                rotation.rotate(Vec3::new(0.0, 0.0, 1.0)),
+            ],
+            half_extents: [half_extents.x, half_extents.y, half_extents.z],
+        }
+    }
+
+    // Half-length of the box's projection onto `axis`
+    fn project_radius(&self, axis: Vec3) -> f32 {
+        (0..3)
+            .map(|i| self.half_extents[i] * self.axes[i].dot(&axis).abs())
+            .sum()
+    }
+
+    fn corners(&self) -> [Vec3; 8] {
+        let mut corners = [Vec3::zero(); 8];
+        for (i, corner) in corners.iter_mut().enumerate() {
+            let mut point = self.center;
+            for axis in 0..3 {
+                let sign = if i & (1 << axis) == 0 { -1.0 } else { 1.0 };
+                point += self.axes[axis] * (self.half_extents[axis] * sign);
+            }
+            *corner = point;
+        }
+        corners
+    }
+
+    // Corner furthest along `direction`
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let mut point = self.center;
+        for axis in 0..3 {
+            let sign = if self.axes[axis].dot(&direction) < 0.0 { -1.0 } else { 1.0 };
+            point += self.axes[axis] * (self.half_extents[axis] * sign);
+        }
+        point
+    }
+
+    // Edge parallel to axis `edge_axis` that lies furthest along `direction`
+    fn support_edge(&self, edge_axis: usize, direction: Vec3) -> (Vec3, Vec3) {
+        let mut mid = self.center;
+        for axis in 0..3 {
+            if axis == edge_axis {
+                continue;
+            }
+            let sign = if self.axes[axis].dot(&direction) < 0.0 { -1.0 } else { 1.0 };
+            mid += self.axes[axis] * (self.half_extents[axis] * sign);
+        }
+        let half_edge = self.axes[edge_axis] * self.half_extents[edge_axis];
+        (mid - half_edge, mid + half_edge)
+    }
+
+    // Corners of the face whose outward normal is closest to `direction`, in
+    // order around the face, and the face's number: 2 * axis, plus 1 for the
+    // face on the negative side
+    fn face_towards(&self, direction: Vec3) -> ([Vec3; 4], u32) {
+        let axis = (0..3)
+            .max_by(|&i, &j| self.axes[i].dot(&direction).abs().total_cmp(&self.axes[j].dot(&direction).abs()))
+            .unwrap();
+        let negative = self.axes[axis].dot(&direction) < 0.0;
+        let sign = if negative { -1.0 } else { 1.0 };
+        let center = self.center + self.axes[axis] * (self.half_extents[axis] * sign);
+        let u = self.axes[(axis + 1) % 3] * self.half_extents[(axis + 1) % 3];
+        let v = self.axes[(axis + 2) % 3] * self.half_extents[(axis + 2) % 3];
+        (
+            [center + u + v, center - u + v, center - u - v, center + u - v],
+            axis as u32 * 2 + negative as u32,
+        )
+    }
+
+    fn closest_point(&self, point: Vec3) -> Vec3 {
+        let delta = point - self.center;
+        let mut closest = self.center;
+        for axis in 0..3 {
+            let extent = self.half_extents[axis];
+            closest += self.axes[axis] * self.axes[axis].dot(&delta).clamp(-extent, extent);
+        }
+        closest
+    }
 }
@@ -0,0 +1,128 @@
+use crate::collision::ContactManifold;
+use crate::vector::Vec3;
+use std::collections::HashMap;
+
+// One contact point remembered from the last fixed step
+#[derive(Debug, Clone, Copy)]
+pub struct CachedContact {
+    // Feature id from ContactPoint::id
+    pub id: u32,
+    pub normal: Vec3,
+    pub point: Vec3,
+    pub depth: f32,
+    // Impulses the solver accumulated at this contact: the (never positive)
+    // amount along the normal and the friction impulse in world space
+    pub normal_impulse: f32,
+    pub friction_impulse: Vec3,
+    // Consecutive steps this contact has existed, counting the current one
+    pub age: u32,
+}
+
+// Everything cached about one touching pair of bodies
+#[derive(Debug, Clone, Default)]
+pub struct ContactPair {
+    pub contacts: Vec<CachedContact>,
+    // Consecutive steps the pair has been touching, counting the current one
+    pub steps: u32,
+}
+
+// Contacts of every touching pair of bodies, kept between fixed steps and
+// keyed by body indices. New contacts are matched to last step's by feature
+// id, so the solver's accumulated impulses and each contact's age carry over.
+#[derive(Debug, Clone, Default)]
+pub struct ContactCache {
+    pairs: HashMap<(usize, usize), ContactPair>,
+}
+
+// Contacts whose normals turned further than this (as a cosine) from last
+// step's are treated as new
+const NORMAL_MATCH_COSINE: f32 = 0.95;
+
+impl ContactCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Number of touching pairs
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    pub fn pair(&self, body_a: usize, body_b: usize) -> Option<&ContactPair> {
+        self.pairs.get(&pair_key(body_a, body_b))
+    }
+
+    pub(crate) fn pair_mut(&mut self, body_a: usize, body_b: usize) -> Option<&mut ContactPair> {
+        self.pairs.get_mut(&pair_key(body_a, body_b))
+    }
+
+    // How many consecutive steps the two bodies have been touching; 0 when
+    // they are apart
+    pub fn touching_steps(&self, body_a: usize, body_b: usize) -> u32 {
+        self.pair(body_a, body_b).map_or(0, |pair| pair.steps)
+    }
+
+    // Body pairs in the cache, `(i, j)` with i < j
+    pub(crate) fn keys(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.pairs.keys().copied()
+    }
+
+    // Drops the pairs of a removed body and moves the indices after it down
+    // one, as removing it from the world's bodies does
+    pub(crate) fn remove_body(&mut self, index: usize) {
+        let shift = |body: usize| if body > index { body - 1 } else { body };
+        self.pairs = std::mem::take(&mut self.pairs)
+            .into_iter()
+            .filter(|&((i, j), _)| i != index && j != index)
+            .map(|((i, j), pair)| ((shift(i), shift(j)), pair))
+            .collect();
+    }
+
+    // Replaces the cache with this step's touching pairs, `(i, j, manifolds)`
+    // with i < j. Pairs in `resting` were not tested, as neither body moved,
+    // so their entries are kept as they are. Other pairs are dropped.
+    pub(crate) fn update(&mut self, touching: &[(usize, usize, Vec<ContactManifold>)], resting: &[(usize, usize)]) {
+        let mut previous = std::mem::take(&mut self.pairs);
+        for &(i, j) in resting {
+            if let Some(pair) = previous.remove(&pair_key(i, j)) {
+                self.pairs.insert(pair_key(i, j), pair);
+            }
+        }
+        for (i, j, manifolds) in touching {
+            let key = pair_key(*i, *j);
+            let old = previous.remove(&key);
+
+            let contacts = manifolds
+                .iter()
+                .flat_map(|manifold| manifold.points.iter().map(move |point| (manifold.normal, point)))
+                .map(|(normal, point)| {
+                    let matched = old.as_ref().and_then(|old| {
+                        old.contacts
+                            .iter()
+                            .find(|cached| cached.id == point.id && cached.normal.dot(&normal) > NORMAL_MATCH_COSINE)
+                    });
+                    CachedContact {
+                        id: point.id,
+                        normal,
+                        point: point.point,
+                        depth: point.depth,
+                        normal_impulse: matched.map_or(0.0, |cached| cached.normal_impulse),
+                        friction_impulse: matched.map_or(Vec3::zero(), |cached| cached.friction_impulse),
+                        age: matched.map_or(1, |cached| cached.age + 1),
+                    }
+                })
+                .collect();
+
+            let steps = old.map_or(1, |old| old.steps + 1);
+            self.pairs.insert(key, ContactPair { contacts, steps });
+        }
+    }
+}
+
+fn pair_key(body_a: usize, body_b: usize) -> (usize, usize) {
+    (body_a.min(body_b), body_a.max(body_b))
+}
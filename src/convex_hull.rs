@@ -0,0 +1,155 @@
+use crate::vector::Vec3;
+
+// Convex hull of a point cloud, stored as its vertices and outward-facing
+// triangles (counter-clockwise when seen from outside)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvexHull {
+    pub vertices: Vec<Vec3>,
+    pub faces: Vec<[usize; 3]>,
+}
+
+impl ConvexHull {
+    // Builds the hull incrementally: start from a tetrahedron of extreme
+    // points, then add each remaining point by removing the faces it can see
+    // and stitching new faces to the horizon. Clouds with no volume (fewer
+    // than four non-coplanar points) keep their points but have no faces.
+    pub fn from_points(points: &[Vec3]) -> Self {
+        let mut unique: Vec<Vec3> = Vec::with_capacity(points.len());
+        for point in points {
+            if unique.iter().all(|p| (*p - *point).magnitude_squared() > HULL_EPSILON * HULL_EPSILON) {
+                unique.push(*point);
+            }
+        }
+
+        let Some(initial) = initial_tetrahedron(&unique) else {
+            return Self { vertices: unique, faces: Vec::new() };
+        };
+
+        let centroid = initial.iter().fold(Vec3::zero(), |sum, &i| sum + unique[i]) * 0.25;
+        let [a, b, c, d] = initial;
+        let mut faces: Vec<[usize; 3]> = Vec::new();
+        for face in [[a, b, c], [a, c, d], [a, d, b], [b, d, c]] {
+            faces.push(orient_outward(&unique, face, centroid));
+        }
+
+        for index in 0..unique.len() {
+            if initial.contains(&index) {
+                continue;
+            }
+            let point = unique[index];
+
+            let visible: Vec<bool> = faces
+                .iter()
+                .map(|face| {
+                    let normal = face_normal(&unique, face);
+                    normal.dot(&(point - unique[face[0]])) > HULL_EPSILON * normal.magnitude()
+                })
+                .collect();
+
+            if !visible.iter().any(|v| *v) {
+                continue; // Inside the current hull
+            }
+
+            // Horizon edges belong to exactly one visible face; keeping their
+            // winding makes the new faces face outwards too
+            let mut horizon: Vec<(usize, usize)> = Vec::new();
+            for (face, _) in faces.iter().zip(&visible).filter(|(_, v)| **v) {
+                for k in 0..3 {
+                    let edge = (face[k], face[(k + 1) % 3]);
+                    let shared = faces.iter().zip(&visible).any(|(other, other_visible)| {
+                        *other_visible && (0..3).any(|m| (other[m], other[(m + 1) % 3]) == (edge.1, edge.0))
+                    });
+                    if !shared {
+                        horizon.push(edge);
+                    }
+                }
+            }
+
+            let mut kept: Vec<[usize; 3]> = faces
+                .iter()
+                .zip(&visible)
+                .filter(|(_, v)| !**v)
+                .map(|(face, _)| *face)
+                .collect();
+            for (from, to) in horizon {
+                kept.push([from, to, index]);
+            }
+            faces = kept;
+        }
+
+        // Drop interior points and renumber the faces
+        let mut remap = vec![usize::MAX; unique.len()];
+        let mut vertices = Vec::new();
+        for face in &mut faces {
+            for index in face.iter_mut() {
+                if remap[*index] == usize::MAX {
+                    remap[*index] = vertices.len();
+                    vertices.push(unique[*index]);
+                }
+                *index = remap[*index];
+            }
+        }
+
+        Self { vertices, faces }
+    }
+
+    // Vertex furthest along `direction`, in the hull's local frame
+    pub fn support(&self, direction: Vec3) -> Vec3 {
+        self.vertices
+            .iter()
+            .copied()
+            .fold((f32::NEG_INFINITY, Vec3::zero()), |best, v| {
+                let d = v.dot(&direction);
+                if d > best.0 { (d, v) } else { best }
+            })
+            .1
+    }
+}
+
+const HULL_EPSILON: f32 = 1.0e-5;
+
+fn face_normal(points: &[Vec3], face: &[usize; 3]) -> Vec3 {
+    let a = points[face[0]];
+    (points[face[1]] - a).cross(&(points[face[2]] - a))
+}
+
+fn orient_outward(points: &[Vec3], face: [usize; 3], interior: Vec3) -> [usize; 3] {
+    if face_normal(points, &face).dot(&(points[face[0]] - interior)) < 0.0 {
+        [face[0], face[2], face[1]]
+    } else {
+        face
+    }
+}
+
+// Four points spanning a volume, or None if the cloud is flat
+fn initial_tetrahedron(points: &[Vec3]) -> Option<[usize; 4]> {
+    if points.len() < 4 {
+        return None;
+    }
+
+    let furthest_from = |score: &dyn Fn(Vec3) -> f32| {
+        (0..points.len())
+            .map(|i| (score(points[i]), i))
+            .fold((f32::NEG_INFINITY, 0), |best, s| if s.0 > best.0 { s } else { best })
+    };
+
+    let a = furthest_from(&|p| -p.x).1;
+    let (ab, b) = furthest_from(&|p| (p - points[a]).magnitude_squared());
+    if ab <= HULL_EPSILON * HULL_EPSILON {
+        return None;
+    }
+
+    let line = (points[b] - points[a]).normalize();
+    let (abc, c) = furthest_from(&|p| line.cross(&(p - points[a])).magnitude_squared());
+    if abc <= HULL_EPSILON * HULL_EPSILON {
+        return None;
+    }
+
+    let normal = (points[b] - points[a]).cross(&(points[c] - points[a])).normalize();
+    let (abcd, d) = furthest_from(&|p| normal.dot(&(p - points[a])).abs());
+    if abcd <= HULL_EPSILON {
+        return None;
+    }
+
+    Some([a, b, c, d])
+}
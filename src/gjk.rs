@@ -0,0 +1,336 @@
+use crate::collision::CollisionInfo;
+use crate::vector::Vec3;
+
+// Anything convex that can report its furthest point in a direction can be
+// tested with GJK/EPA, with no pair-specific code
+pub trait SupportMap {
+    // World-space point of the shape furthest along `direction`
+    fn support(&self, direction: Vec3) -> Vec3;
+
+    // Any point inside the shape, used to pick GJK's first search direction
+    fn center(&self) -> Vec3;
+}
+
+// A vertex of the Minkowski difference A - B, remembering which points of A
+// and B produced it so contact points can be recovered
+#[derive(Debug, Clone, Copy)]
+struct SupportPoint {
+    point: Vec3,
+    on_a: Vec3,
+    on_b: Vec3,
+}
+
+fn minkowski_support(a: &dyn SupportMap, b: &dyn SupportMap, direction: Vec3) -> SupportPoint {
+    let on_a = a.support(direction);
+    let on_b = b.support(-direction);
+    SupportPoint { point: on_a - on_b, on_a, on_b }
+}
+
+const GJK_MAX_ITERATIONS: usize = 64;
+const EPA_MAX_ITERATIONS: usize = 64;
+const EPA_TOLERANCE: f32 = 1.0e-4;
+
+// GJK intersection test: true when the shapes overlap (or touch)
+pub fn gjk_intersect(a: &dyn SupportMap, b: &dyn SupportMap) -> bool {
+    gjk(a, b).is_some()
+}
+
+// Full narrowphase for two convex shapes: GJK decides whether they overlap and
+// EPA then finds the penetration normal (pointing from A to B), depth and a
+// contact point halfway between the deepest points of each shape
+pub fn gjk_epa(a: &dyn SupportMap, b: &dyn SupportMap) -> CollisionInfo {
+    match gjk(a, b) {
+        Some(simplex) => epa(a, b, simplex),
+        None => CollisionInfo::none(),
+    }
+}
+
+// Returns a simplex of A - B that encloses the origin, or None if the origin
+// is outside the difference (the shapes are apart)
+fn gjk(a: &dyn SupportMap, b: &dyn SupportMap) -> Option<Vec<SupportPoint>> {
+    let mut direction = b.center() - a.center();
+    if direction.magnitude_squared() < 1.0e-12 {
+        direction = Vec3::new(1.0, 0.0, 0.0);
+    }
+
+    let mut simplex = vec![minkowski_support(a, b, direction)];
+    direction = -simplex[0].point;
+
+    for _ in 0..GJK_MAX_ITERATIONS {
+        // The origin lies on the current simplex: the shapes are touching
+        if direction.magnitude_squared() < 1.0e-12 {
+            return Some(simplex);
+        }
+
+        let next = minkowski_support(a, b, direction);
+        if next.point.dot(&direction) < 0.0 {
+            return None;
+        }
+
+        simplex.push(next);
+        if update_simplex(&mut simplex, &mut direction) {
+            return Some(simplex);
+        }
+    }
+
+    // Out of iterations while still closing in on the origin; treat as touching
+    Some(simplex)
+}
+
+// Reduces the simplex to the feature closest to the origin and points
+// `direction` at the origin from it. Returns true once a tetrahedron contains
+// the origin. The newest point is always last.
+fn update_simplex(simplex: &mut Vec<SupportPoint>, direction: &mut Vec3) -> bool {
+    match simplex.len() {
+        2 => {
+            let (b, a) = (simplex[0], simplex[1]);
+            let ab = b.point - a.point;
+            let ao = -a.point;
+            if ab.dot(&ao) > 0.0 {
+                *direction = ab.cross(&ao).cross(&ab);
+                // Origin on the segment's line
+                if direction.magnitude_squared() < 1.0e-12 {
+                    *direction = Vec3::zero();
+                }
+            } else {
+                *simplex = vec![a];
+                *direction = ao;
+            }
+            false
+        }
+        3 => {
+            let (c, b, a) = (simplex[0], simplex[1], simplex[2]);
+            let ab = b.point - a.point;
+            let ac = c.point - a.point;
+            let ao = -a.point;
+            let abc = ab.cross(&ac);
+
+            if abc.cross(&ac).dot(&ao) > 0.0 {
+                if ac.dot(&ao) > 0.0 {
+                    *simplex = vec![c, a];
+                    *direction = ac.cross(&ao).cross(&ac);
+                } else {
+                    *simplex = vec![b, a];
+                    return update_simplex(simplex, direction);
+                }
+            } else if ab.cross(&abc).dot(&ao) > 0.0 {
+                *simplex = vec![b, a];
+                return update_simplex(simplex, direction);
+            } else {
+                let side = abc.dot(&ao);
+                if side > 0.0 {
+                    *direction = abc;
+                } else if side < 0.0 {
+                    *direction = -abc;
+                } else {
+                    // Origin in the triangle's plane, inside it
+                    *direction = Vec3::zero();
+                }
+            }
+            false
+        }
+        4 => {
+            let (d, c, b, a) = (simplex[0], simplex[1], simplex[2], simplex[3]);
+            let ao = -a.point;
+
+            // Faces touching the newest point, each with the vertex opposite it.
+            // The face opposite `a` was already known to face the origin.
+            let faces = [(c, b, d), (d, c, b), (b, d, c)];
+            for (p, q, opposite) in faces {
+                let mut normal = (p.point - a.point).cross(&(q.point - a.point));
+                if normal.dot(&(opposite.point - a.point)) > 0.0 {
+                    normal = -normal;
+                }
+                if normal.dot(&ao) > 0.0 {
+                    *simplex = vec![q, p, a];
+                    return update_simplex(simplex, direction);
+                }
+            }
+            true
+        }
+        _ => false,
+    }
+}
+
+// Expanding Polytope Algorithm: grows the GJK simplex towards the boundary of
+// A - B until the face nearest the origin is on the boundary; that face gives
+// the minimum translation that separates the shapes
+fn epa(a: &dyn SupportMap, b: &dyn SupportMap, simplex: Vec<SupportPoint>) -> CollisionInfo {
+    let Some(mut vertices) = complete_tetrahedron(a, b, simplex) else {
+        // Zero-volume difference: the shapes only graze each other
+        return CollisionInfo::none();
+    };
+
+    let interior = vertices.iter().fold(Vec3::zero(), |sum, v| sum + v.point) * 0.25;
+    let mut faces: Vec<[usize; 3]> = vec![[0, 1, 2], [0, 2, 3], [0, 3, 1], [1, 3, 2]]
+        .into_iter()
+        .map(|face| {
+            let normal = triangle_normal(&vertices, face);
+            if normal.dot(&(vertices[face[0]].point - interior)) < 0.0 {
+                [face[0], face[2], face[1]]
+            } else {
+                face
+            }
+        })
+        .collect();
+
+    let mut closest = (faces[0], Vec3::zero(), 0.0);
+
+    for _ in 0..EPA_MAX_ITERATIONS {
+        closest = closest_face(&vertices, &faces);
+        let (_, normal, distance) = closest;
+
+        let next = minkowski_support(a, b, normal);
+        if next.point.dot(&normal) - distance < EPA_TOLERANCE {
+            break;
+        }
+
+        // Remove every face the new point can see and patch the hole from the
+        // horizon edges
+        let new_index = vertices.len();
+        vertices.push(next);
+
+        let mut horizon: Vec<(usize, usize)> = Vec::new();
+        faces.retain(|face| {
+            let normal = triangle_normal(&vertices, *face).normalize();
+            if normal.dot(&(next.point - vertices[face[0]].point)) > 0.0 {
+                for k in 0..3 {
+                    let edge = (face[k], face[(k + 1) % 3]);
+                    if let Some(position) = horizon.iter().position(|e| *e == (edge.1, edge.0)) {
+                        horizon.swap_remove(position);
+                    } else {
+                        horizon.push(edge);
+                    }
+                }
+                false
+            } else {
+                true
+            }
+        });
+
+        if horizon.is_empty() {
+            break;
+        }
+        for (from, to) in horizon {
+            faces.push([from, to, new_index]);
+        }
+    }
+
+    let (face, normal, distance) = closest;
+    let (u, v, w) = barycentric(
+        normal * distance,
+        vertices[face[0]].point,
+        vertices[face[1]].point,
+        vertices[face[2]].point,
+    );
+    let on_a = vertices[face[0]].on_a * u + vertices[face[1]].on_a * v + vertices[face[2]].on_a * w;
+    let on_b = vertices[face[0]].on_b * u + vertices[face[1]].on_b * v + vertices[face[2]].on_b * w;
+
+    CollisionInfo::new(normal, distance, (on_a + on_b) * 0.5)
+}
+
+// EPA needs a full tetrahedron; GJK can stop early on a point, segment or
+// triangle when the origin lies on it, so fill in the missing vertices
+fn complete_tetrahedron(
+    a: &dyn SupportMap,
+    b: &dyn SupportMap,
+    mut simplex: Vec<SupportPoint>,
+) -> Option<Vec<SupportPoint>> {
+    let axes = [
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Vec3::new(0.0, 0.0, 1.0),
+    ];
+
+    while simplex.len() < 4 {
+        let mut candidates: Vec<Vec3> = Vec::new();
+        match simplex.len() {
+            1 => {
+                for axis in axes {
+                    candidates.push(axis);
+                    candidates.push(-axis);
+                }
+            }
+            2 => {
+                let line = simplex[1].point - simplex[0].point;
+                for axis in axes {
+                    let perpendicular = line.cross(&axis);
+                    candidates.push(perpendicular);
+                    candidates.push(-perpendicular);
+                }
+            }
+            _ => {
+                let normal = (simplex[1].point - simplex[0].point).cross(&(simplex[2].point - simplex[0].point));
+                candidates.push(normal);
+                candidates.push(-normal);
+            }
+        }
+
+        let before = simplex.len();
+        for direction in candidates {
+            if direction.magnitude_squared() < 1.0e-12 {
+                continue;
+            }
+            let point = minkowski_support(a, b, direction);
+            if adds_dimension(&simplex, point.point) {
+                simplex.push(point);
+                break;
+            }
+        }
+        if simplex.len() == before {
+            return None;
+        }
+    }
+
+    Some(simplex)
+}
+
+fn adds_dimension(simplex: &[SupportPoint], point: Vec3) -> bool {
+    const EPSILON: f32 = 1.0e-6;
+    let origin = simplex[0].point;
+    match simplex.len() {
+        1 => (point - origin).magnitude_squared() > EPSILON,
+        2 => (simplex[1].point - origin).cross(&(point - origin)).magnitude_squared() > EPSILON,
+        _ => {
+            let normal = (simplex[1].point - origin).cross(&(simplex[2].point - origin));
+            normal.dot(&(point - origin)).abs() > EPSILON
+        }
+    }
+}
+
+fn triangle_normal(vertices: &[SupportPoint], face: [usize; 3]) -> Vec3 {
+    let a = vertices[face[0]].point;
+    (vertices[face[1]].point - a).cross(&(vertices[face[2]].point - a))
+}
+
+// Face of the polytope nearest the origin, with its unit normal and distance
+fn closest_face(vertices: &[SupportPoint], faces: &[[usize; 3]]) -> ([usize; 3], Vec3, f32) {
+    let mut best = (faces[0], Vec3::zero(), f32::INFINITY);
+    for face in faces {
+        let normal = triangle_normal(vertices, *face).normalize();
+        let distance = normal.dot(&vertices[face[0]].point);
+        if distance < best.2 {
+            best = (*face, normal, distance);
+        }
+    }
+    best
+}
+
+// Barycentric coordinates of `p` (assumed to lie in the triangle's plane)
+fn barycentric(p: Vec3, a: Vec3, b: Vec3, c: Vec3) -> (f32, f32, f32) {
+    let v0 = b - a;
+    let v1 = c - a;
+    let v2 = p - a;
+    let d00 = v0.dot(&v0);
+    let d01 = v0.dot(&v1);
+    let d11 = v1.dot(&v1);
+    let d20 = v2.dot(&v0);
+    let d21 = v2.dot(&v1);
+    let denom = d00 * d11 - d01 * d01;
+    if denom.abs() < 1.0e-12 {
+        return (1.0, 0.0, 0.0);
+    }
+    let v = (d11 * d20 - d01 * d21) / denom;
+    let w = (d00 * d21 - d01 * d20) / denom;
+    (1.0 - v - w, v, w)
+}
@@ -0,0 +1,222 @@
+use crate::broadphase::overlaps;
+use crate::collision::{ray_vs_triangle, RayHit};
+use crate::trimesh::{is_convex_edge, triangle_bounds, TriangleSource};
+use crate::vector::Vec3;
+
+// Terrain as a regular grid of heights. Sample (column, row) sits at local
+// (column * scale.x, height * scale.y, row * scale.z), so the grid starts at
+// the body origin and extends along +x and +z. Each cell is split into two
+// triangles along its (column, row) - (column + 1, row + 1) diagonal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heightfield {
+    heights: Vec<f32>,
+    columns: usize,
+    rows: usize,
+    pub scale: Vec3,
+    min_height: f32,
+    max_height: f32,
+}
+
+impl Heightfield {
+    // `heights` is row-major: all columns of row 0, then row 1, and so on
+    pub fn new(columns: usize, rows: usize, heights: Vec<f32>, scale: Vec3) -> Self {
+        assert!(columns >= 2 && rows >= 2, "a heightfield needs at least one cell");
+        assert_eq!(heights.len(), columns * rows, "expected columns * rows heights");
+
+        let min_height = heights.iter().copied().fold(f32::INFINITY, f32::min);
+        let max_height = heights.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        Self { heights, columns, rows, scale, min_height, max_height }
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    // Unscaled height sample
+    pub fn height(&self, column: usize, row: usize) -> f32 {
+        self.heights[row * self.columns + column]
+    }
+
+    pub fn vertex(&self, column: usize, row: usize) -> Vec3 {
+        Vec3::new(
+            column as f32 * self.scale.x,
+            self.height(column, row) * self.scale.y,
+            row as f32 * self.scale.z,
+        )
+    }
+
+    // Local-space bounds of the whole terrain
+    pub fn bounds(&self) -> (Vec3, Vec3) {
+        let low = (self.min_height * self.scale.y).min(self.max_height * self.scale.y);
+        let high = (self.min_height * self.scale.y).max(self.max_height * self.scale.y);
+        (
+            Vec3::new(0.0, low, 0.0),
+            Vec3::new(
+                (self.columns - 1) as f32 * self.scale.x,
+                high,
+                (self.rows - 1) as f32 * self.scale.z,
+            ),
+        )
+    }
+
+    // The two triangles of a cell, wound to face +y
+    pub fn cell_triangles(&self, column: usize, row: usize) -> [[Vec3; 3]; 2] {
+        let p00 = self.vertex(column, row);
+        let p01 = self.vertex(column, row + 1);
+        let p11 = self.vertex(column + 1, row + 1);
+        let p10 = self.vertex(column + 1, row);
+        [[p00, p01, p11], [p00, p11, p10]]
+    }
+
+    // Local-space raycast, walking only the cells the ray passes over
+    pub fn raycast(&self, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<RayHit> {
+        if direction.magnitude_squared() == 0.0 {
+            return None;
+        }
+        let direction = direction.normalize();
+
+        // Clip the ray to the terrain's bounding box
+        let (min, max) = self.bounds();
+        let (mut t_enter, mut t_exit) = (0.0_f32, max_distance);
+        for (o, d, lo, hi) in [
+            (origin.x, direction.x, min.x, max.x),
+            (origin.y, direction.y, min.y, max.y),
+            (origin.z, direction.z, min.z, max.z),
+        ] {
+            if d.abs() < 1.0e-12 {
+                if o < lo || o > hi {
+                    return None;
+                }
+                continue;
+            }
+            let (t0, t1) = ((lo - o) / d, (hi - o) / d);
+            t_enter = t_enter.max(t0.min(t1));
+            t_exit = t_exit.min(t0.max(t1));
+        }
+        if t_enter > t_exit {
+            return None;
+        }
+
+        // Step through the cells under the ray (Amanatides & Woo)
+        let start = origin + direction * t_enter;
+        let last_column = (self.columns - 2) as isize;
+        let last_row = (self.rows - 2) as isize;
+        let mut column = ((start.x / self.scale.x).floor() as isize).clamp(0, last_column);
+        let mut row = ((start.z / self.scale.z).floor() as isize).clamp(0, last_row);
+
+        let axis_step = |o: f32, d: f32, cell: isize, size: f32| {
+            if d > 0.0 {
+                (1, ((cell + 1) as f32 * size - o) / d, size / d)
+            } else if d < 0.0 {
+                (-1, (cell as f32 * size - o) / d, -size / d)
+            } else {
+                (0, f32::INFINITY, f32::INFINITY)
+            }
+        };
+        let (step_column, mut next_column, delta_column) = axis_step(origin.x, direction.x, column, self.scale.x);
+        let (step_row, mut next_row, delta_row) = axis_step(origin.z, direction.z, row, self.scale.z);
+
+        loop {
+            let mut nearest: Option<RayHit> = None;
+            for [a, b, c] in self.cell_triangles(column as usize, row as usize) {
+                let Some(distance) = ray_vs_triangle(origin, direction, a, b, c) else {
+                    continue;
+                };
+                if distance > max_distance || nearest.as_ref().is_some_and(|hit| hit.distance <= distance) {
+                    continue;
+                }
+                let mut normal = (b - a).cross(&(c - a)).normalize();
+                if normal.dot(&direction) > 0.0 {
+                    normal = -normal;
+                }
+                nearest = Some(RayHit { distance, point: origin + direction * distance, normal });
+            }
+            if nearest.is_some() {
+                return nearest;
+            }
+
+            if next_column < next_row {
+                if next_column > t_exit {
+                    return None;
+                }
+                column += step_column;
+                next_column += delta_column;
+            } else {
+                if next_row > t_exit {
+                    return None;
+                }
+                row += step_row;
+                next_row += delta_row;
+            }
+            if column < 0 || column > last_column || row < 0 || row > last_row {
+                return None;
+            }
+        }
+    }
+
+    // Inclusive range of cells along one axis overlapping [min, max]
+    fn cell_range(min: f32, max: f32, size: f32, cells: usize) -> Option<(usize, usize)> {
+        let first = (min / size).floor();
+        let last = (max / size).floor();
+        if last < 0.0 || first > (cells - 1) as f32 {
+            return None;
+        }
+        Some((first.max(0.0) as usize, (last as usize).min(cells - 1)))
+    }
+
+    // Far vertex of the triangle across edge `edge` of triangle `half` in the
+    // cell, or None on the terrain's border
+    fn neighbour_far_vertex(&self, column: usize, row: usize, half: usize, edge: usize) -> Option<Vec3> {
+        let (c, r) = (column as isize, row as isize);
+        let (far_column, far_row) = match (half, edge) {
+            // First triangle: (c, r) -> (c, r + 1) -> (c + 1, r + 1)
+            (0, 0) => (c - 1, r),
+            (0, 1) => (c + 1, r + 2),
+            (0, _) => (c + 1, r),
+            // Second triangle: (c, r) -> (c + 1, r + 1) -> (c + 1, r)
+            (_, 0) => (c, r + 1),
+            (_, 1) => (c + 2, r + 1),
+            _ => (c, r - 1),
+        };
+        if far_column < 0 || far_row < 0 || far_column >= self.columns as isize || far_row >= self.rows as isize {
+            return None;
+        }
+        Some(self.vertex(far_column as usize, far_row as usize))
+    }
+}
+
+impl TriangleSource for Heightfield {
+    fn triangles_near(&self, min: Vec3, max: Vec3) -> Vec<([Vec3; 3], [bool; 3])> {
+        let mut found = Vec::new();
+        if max.y < self.bounds().0.y || min.y > self.bounds().1.y {
+            return found;
+        }
+        let Some((first_column, last_column)) = Self::cell_range(min.x, max.x, self.scale.x, self.columns - 1) else {
+            return found;
+        };
+        let Some((first_row, last_row)) = Self::cell_range(min.z, max.z, self.scale.z, self.rows - 1) else {
+            return found;
+        };
+
+        for row in first_row..=last_row {
+            for column in first_column..=last_column {
+                for (half, triangle) in self.cell_triangles(column, row).into_iter().enumerate() {
+                    let (tri_min, tri_max) = triangle_bounds(&triangle);
+                    if !overlaps(tri_min, tri_max, min, max) {
+                        continue;
+                    }
+                    let active = [0, 1, 2].map(|edge| {
+                        self.neighbour_far_vertex(column, row, half, edge)
+                            .is_none_or(|far| is_convex_edge(triangle, far))
+                    });
+                    found.push((triangle, active));
+                }
+            }
+        }
+        found
+    }
+}
@@ -1,12 +1,40 @@
+pub mod aabb_tree;
 pub mod body;
+pub mod broadphase;
 pub mod collision;
+pub mod contact_cache;
+pub mod convex_hull;
+pub mod gjk;
+pub mod heightfield;
+pub mod mass;
+pub mod octree;
 pub mod physics_loop;
+pub mod spatial_hash;
+pub mod trimesh;
 pub mod vector;
 
+pub use aabb_tree::DynamicAabbTree;
 pub use body::{Body, RigidBody, StaticBody};
+pub use broadphase::{Broadphase, BruteForce, SweepAndPrune};
 pub use collision::{
-    aabb_vs_aabb, sphere_vs_aabb, sphere_vs_plane, sphere_vs_sphere, CollisionInfo,
-    CollisionShape,
+    aabb_vs_aabb, aabb_vs_plane, box_vs_box, box_vs_box_manifold, box_vs_heightfield, box_vs_plane,
+    box_vs_plane_manifold, box_vs_triangle, box_vs_trimesh, capsule_segment, capsule_vs_box,
+    capsule_vs_capsule, capsule_vs_heightfield, capsule_vs_plane, capsule_vs_triangle,
+    capsule_vs_trimesh, closest_point_on_segment, closest_point_on_triangle,
+    closest_points_segment_segment, collide, collide_contacts, collide_manifolds, cone_vs_plane,
+    convex_vs_heightfield, convex_vs_trimesh, cylinder_vs_plane, plane_world_distance,
+    ray_vs_triangle, raycast_heightfield, sphere_vs_aabb, sphere_vs_box, sphere_vs_capsule,
+    sphere_vs_cone, sphere_vs_cylinder, sphere_vs_heightfield, sphere_vs_plane, sphere_vs_sphere,
+    sphere_vs_triangle, sphere_vs_trimesh, CollisionInfo, CollisionShape, ContactManifold,
+    ContactPoint, LocalTransform, RayHit, ShapeSupport, MAX_MANIFOLD_POINTS,
 };
+pub use contact_cache::{CachedContact, ContactCache, ContactPair};
+pub use convex_hull::ConvexHull;
+pub use gjk::{gjk_epa, gjk_intersect, SupportMap};
+pub use heightfield::Heightfield;
+pub use mass::MassProperties;
+pub use octree::Octree;
 pub use physics_loop::PhysicsWorld;
-pub use vector::Vec3;
\ No newline at end of file
+pub use spatial_hash::SpatialHashGrid;
+pub use trimesh::TriMesh;
+pub use vector::{Mat3, Quat, Vec3};
\ No newline at end of file
@@ -0,0 +1,179 @@
+use crate::collision::{CollisionShape, LocalTransform};
+use crate::convex_hull::ConvexHull;
+use crate::vector::{Mat3, Vec3};
+use std::f32::consts::PI;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MassProperties {
+    pub mass: f32,
+    pub inverse_mass: f32,
+    // Inertia tensor about the centre of mass, in the body's local frame
+    pub local_inertia: Mat3,
+    // Centre of mass relative to the body origin, in the body's local frame
+    pub center_of_mass: Vec3,
+}
+
+impl MassProperties {
+    pub fn new(mass: f32, local_inertia: Mat3, center_of_mass: Vec3) -> Self {
+        let inverse_mass = if mass > 0.0 && mass.is_finite() { 1.0 / mass } else { 0.0 };
+        Self {
+            mass,
+            inverse_mass,
+            local_inertia,
+            center_of_mass,
+        }
+    }
+
+    // Mass properties of a solid shape with uniform density
+    pub fn from_shape(shape: &CollisionShape, density: f32) -> Self {
+        match shape {
+            CollisionShape::Sphere { radius } => {
+                let mass = density * (4.0 / 3.0) * PI * radius * radius * radius;
+                let i = 0.4 * mass * radius * radius;
+                Self::new(mass, Mat3::from_diagonal(Vec3::new(i, i, i)), Vec3::zero())
+            }
+            CollisionShape::AABB { min, max } => {
+                let size = *max - *min;
+                let mass = density * size.x * size.y * size.z;
+                let (x2, y2, z2) = (size.x * size.x, size.y * size.y, size.z * size.z);
+                let inertia = Mat3::from_diagonal(Vec3::new(y2 + z2, x2 + z2, x2 + y2) * (mass / 12.0));
+                Self::new(mass, inertia, (*min + *max) * 0.5)
+            }
+            CollisionShape::Box { half_extents } => {
+                let size = *half_extents * 2.0;
+                let mass = density * size.x * size.y * size.z;
+                let (x2, y2, z2) = (size.x * size.x, size.y * size.y, size.z * size.z);
+                let inertia = Mat3::from_diagonal(Vec3::new(y2 + z2, x2 + z2, x2 + y2) * (mass / 12.0));
+                Self::new(mass, inertia, Vec3::zero())
+            }
+            CollisionShape::Capsule { half_height, radius } => {
+                // Cylinder of height 2 * half_height plus two hemispherical caps
+                let height = 2.0 * half_height;
+                let r2 = radius * radius;
+                let cylinder_mass = density * PI * r2 * height;
+                let caps_mass = density * (4.0 / 3.0) * PI * r2 * radius;
+                let mass = cylinder_mass + caps_mass;
+
+                let axial = cylinder_mass * r2 * 0.5 + caps_mass * r2 * 0.4;
+                // Caps about a transverse axis through the centre, via the
+                // parallel-axis theorem on each hemisphere
+                let transverse = cylinder_mass * (height * height / 12.0 + r2 / 4.0)
+                    + caps_mass * (0.4 * r2 + height * height / 4.0 + 0.375 * height * radius);
+                Self::new(mass, Mat3::from_diagonal(Vec3::new(transverse, axial, transverse)), Vec3::zero())
+            }
+            CollisionShape::Cylinder { half_height, radius } => {
+                let height = 2.0 * half_height;
+                let r2 = radius * radius;
+                let mass = density * PI * r2 * height;
+                let axial = 0.5 * mass * r2;
+                let transverse = mass * (3.0 * r2 + height * height) / 12.0;
+                Self::new(mass, Mat3::from_diagonal(Vec3::new(transverse, axial, transverse)), Vec3::zero())
+            }
+            CollisionShape::Cone { half_height, radius } => {
+                let height = 2.0 * half_height;
+                let r2 = radius * radius;
+                let mass = density * PI * r2 * height / 3.0;
+                let axial = 0.3 * mass * r2;
+                // About the centre of mass, a quarter of the height above the base
+                let transverse = mass * (3.0 * r2 / 20.0 + 3.0 * height * height / 80.0);
+                Self::new(
+                    mass,
+                    Mat3::from_diagonal(Vec3::new(transverse, axial, transverse)),
+                    Vec3::new(0.0, -half_height + height / 4.0, 0.0),
+                )
+            }
+            CollisionShape::ConvexHull(hull) => Self::from_convex_hull(hull, density),
+            CollisionShape::Compound(children) => Self::from_compound(children, density),
+            // A half-space has infinite volume, and meshes and terrain are
+            // static level geometry; treat them all as immovable
+            CollisionShape::Plane { .. } | CollisionShape::TriMesh(_) | CollisionShape::Heightfield(_) => {
+                Self::new(f32::INFINITY, Mat3::zero(), Vec3::zero())
+            }
+        }
+    }
+
+    // Integrates over the tetrahedra formed by each face and the origin. Each
+    // contributes its signed volume, first moment and covariance
+    // C = det/120 * (aa^T + bb^T + cc^T + ss^T), s = a + b + c; the inertia
+    // tensor about the centre of mass is then tr(C) * Id - C.
+    fn from_convex_hull(hull: &ConvexHull, density: f32) -> Self {
+        let mut volume = 0.0;
+        let mut moment = Vec3::zero();
+        let mut covariance = Mat3::zero();
+
+        for face in &hull.faces {
+            let (a, b, c) = (hull.vertices[face[0]], hull.vertices[face[1]], hull.vertices[face[2]]);
+            let det = a.dot(&b.cross(&c));
+            let sum = a + b + c;
+            volume += det / 6.0;
+            moment += sum * (det / 24.0);
+            covariance = covariance
+                + (Mat3::outer_product(a, a)
+                    + Mat3::outer_product(b, b)
+                    + Mat3::outer_product(c, c)
+                    + Mat3::outer_product(sum, sum))
+                    * (det / 120.0);
+        }
+
+        // Flat or empty hulls have no volume to integrate
+        if volume <= 0.0 {
+            return Self::new(0.0, Mat3::zero(), Vec3::zero());
+        }
+
+        let center_of_mass = moment * (1.0 / volume);
+        let mass = density * volume;
+        // Move the covariance to the centre of mass, then scale by density
+        let covariance = (covariance - Mat3::outer_product(center_of_mass, center_of_mass) * volume) * density;
+        let inertia = Mat3::identity() * covariance.trace() - covariance;
+        Self::new(mass, inertia, center_of_mass)
+    }
+
+    // Sums the children about the combined centre of mass: each child's
+    // tensor is rotated into the body frame and moved with the parallel-axis
+    // theorem, I + m * (|d|^2 * Id - d d^T)
+    fn from_compound(children: &[(LocalTransform, CollisionShape)], density: f32) -> Self {
+        let parts: Vec<(Self, Vec3, Mat3)> = children
+            .iter()
+            .map(|(transform, child)| {
+                let props = Self::from_shape(child, density);
+                let center = transform.position + transform.rotation.rotate(props.center_of_mass);
+                let inertia = props.local_inertia.rotate_tensor(&Mat3::from_quat(transform.rotation));
+                (props, center, inertia)
+            })
+            .collect();
+
+        let mass: f32 = parts.iter().map(|(props, _, _)| props.mass).sum();
+        // An immovable child pins the whole body
+        if !mass.is_finite() {
+            return Self::new(f32::INFINITY, Mat3::zero(), Vec3::zero());
+        }
+        if mass <= 0.0 {
+            return Self::new(0.0, Mat3::zero(), Vec3::zero());
+        }
+
+        let center_of_mass = parts
+            .iter()
+            .fold(Vec3::zero(), |sum, (props, center, _)| sum + *center * props.mass)
+            * (1.0 / mass);
+        let inertia = parts.iter().fold(Mat3::zero(), |sum, (props, center, inertia)| {
+            let offset = *center - center_of_mass;
+            let shift = Mat3::identity() * offset.magnitude_squared() - Mat3::outer_product(offset, offset);
+            sum + *inertia + shift * props.mass
+        });
+        Self::new(mass, inertia, center_of_mass)
+    }
+
+    // Mass properties of a shape scaled to an explicit total mass. Planes,
+    // meshes, terrain and compounds holding one keep their infinite mass
+    // whatever mass is asked for, as from_shape gives them.
+    pub fn from_shape_with_mass(shape: &CollisionShape, mass: f32) -> Self {
+        let unit = Self::from_shape(shape, 1.0);
+        if !unit.mass.is_finite() {
+            return unit;
+        }
+        if unit.mass <= 0.0 {
+            return Self::new(mass, Mat3::zero(), unit.center_of_mass);
+        }
+        Self::new(mass, unit.local_inertia * (mass / unit.mass), unit.center_of_mass)
+    }
+}
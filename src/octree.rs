@@ -0,0 +1,139 @@
+use crate::broadphase::{component, component_max, component_min, encloses, is_bounded, overlaps};
+use crate::vector::Vec3;
+
+// Octree over bounding boxes that never move, such as a level's static
+// colliders. It is built once from all of them: a node splits into eight
+// octants when it holds more than `leaf_capacity` boxes and is shallower than
+// `max_depth`, and each box moves down into the octant that holds it whole.
+// Boxes straddling octant borders stay in the node above. Unbounded boxes
+// (planes) are kept aside and returned by every query.
+#[derive(Debug, Clone)]
+pub struct Octree {
+    nodes: Vec<OctreeNode>,
+    unbounded: Vec<usize>,
+    max_depth: usize,
+    leaf_capacity: usize,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+struct OctreeNode {
+    min: Vec3,
+    max: Vec3,
+    // Index of the first of eight consecutive children, for split nodes
+    first_child: Option<usize>,
+    items: Vec<(usize, Vec3, Vec3)>,
+    depth: usize,
+}
+
+impl Octree {
+    // `items` are `(id, min, max)`
+    pub fn build(items: &[(usize, Vec3, Vec3)], max_depth: usize, leaf_capacity: usize) -> Self {
+        let mut octree = Self { nodes: Vec::new(), unbounded: Vec::new(), max_depth, leaf_capacity, len: items.len() };
+        let (bounded, unbounded): (Vec<_>, Vec<_>) = items.iter().partition(|(_, min, max)| is_bounded(*min, *max));
+        octree.unbounded = unbounded.iter().map(|(id, _, _)| *id).collect();
+
+        let Some(&(_, first_min, first_max)) = bounded.first() else {
+            return octree;
+        };
+        let (min, max) = bounded.iter().fold((first_min, first_max), |(min, max), (_, item_min, item_max)| {
+            (component_min(min, *item_min), component_max(max, *item_max))
+        });
+        octree.nodes.push(OctreeNode { min, max, first_child: None, items: Vec::new(), depth: 0 });
+        for &item in &bounded {
+            octree.insert(0, item);
+        }
+        octree
+    }
+
+    // Number of boxes the tree was built from
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    pub fn leaf_capacity(&self) -> usize {
+        self.leaf_capacity
+    }
+
+    // Deepest level any node reached, 0 for just the root
+    pub fn depth(&self) -> usize {
+        self.nodes.iter().map(|node| node.depth).max().unwrap_or(0)
+    }
+
+    // Ids of the boxes overlapping the query box, in increasing order
+    pub fn query_aabb(&self, min: Vec3, max: Vec3) -> Vec<usize> {
+        let mut found = self.unbounded.clone();
+        let mut stack: Vec<usize> = if self.nodes.is_empty() { Vec::new() } else { vec![0] };
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !overlaps(node.min, node.max, min, max) {
+                continue;
+            }
+            for &(id, item_min, item_max) in &node.items {
+                if overlaps(item_min, item_max, min, max) {
+                    found.push(id);
+                }
+            }
+            if let Some(first) = node.first_child {
+                stack.extend(first..first + 8);
+            }
+        }
+        found.sort_unstable();
+        found
+    }
+
+    fn insert(&mut self, mut index: usize, item: (usize, Vec3, Vec3)) {
+        // Walk down while some octant holds the box whole
+        let (_, min, max) = item;
+        while let Some(first) = self.nodes[index].first_child {
+            let holds = |child: &usize| encloses(self.nodes[*child].min, self.nodes[*child].max, min, max);
+            match (first..first + 8).find(holds) {
+                Some(child) => index = child,
+                None => break,
+            }
+        }
+        self.nodes[index].items.push(item);
+
+        let node = &self.nodes[index];
+        if node.first_child.is_none() && node.items.len() > self.leaf_capacity && node.depth < self.max_depth {
+            self.split(index);
+        }
+    }
+
+    // Gives a leaf eight children and moves its boxes down where they fit
+    fn split(&mut self, index: usize) {
+        let (min, max, depth) = (self.nodes[index].min, self.nodes[index].max, self.nodes[index].depth);
+        let center = (min + max) * 0.5;
+        let first = self.nodes.len();
+        for octant in 0..8 {
+            let pick = |axis: usize| {
+                if octant & (1 << axis) == 0 {
+                    (component(min, axis), component(center, axis))
+                } else {
+                    (component(center, axis), component(max, axis))
+                }
+            };
+            let (x, y, z) = (pick(0), pick(1), pick(2));
+            self.nodes.push(OctreeNode {
+                min: Vec3::new(x.0, y.0, z.0),
+                max: Vec3::new(x.1, y.1, z.1),
+                first_child: None,
+                items: Vec::new(),
+                depth: depth + 1,
+            });
+        }
+        self.nodes[index].first_child = Some(first);
+
+        for item in std::mem::take(&mut self.nodes[index].items) {
+            self.insert(index, item);
+        }
+    }
+}
@@ -1,6 +1,10 @@
 use crate::body::Body;
-use crate::collision::{sphere_vs_plane, CollisionInfo, CollisionShape, sphere_vs_sphere, sphere_vs_aabb, aabb_vs_aabb};
-use crate::vector::Vec3;
+use crate::broadphase::{overlaps, Broadphase, SweepAndPrune};
+use crate::collision::{collide_manifolds, CollisionShape, ContactManifold};
+use crate::contact_cache::ContactCache;
+use crate::octree::Octree;
+use crate::vector::{Mat3, Vec3};
+use std::collections::HashMap;
 
 pub struct PhysicsWorld {
     pub bodies: Vec<Body>,
@@ -8,7 +12,50 @@ pub struct PhysicsWorld {
     fixed_timestep: f32,
     pub gravity: Vec3,
     pub restitution: f32,
+    // Contacts approaching slower than this do not bounce, so the speed
+    // gravity adds each step does not keep resting bodies hopping
+    pub restitution_threshold: f32,
     pub friction: f32,
+    // Solver passes over every contact per step: velocity iterations settle
+    // the impulses, position iterations push overlapping bodies apart
+    pub velocity_iterations: usize,
+    pub position_iterations: usize,
+    // Share of each contact's impulses from the last step applied before the
+    // velocity iterations start; 0 starts every step from scratch
+    pub warm_start_factor: f32,
+    // Split impulses: when on, overlaps are resolved with pseudo-velocities
+    // that move the bodies but are never stored in their velocities, in place
+    // of the Baumgarte bias and the position iterations' direct moves. Depths
+    // up to the slop are left alone, and the factor is the share of the rest
+    // removed per step.
+    pub split_impulse: bool,
+    pub split_impulse_slop: f32,
+    pub split_impulse_factor: f32,
+    // Finds the candidate pairs among rigid bodies the narrowphase tests each
+    // step, and answers bounds queries for them; swap in a DynamicAabbTree
+    // for scenes of fast movers, or a SpatialHashGrid for many bodies of
+    // about the same size
+    pub broadphase: Box<dyn Broadphase>,
+    // Static bodies go in an octree instead, built again only when a static
+    // body is added, removed or moved, or these settings change
+    pub octree_max_depth: usize,
+    pub octree_leaf_capacity: usize,
+    // Rigid bodies whose linear and angular speeds stay under the thresholds
+    // for `sleep_time` seconds go to sleep, along with the bodies they touch:
+    // they skip integration and are only tested against awake bodies, which
+    // wake them on contact. They also wake when a body they touch moves away
+    // or is removed, or a static body is placed on them.
+    pub allow_sleeping: bool,
+    pub sleep_linear_threshold: f32,
+    pub sleep_angular_threshold: f32,
+    pub sleep_time: f32,
+    static_octree: Octree,
+    // Indices and bounds of the static bodies the octree was built from
+    octree_items: Vec<(usize, Vec3, Vec3)>,
+    contacts: ContactCache,
+    // Number of bodies after the last step, to notice bodies taken out of
+    // `bodies` directly
+    body_count: usize,
 }
 
 impl PhysicsWorld {
@@ -19,19 +66,30 @@ impl PhysicsWorld {
             fixed_timestep,
             gravity: Vec3::new(0.0, -9.8, 0.0),
             restitution: 0.5,
+            restitution_threshold: 1.0,
             friction: 0.25,
+            velocity_iterations: 10,
+            position_iterations: 3,
+            warm_start_factor: 0.9,
+            split_impulse: false,
+            split_impulse_slop: 0.005,
+            split_impulse_factor: 0.8,
+            broadphase: Box::new(SweepAndPrune::new()),
+            octree_max_depth: 8,
+            octree_leaf_capacity: 8,
+            allow_sleeping: true,
+            sleep_linear_threshold: 0.1,
+            sleep_angular_threshold: 0.1,
+            sleep_time: 0.5,
+            static_octree: Octree::build(&[], 8, 8),
+            octree_items: Vec::new(),
+            contacts: ContactCache::new(),
+            body_count: 0,
         }
     }
 
     pub fn with_gravity(fixed_timestep: f32, gravity: Vec3) -> Self {
-        Self {
-            bodies: Vec::new(),
-            accumulator: 0.0,
-            fixed_timestep,
-            gravity,
-            restitution: 0.5,
-            friction: 0.25,
-        }
+        Self { gravity, ..Self::new(fixed_timestep) }
     }
 
     // Modified add_body to accept Body enum
@@ -39,210 +97,661 @@ impl PhysicsWorld {
         self.bodies.push(body);
     }
 
+    // Takes a body out of the world. Whatever rested on it wakes up, along
+    // with everything touching that, and the bodies after it move down one
+    // index. Bodies removed from `bodies` directly wake every sleeping body
+    // at the next step instead, as there is no telling which one went.
+    pub fn remove_body(&mut self, index: usize) -> Body {
+        self.forget_removed_bodies();
+        self.wake_island(index);
+        self.contacts.remove_body(index);
+        self.body_count -= 1;
+        self.bodies.remove(index)
+    }
+
+    // Bodies removed without remove_body leave the contacts cached under
+    // shifted indices, and nothing to tell what rested on them; start over
+    // with every body awake
+    fn forget_removed_bodies(&mut self) {
+        if self.bodies.len() < self.body_count {
+            self.contacts = ContactCache::new();
+            self.bodies.iter_mut().for_each(Body::wake);
+        }
+        self.body_count = self.bodies.len();
+    }
+
+    // Wakes the bodies touching a body, and the ones touching those in turn.
+    // Only rigid bodies pass it on, or one ground would wake the whole world.
+    fn wake_island(&mut self, index: usize) {
+        let pairs: Vec<(usize, usize)> = self.contacts.keys().collect();
+        let mut reached = vec![false; self.bodies.len()];
+        reached[index] = true;
+        let mut frontier = vec![index];
+        while let Some(body) = frontier.pop() {
+            for &(i, j) in &pairs {
+                let other = if body == i {
+                    j
+                } else if body == j {
+                    i
+                } else {
+                    continue;
+                };
+                if !reached[other] && self.bodies[other].as_rigid_body().is_some() {
+                    reached[other] = true;
+                    self.bodies[other].wake();
+                    frontier.push(other);
+                }
+            }
+        }
+    }
+
+    // Wakes the sleeping bodies whose bounds overlap a box
+    fn wake_overlapping(&mut self, min: Vec3, max: Vec3) {
+        for body in &mut self.bodies {
+            if body.is_sleeping() {
+                let (body_min, body_max) = body_bounds(body);
+                if overlaps(body_min, body_max, min, max) {
+                    body.wake();
+                }
+            }
+        }
+    }
+
+    // Contacts of the pairs touching as of the last fixed step, including
+    // pairs of sleeping bodies, which keep what they had when they fell asleep
+    pub fn contacts(&self) -> &ContactCache {
+        &self.contacts
+    }
+
+    // Indices of the bodies whose world bounds overlap the box, in order:
+    // rigid bodies come from the broadphase, static ones from the octree.
+    // Both are brought up to date by each update; bodies added, removed or
+    // moved since are still found, by checking them one by one.
+    pub fn query_aabb(&self, min: Vec3, max: Vec3) -> Vec<usize> {
+        let (rigid, bounds) = self.rigid_bounds();
+        let mut found: Vec<usize> =
+            self.broadphase.query_bounds(&bounds, min, max).into_iter().map(|index| rigid[index]).collect();
+        let statics = static_items(&self.bodies);
+        if statics == self.octree_items {
+            found.extend(self.static_octree.query_aabb(min, max));
+        } else {
+            let overlapping = statics.into_iter().filter(|&(_, low, high)| overlaps(low, high, min, max));
+            found.extend(overlapping.map(|(index, _, _)| index));
+        }
+        found.sort_unstable();
+        found
+    }
+
+    // The octree over static bodies as of the last step
+    pub fn static_octree(&self) -> &Octree {
+        &self.static_octree
+    }
+
+    // Comparing every static body's bounds with the ones the octree was
+    // built from is far cheaper than a rebuild, and catches statics that were
+    // added, removed or moved since
+    fn refresh_static_octree(&mut self) {
+        let items = static_items(&self.bodies);
+        let settings_changed = self.static_octree.max_depth() != self.octree_max_depth
+            || self.static_octree.leaf_capacity() != self.octree_leaf_capacity;
+        if settings_changed || items != self.octree_items {
+            // Bodies asleep where a static body was added, moved or removed
+            // wake up, whether it now holds them up or no longer does
+            let mut previous: HashMap<usize, (Vec3, Vec3)> =
+                self.octree_items.iter().map(|&(index, min, max)| (index, (min, max))).collect();
+            let mut changed = Vec::new();
+            for &(index, min, max) in &items {
+                match previous.remove(&index) {
+                    Some(bounds) if bounds == (min, max) => {}
+                    Some(bounds) => changed.extend([bounds, (min, max)]),
+                    None => changed.push((min, max)),
+                }
+            }
+            changed.extend(previous.into_values());
+            for (min, max) in changed {
+                self.wake_overlapping(min, max);
+            }
+
+            self.static_octree = Octree::build(&items, self.octree_max_depth, self.octree_leaf_capacity);
+            self.octree_items = items;
+        }
+    }
+
+    // Indices and world bounds of the rigid bodies
+    fn rigid_bounds(&self) -> (Vec<usize>, Vec<(Vec3, Vec3)>) {
+        let rigid: Vec<usize> =
+            (0..self.bodies.len()).filter(|&index| self.bodies[index].as_rigid_body().is_some()).collect();
+        let bounds = rigid.iter().map(|&index| body_bounds(&self.bodies[index])).collect();
+        (rigid, bounds)
+    }
+
+    // Pairs whose bounds overlap, sorted: rigid pairs from the broadphase and
+    // rigid-static pairs from the octree. Static pairs never need resolving.
+    fn candidate_pairs(&mut self) -> Vec<(usize, usize)> {
+        self.refresh_static_octree();
+        let (rigid, bounds) = self.rigid_bounds();
+        let mut pairs: Vec<(usize, usize)> =
+            self.broadphase.find_pairs(&bounds).into_iter().map(|(a, b)| (rigid[a], rigid[b])).collect();
+        for (&body, &(min, max)) in rigid.iter().zip(&bounds) {
+            let statics = self.static_octree.query_aabb(min, max);
+            pairs.extend(statics.into_iter().map(|other| (body.min(other), body.max(other))));
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+
     pub fn update(&mut self, delta_time: f32) {
         self.accumulator += delta_time;
 
+        let mut stepped = false;
         while self.accumulator >= self.fixed_timestep - f32::EPSILON {
             self.fixed_update();
             self.accumulator -= self.fixed_timestep;
+            stepped = true;
+        }
+
+        // The broadphase last saw the bounds before the solver moved the
+        // bodies; catch it up so queries until the next step can use it
+        if stepped {
+            let (_, bounds) = self.rigid_bounds();
+            self.broadphase.sync(&bounds);
         }
     }
 
     fn fixed_update(&mut self) {
+        self.forget_removed_bodies();
+
         // Apply gravity and update positions only for RigidBody instances
         for body in &mut self.bodies {
-            if let Body::Rigid(rigid_body) = body {
-                let gravity_force = self.gravity * rigid_body.mass;
-                rigid_body.apply_force(gravity_force);
+            if let Body::Rigid(rigid_body) = body
+                && !rigid_body.is_sleeping()
+            {
+                // Bodies of infinite or no mass keep the velocity they were
+                // given; gravity times an infinite mass would be NaN on the
+                // axes it does not act along
+                if rigid_body.inverse_mass() > 0.0 {
+                    let gravity_force = self.gravity * rigid_body.mass;
+                    rigid_body.apply_force(gravity_force);
+                }
                 rigid_body.update(self.fixed_timestep);
             }
         }
 
         // Detect and resolve collisions
-        self.resolve_collisions();
+        let touching = self.resolve_collisions();
+
+        if self.allow_sleeping {
+            self.put_islands_to_sleep(&touching);
+        }
     }
 
-    fn resolve_collisions(&mut self) {
-        let body_count = self.bodies.len();
+    // Bodies touching each other, directly or through other moving bodies,
+    // form an island, and an island only sleeps once every body in it has
+    // stayed slow for the sleep time. A body resting on another keeps it
+    // awake until both settle, so they fall asleep together and neither
+    // wakes the other up again.
+    fn put_islands_to_sleep(&mut self, touching: &[(usize, usize)]) {
+        for body in &mut self.bodies {
+            if let Body::Rigid(rigid_body) = body
+                && !rigid_body.is_sleeping()
+            {
+                rigid_body.track_rest(self.fixed_timestep, self.sleep_linear_threshold, self.sleep_angular_threshold);
+            }
+        }
 
-        // Collect collision data first
-        let mut collisions_to_resolve: Vec<(usize, usize, CollisionInfo)> = Vec::new();
+        // Static bodies and immovable ones standing still do not join
+        // islands, or everything on the same ground would be one island
+        let mut islands: Vec<usize> = (0..self.bodies.len()).collect();
+        for &(i, j) in touching {
+            if !at_rest(&self.bodies[i]) && !at_rest(&self.bodies[j]) {
+                let (root_i, root_j) = (island_root(&mut islands, i), island_root(&mut islands, j));
+                islands[root_i.max(root_j)] = root_i.min(root_j);
+            }
+        }
 
-        for i in 0..body_count {
-            for j in (i + 1)..body_count {
+        // Immovable bodies never sleep, as that would stop them moving
+        let mut restless = vec![false; self.bodies.len()];
+        for index in 0..self.bodies.len() {
+            if let Some(rigid_body) = self.bodies[index].as_rigid_body()
+                && !rigid_body.is_sleeping()
+                && (rigid_body.sleep_timer() < self.sleep_time || rigid_body.inverse_mass() == 0.0)
+            {
+                restless[island_root(&mut islands, index)] = true;
+            }
+        }
+        for index in 0..self.bodies.len() {
+            let root = island_root(&mut islands, index);
+            if let Some(rigid_body) = self.bodies[index].as_rigid_body_mut()
+                && !rigid_body.is_sleeping()
+                && !restless[root]
+            {
+                rigid_body.sleep();
+            }
+        }
+    }
+
+    // Returns the pairs that touched
+    fn resolve_collisions(&mut self) -> Vec<(usize, usize)> {
+        // Only pairs whose bounds overlap can touch
+        let candidate_pairs = self.candidate_pairs();
+
+        // Collect collision data first: every contact manifold of each touching
+        // pair. Nothing moves between bodies that are asleep, static or
+        // standing still, so those pairs wait until one of them is woken by
+        // another pair. Pairs of bodies the solver cannot move are dropped.
+        let mut collisions_to_resolve: Vec<(usize, usize, Vec<ContactManifold>)> = Vec::new();
+        let mut waiting: Vec<(usize, usize)> = candidate_pairs
+            .into_iter()
+            .filter(|&(i, j)| !immovable(&self.bodies[i]) || !immovable(&self.bodies[j]))
+            .collect();
+        loop {
+            let active: Vec<(usize, usize)>;
+            (waiting, active) =
+                waiting.into_iter().partition(|&(i, j)| at_rest(&self.bodies[i]) && at_rest(&self.bodies[j]));
+            if active.is_empty() {
+                break;
+            }
+            for (i, j) in active {
                 let body_i = &self.bodies[i];
                 let body_j = &self.bodies[j];
-
-                let collision_info = match (body_i.shape(), body_j.shape()) {
-                    (
-                        CollisionShape::Sphere { radius: r1 },
-                        CollisionShape::Sphere { radius: r2 },
-                    ) => sphere_vs_sphere(*body_i.position(), *r1, *body_j.position(), *r2),
-                    (
-                        CollisionShape::Sphere { radius },
-                        CollisionShape::Plane { normal, distance },
-                    ) => sphere_vs_plane(*body_i.position(), *radius, *normal, *distance),
-                    (
-                        CollisionShape::Plane { normal, distance },
-                        CollisionShape::Sphere { radius },
-                    ) => {
-                        let mut info =
-                            sphere_vs_plane(*body_j.position(), *radius, *normal, *distance);
-                        if info.is_colliding {
-                            info.normal = -info.normal;
-                        }
-                        info
-                    }
-                    (
-                        CollisionShape::Sphere { radius },
-                        CollisionShape::AABB { min, max },
-                    ) => sphere_vs_aabb(*body_i.position(), *radius, *min, *max),
-                    (
-                        CollisionShape::AABB { min, max },
-                        CollisionShape::Sphere { radius },
-                    ) => {
-                        let mut info = sphere_vs_aabb(*body_j.position(), *radius, *min, *max);
-                        if info.is_colliding {
-                            info.normal = -info.normal;
-                        }
-                        info
-                    }
-                    (
-                        CollisionShape::AABB { min: min1, max: max1 },
-                        CollisionShape::AABB { min: min2, max: max2 },
-                    ) => aabb_vs_aabb(*min1, *max1, *min2, *max2),
-                    _ => continue,
-                };
-
-                if collision_info.is_colliding {
-                    // Only resolve collision if at least one body is Rigid
-                    if body_i.as_rigid_body().is_some() || body_j.as_rigid_body().is_some() {
-                        collisions_to_resolve.push((i, j, collision_info));
+                let manifolds = collide_manifolds(
+                    body_i.shape(),
+                    *body_i.position(),
+                    *body_i.orientation(),
+                    body_j.shape(),
+                    *body_j.position(),
+                    *body_j.orientation(),
+                );
+                if manifolds.is_empty() {
+                    continue;
+                }
+                // Any awake body that can move wakes a sleeping one it touches
+                for (sleeper, other) in [(i, j), (j, i)] {
+                    if self.bodies[sleeper].is_sleeping() && !at_rest(&self.bodies[other]) {
+                        self.bodies[sleeper].wake();
                     }
                 }
+                collisions_to_resolve.push((i, j, manifolds));
             }
         }
+        collisions_to_resolve.sort_unstable_by_key(|&(i, j, _)| (i, j));
+
+        // A pair that touched last step but is neither touching nor waiting
+        // now lost its contact, e.g. one body was moved from under the other.
+        // Both wake, so nothing stays resting on a body no longer there.
+        let lost: Vec<(usize, usize)> = self
+            .contacts
+            .keys()
+            .filter(|pair| {
+                collisions_to_resolve.binary_search_by_key(pair, |&(i, j, _)| (i, j)).is_err()
+                    && waiting.binary_search(pair).is_err()
+            })
+            .collect();
+        for (i, j) in lost {
+            self.bodies[i].wake();
+            self.bodies[j].wake();
+        }
 
-        // Apply resolutions
-        for (i, j, collision_info) in collisions_to_resolve {
-            // Get mutable references to the bodies using split_at_mut
-            let (body1, body2) = if i < j {
-                let (left, right) = self.bodies.split_at_mut(j);
-                (&mut left[i], &mut right[0])
-            } else {
-                let (left, right) = self.bodies.split_at_mut(i);
-                (&mut right[0], &mut left[j])
-            };
-
-            // Extract all necessary immutable data *before* getting mutable RigidBody options
-            let pos_i = *body1.position();
-            let pos_j = *body2.position();
-
-            let vel_i_initial = body1.as_rigid_body().map_or(Vec3::zero(), |b| b.velocity);
-            let vel_j_initial = body2.as_rigid_body().map_or(Vec3::zero(), |b| b.velocity);
+        // Match this step's contacts to the last step's. Pairs still waiting
+        // keep what they had, so a sleeping stack wakes up warm started.
+        self.contacts.update(&collisions_to_resolve, &waiting);
 
-            let mass_i = body1.as_rigid_body().map_or(f32::INFINITY, |b| b.mass);
-            let mass_j = body2.as_rigid_body().map_or(f32::INFINITY, |b| b.mass);
+        let mut states: Vec<VelocityState> = self.bodies.iter().map(VelocityState::new).collect();
+        let mut constraints = self.build_constraints(&collisions_to_resolve, &states);
 
-            let friction_i = body1.friction();
-            let friction_j = body2.friction();
+        // Start from the impulses that held the contacts last step, so resting
+        // bodies do not have to rebuild their support from zero every step
+        for constraint in &constraints {
+            constraint.warm_start(&mut states);
+        }
 
-            // Now get mutable RigidBody options
-            let mut rigid_body_i_option = body1.as_rigid_body_mut();
-            let mut rigid_body_j_option = body2.as_rigid_body_mut();
+        // Projected Gauss-Seidel: sweep every contact in turn, several times,
+        // so contacts sharing a body (a stack, a pile) settle together
+        for _ in 0..self.velocity_iterations {
+            for constraint in &mut constraints {
+                constraint.solve_velocity(&mut states);
+            }
+        }
 
-            // If both are static, no resolution needed
-            if rigid_body_i_option.is_none() && rigid_body_j_option.is_none() {
-                continue;
+        for (body, state) in self.bodies.iter_mut().zip(&states) {
+            state.store(body);
+        }
+        for constraint in &constraints {
+            if let Some(pair) = self.contacts.pair_mut(constraint.body_i, constraint.body_j) {
+                let contact = &mut pair.contacts[constraint.contact];
+                contact.normal_impulse = constraint.normal_impulse;
+                contact.friction_impulse = constraint.tangents[0] * constraint.tangent_impulse[0]
+                    + constraint.tangents[1] * constraint.tangent_impulse[1];
             }
+        }
 
-            // Separate bodies
-            let separation = collision_info.normal * collision_info.penetration_depth;
+        if self.split_impulse {
+            self.apply_split_impulses(&mut constraints, &states);
+        } else {
+            self.correct_positions(&constraints, &states);
+        }
+        collisions_to_resolve.iter().map(|&(i, j, _)| (i, j)).collect()
+    }
 
-            if let Some(rb_i) = rigid_body_i_option.as_mut() {
-                let move_amount = if mass_j.is_infinite() {
-                    separation
-                } else {
-                    separation * (mass_j / (mass_i + mass_j))
+    // One constraint per cached contact, in the order the pairs were found
+    fn build_constraints(
+        &self,
+        collisions: &[(usize, usize, Vec<ContactManifold>)],
+        states: &[VelocityState],
+    ) -> Vec<ContactConstraint> {
+        let mut constraints = Vec::new();
+        for &(i, j, _) in collisions {
+            let Some(pair) = self.contacts.pair(i, j) else {
+                continue;
+            };
+            let (body_i, body_j) = (&self.bodies[i], &self.bodies[j]);
+            let com_i = body_i.as_rigid_body().map_or(*body_i.position(), |b| b.center_of_mass_world());
+            let com_j = body_j.as_rigid_body().map_or(*body_j.position(), |b| b.center_of_mass_world());
+            let friction = (body_i.friction() + body_j.friction()) * 0.5; // Average friction
+
+            for (index, contact) in pair.contacts.iter().enumerate() {
+                // Lever arms from each centre of mass to the contact point
+                let r_i = contact.point - com_i;
+                let r_j = contact.point - com_j;
+                let inverse_effective_mass = |direction: Vec3| {
+                    states[i].inverse_effective_mass(direction, r_i) + states[j].inverse_effective_mass(direction, r_j)
                 };
-                rb_i.position = pos_i - move_amount;
-            }
-            if let Some(rb_j) = rigid_body_j_option.as_mut() {
-                let move_amount = if mass_i.is_infinite() {
-                    separation
+
+                // Only contacts approaching faster than the threshold bounce
+                let velocity_along_normal =
+                    (states[i].point_velocity(r_i) - states[j].point_velocity(r_j)).dot(&contact.normal);
+                let restitution =
+                    if velocity_along_normal > self.restitution_threshold { self.restitution } else { 0.0 };
+                let tangents = tangent_basis(contact.normal);
+                // Last step's friction impulse may lie along other tangents
+                let tangent_impulse = tangents.map(|tangent| contact.friction_impulse.dot(&tangent));
+                // With split impulses the overlap is left to the pseudo-velocities
+                let (bias, pseudo_target_velocity) = if self.split_impulse {
+                    let overlap = (contact.depth - self.split_impulse_slop).max(0.0);
+                    (0.0, -self.split_impulse_factor * overlap / self.fixed_timestep)
                 } else {
-                    separation * (mass_i / (mass_i + mass_j))
+                    let overlap = (contact.depth - PENETRATION_SLOP).max(0.0);
+                    (PENETRATION_BIAS * overlap / self.fixed_timestep, 0.0)
                 };
-                rb_j.position = pos_j + move_amount;
+                constraints.push(ContactConstraint {
+                    body_i: i,
+                    body_j: j,
+                    contact: index,
+                    normal: contact.normal,
+                    tangents,
+                    r_i,
+                    r_j,
+                    depth: contact.depth,
+                    friction,
+                    normal_mass: 1.0 / inverse_effective_mass(contact.normal),
+                    tangent_mass: tangents.map(|tangent| 1.0 / inverse_effective_mass(tangent)),
+                    target_velocity: -restitution * velocity_along_normal - bias,
+                    pseudo_target_velocity,
+                    normal_impulse: contact.normal_impulse * self.warm_start_factor,
+                    tangent_impulse: tangent_impulse.map(|impulse| impulse * self.warm_start_factor),
+                    pseudo_impulse: 0.0,
+                });
             }
+        }
+        constraints
+    }
 
-            // Calculate relative velocity along collision normal
-            let relative_velocity = vel_i_initial - vel_j_initial;
-            let velocity_along_normal = relative_velocity.dot(&collision_info.normal);
+    // Split-impulse pass: the position iterations solve the contacts again,
+    // this time for pseudo-velocities that separate overlapping pairs, and
+    // the bodies are moved by those for one step. The real velocities were
+    // already stored, so pushing bodies apart adds no energy.
+    fn apply_split_impulses(&mut self, constraints: &mut [ContactConstraint], states: &[VelocityState]) {
+        let mut pseudo_states: Vec<VelocityState> = states.iter().map(VelocityState::at_rest).collect();
+        for _ in 0..self.position_iterations {
+            for constraint in constraints.iter_mut() {
+                constraint.solve_pseudo_velocity(&mut pseudo_states);
+            }
+        }
 
-            // If bodies are separating (velocity_along_normal < 0), skip impulse resolution.
-            if velocity_along_normal < 0.0 {
-                continue;
+        for (body, pseudo) in self.bodies.iter_mut().zip(&pseudo_states) {
+            if let Some(rigid_body) = body.as_rigid_body_mut()
+                && !rigid_body.is_sleeping()
+            {
+                let center_of_mass = rigid_body.center_of_mass_world() + pseudo.velocity * self.fixed_timestep;
+                rigid_body.orientation = rigid_body.orientation.integrate(pseudo.angular_velocity, self.fixed_timestep);
+                rigid_body.position = center_of_mass - rigid_body.orientation.rotate(rigid_body.center_of_mass);
             }
+        }
+    }
 
-            // Calculate normal impulse (with Baumgarte stabilization for penetration)
-            let e = self.restitution;
-            const PENETRATION_BIAS: f32 = 0.2;
-            let impulse_magnitude_normal = (-(1.0 + e) * velocity_along_normal
-                - PENETRATION_BIAS * collision_info.penetration_depth / self.fixed_timestep)
-                / (1.0 / mass_i + 1.0 / mass_j);
-            let normal_impulse = collision_info.normal * impulse_magnitude_normal;
-
-            // Apply normal impulse
-            if let Some(rb_i) = rigid_body_i_option.as_mut() {
-                rb_i.velocity = vel_i_initial + normal_impulse * (1.0 / mass_i);
-            }
-            if let Some(rb_j) = rigid_body_j_option.as_mut() {
-                rb_j.velocity = vel_j_initial - normal_impulse * (1.0 / mass_j);
-            }
-
-            // --- Friction Calculation ---
-            let combined_friction = (friction_i + friction_j) * 0.5; // Average friction
-
-            // Recalculate relative velocity after normal impulse
-            let current_vel_i = rigid_body_i_option
-                .as_ref()
-                .map_or(vel_i_initial, |b| b.velocity);
-            let current_vel_j = rigid_body_j_option
-                .as_ref()
-                .map_or(vel_j_initial, |b| b.velocity);
-            let relative_velocity_after_normal = current_vel_i - current_vel_j;
-
-            // Calculate tangential velocity
-            let tangent_direction = relative_velocity_after_normal
-                - collision_info.normal
-                    * relative_velocity_after_normal.dot(&collision_info.normal);
-            let tangent_magnitude = tangent_direction.magnitude();
-
-            if tangent_magnitude > f32::EPSILON {
-                // Avoid division by zero
-                let unit_tangent = tangent_direction.normalize();
-
-                // Calculate tangential impulse magnitude
-                let impulse_magnitude_tangent = -relative_velocity_after_normal.dot(&unit_tangent)
-                    / (1.0 / mass_i + 1.0 / mass_j);
-
-                // Apply Coulomb's friction model
-                let friction_impulse_magnitude = impulse_magnitude_tangent.clamp(
-                    -impulse_magnitude_normal.abs() * combined_friction,
-                    impulse_magnitude_normal.abs() * combined_friction,
-                );
+    // Gauss-Seidel on positions: each pass measures every contact's remaining
+    // overlap from the corrections made so far and moves the pair apart along
+    // the normal, split by mass so the lighter body moves further. Resting
+    // contacts only remove a fraction per pass, since pushing them fully
+    // apart lifts one side of a box off its support and sets stacks rocking;
+    // bodies found deep inside each other are separated outright.
+    fn correct_positions(&mut self, constraints: &[ContactConstraint], states: &[VelocityState]) {
+        let mut offsets = vec![Vec3::zero(); self.bodies.len()];
+
+        for _ in 0..self.position_iterations {
+            for constraint in constraints {
+                let (i, j) = (constraint.body_i, constraint.body_j);
+                let inverse_mass = states[i].inverse_mass + states[j].inverse_mass;
+                let separation = constraint.normal.dot(&(offsets[j] - offsets[i])) - constraint.depth;
+                if separation >= 0.0 || inverse_mass == 0.0 {
+                    continue;
+                }
 
-                let friction_impulse = unit_tangent * friction_impulse_magnitude;
+                let fraction = if constraint.depth > DEEP_PENETRATION { 1.0 } else { POSITION_CORRECTION };
+                let push = constraint.normal * (-separation * fraction / inverse_mass);
+                offsets[i] -= push * states[i].inverse_mass;
+                offsets[j] += push * states[j].inverse_mass;
+            }
+        }
 
-                // Apply friction impulse
-                if let Some(rb_i) = rigid_body_i_option.as_mut() {
-                    rb_i.velocity += friction_impulse * (1.0 / mass_i);
-                }
-                if let Some(rb_j) = rigid_body_j_option.as_mut() {
-                    rb_j.velocity -= friction_impulse * (1.0 / mass_j);
-                }
+        for (body, offset) in self.bodies.iter_mut().zip(offsets) {
+            if let Some(rigid_body) = body.as_rigid_body_mut() {
+                rigid_body.position += offset;
             }
         }
     }
 }
+
+// Baumgarte stabilization: fraction of the penetration beyond the slop
+// turned into separating velocity each step. The slop keeps resting contacts
+// from being pushed apart a little every step.
+const PENETRATION_BIAS: f32 = 0.2;
+const PENETRATION_SLOP: f32 = 0.02;
+
+// Fraction of a resting contact's overlap removed per position iteration,
+// and the depth past which an overlap is removed at once instead
+const POSITION_CORRECTION: f32 = 0.2;
+const DEEP_PENETRATION: f32 = 0.1;
+
+// One contact between bodies `body_i` and `body_j`, prepared for the solver
+struct ContactConstraint {
+    body_i: usize,
+    body_j: usize,
+    // Index into the pair's cached contacts
+    contact: usize,
+    normal: Vec3,
+    tangents: [Vec3; 2],
+    r_i: Vec3,
+    r_j: Vec3,
+    depth: f32,
+    friction: f32,
+    normal_mass: f32,
+    tangent_mass: [f32; 2],
+    // Normal velocity the impulses aim for: the bounce plus the Baumgarte term
+    target_velocity: f32,
+    // Separating pseudo-velocity the split-impulse pass aims for
+    pseudo_target_velocity: f32,
+    // Accumulated impulses along the normal (never positive, so contacts
+    // only push) and the two tangents
+    normal_impulse: f32,
+    tangent_impulse: [f32; 2],
+    // Accumulated split impulse, clamped like the normal one
+    pseudo_impulse: f32,
+}
+
+impl ContactConstraint {
+    // Applies the accumulated impulses the constraint starts with
+    fn warm_start(&self, states: &mut [VelocityState]) {
+        let impulse = self.normal * self.normal_impulse
+            + self.tangents[0] * self.tangent_impulse[0]
+            + self.tangents[1] * self.tangent_impulse[1];
+        self.apply(states, impulse);
+    }
+
+    // One Gauss-Seidel step: the normal impulse, clamped so the total only
+    // ever pushes apart, then Coulomb friction clamped against it
+    fn solve_velocity(&mut self, states: &mut [VelocityState]) {
+        let (r_i, r_j) = (self.r_i, self.r_j);
+        let relative_velocity =
+            |states: &[VelocityState]| states[self.body_i].point_velocity(r_i) - states[self.body_j].point_velocity(r_j);
+
+        let velocity_along_normal = relative_velocity(states).dot(&self.normal);
+        let delta = (self.target_velocity - velocity_along_normal) * self.normal_mass;
+        let accumulated = (self.normal_impulse + delta).min(0.0);
+        let impulse = self.normal * (accumulated - self.normal_impulse);
+        self.normal_impulse = accumulated;
+        self.apply(states, impulse);
+
+        let max_friction = self.normal_impulse.abs() * self.friction;
+        for axis in 0..2 {
+            let tangent = self.tangents[axis];
+            let delta = -relative_velocity(states).dot(&tangent) * self.tangent_mass[axis];
+            let accumulated = (self.tangent_impulse[axis] + delta).clamp(-max_friction, max_friction);
+            let impulse = tangent * (accumulated - self.tangent_impulse[axis]);
+            self.tangent_impulse[axis] = accumulated;
+            self.apply(states, impulse);
+        }
+    }
+
+    // Gauss-Seidel step on pseudo-velocities, without bounce or friction
+    fn solve_pseudo_velocity(&mut self, pseudo_states: &mut [VelocityState]) {
+        let velocity_along_normal = (pseudo_states[self.body_i].point_velocity(self.r_i)
+            - pseudo_states[self.body_j].point_velocity(self.r_j))
+        .dot(&self.normal);
+        let delta = (self.pseudo_target_velocity - velocity_along_normal) * self.normal_mass;
+        let accumulated = (self.pseudo_impulse + delta).min(0.0);
+        let impulse = self.normal * (accumulated - self.pseudo_impulse);
+        self.pseudo_impulse = accumulated;
+        self.apply(pseudo_states, impulse);
+    }
+
+    // Impulse on body i at its contact point, and the opposite one on body j
+    fn apply(&self, states: &mut [VelocityState], impulse: Vec3) {
+        states[self.body_i].apply_impulse(impulse, self.r_i);
+        states[self.body_j].apply_impulse(-impulse, self.r_j);
+    }
+}
+
+// Velocity state of one body during the solve; static and sleeping bodies
+// have zero inverse mass and inertia, so impulses leave them alone
+struct VelocityState {
+    velocity: Vec3,
+    angular_velocity: Vec3,
+    inverse_mass: f32,
+    inverse_inertia: Mat3,
+}
+
+impl VelocityState {
+    fn new(body: &Body) -> Self {
+        let rigid = body.as_rigid_body().filter(|b| !b.is_sleeping());
+        Self {
+            velocity: rigid.map_or(Vec3::zero(), |b| b.velocity),
+            angular_velocity: rigid.map_or(Vec3::zero(), |b| b.angular_velocity),
+            inverse_mass: rigid.map_or(0.0, |b| b.inverse_mass()),
+            inverse_inertia: contact_inverse_inertia(body),
+        }
+    }
+
+    // The same body standing still, to accumulate pseudo-velocities in
+    fn at_rest(&self) -> Self {
+        Self {
+            velocity: Vec3::zero(),
+            angular_velocity: Vec3::zero(),
+            inverse_mass: self.inverse_mass,
+            inverse_inertia: self.inverse_inertia,
+        }
+    }
+
+    fn point_velocity(&self, r: Vec3) -> Vec3 {
+        self.velocity + self.angular_velocity.cross(&r)
+    }
+
+    // This body's share of the pair's inverse effective mass along a
+    // direction through the contact point: 1/m + d . ((I^-1 (r x d)) x r)
+    fn inverse_effective_mass(&self, direction: Vec3, r: Vec3) -> f32 {
+        self.inverse_mass + direction.dot(&(self.inverse_inertia * r.cross(&direction)).cross(&r))
+    }
+
+    fn apply_impulse(&mut self, impulse: Vec3, r: Vec3) {
+        self.velocity += impulse * self.inverse_mass;
+        self.angular_velocity += self.inverse_inertia * r.cross(&impulse);
+    }
+
+    fn store(&self, body: &mut Body) {
+        if let Some(rigid_body) = body.as_rigid_body_mut()
+            && !rigid_body.is_sleeping()
+        {
+            rigid_body.velocity = self.velocity;
+            rigid_body.angular_velocity = self.angular_velocity;
+        }
+    }
+}
+
+// Follows an island's links to the body that stands for it
+fn island_root(islands: &mut [usize], mut index: usize) -> usize {
+    while islands[index] != index {
+        islands[index] = islands[islands[index]];
+        index = islands[index];
+    }
+    index
+}
+
+// Static, asleep, or a rigid body nothing can move standing still
+fn at_rest(body: &Body) -> bool {
+    match body {
+        Body::Rigid(rigid_body) => {
+            rigid_body.is_sleeping()
+                || (rigid_body.inverse_mass() == 0.0
+                    && rigid_body.velocity == Vec3::zero()
+                    && rigid_body.angular_velocity == Vec3::zero())
+        }
+        Body::Static(_) => true,
+    }
+}
+
+// Static, or a rigid body of infinite or no mass
+fn immovable(body: &Body) -> bool {
+    body.as_rigid_body().is_none_or(|rigid_body| rigid_body.inverse_mass() == 0.0)
+}
+
+// Index and world bounds of each static body
+fn static_items(bodies: &[Body]) -> Vec<(usize, Vec3, Vec3)> {
+    let statics = bodies.iter().enumerate().filter(|(_, body)| body.as_static_body().is_some());
+    statics
+        .map(|(index, body)| {
+            let (min, max) = body_bounds(body);
+            (index, min, max)
+        })
+        .collect()
+}
+
+fn body_bounds(body: &Body) -> (Vec3, Vec3) {
+    body.shape().world_bounds(*body.position(), *body.orientation())
+}
+
+// Two unit tangents perpendicular to the normal and to each other
+fn tangent_basis(normal: Vec3) -> [Vec3; 2] {
+    let helper = if normal.x.abs() < 0.57 { Vec3::new(1.0, 0.0, 0.0) } else { Vec3::new(0.0, 1.0, 0.0) };
+    let first = normal.cross(&helper).normalize();
+    [first, normal.cross(&first)]
+}
+
+// World-space inverse inertia used when resolving contacts. An AABB keeps its
+// extents axis-aligned whatever the body's orientation, so letting contacts
+// spin it would only make it roll on a face it can never tip over.
+fn contact_inverse_inertia(body: &Body) -> Mat3 {
+    match (body.as_rigid_body().filter(|b| !b.is_sleeping()), body.shape()) {
+        (Some(_), CollisionShape::AABB { .. }) | (None, _) => Mat3::zero(),
+        (Some(rigid_body), _) => rigid_body.inverse_inertia_world(),
+    }
+}
@@ -0,0 +1,150 @@
+use crate::broadphase::{is_bounded, overlaps, Broadphase};
+use crate::vector::Vec3;
+use std::collections::HashMap;
+
+// Uniform grid of cubic cells, hashed by their integer coordinates so only
+// occupied cells take memory. Every body is entered in each cell its bounds
+// touch, and only bodies sharing a cell are compared. Suits many bodies of
+// about the same size, with the cell size a little larger than a typical
+// body. Bodies that would cover too many cells, and unbounded ones such as
+// planes, are kept aside and checked against every body instead. The grid
+// is only filled again when the bounds it is given change. Queries read the
+// cells as long as they were filled from the same bounds, as they are after
+// a step, and check every body otherwise.
+#[derive(Debug, Clone)]
+pub struct SpatialHashGrid {
+    cell_size: f32,
+    cells: HashMap<Cell, Vec<usize>>,
+    oversized: Vec<usize>,
+    // Bounds the cells were filled from
+    bounds: Vec<(Vec3, Vec3)>,
+}
+
+// Integer coordinates of a cell
+type Cell = (i32, i32, i32);
+
+const DEFAULT_CELL_SIZE: f32 = 1.0;
+
+// Bodies covering more cells than this are not bucketed
+const MAX_CELLS_PER_BODY: i64 = 512;
+
+impl Default for SpatialHashGrid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SpatialHashGrid {
+    pub fn new() -> Self {
+        Self::with_cell_size(DEFAULT_CELL_SIZE)
+    }
+
+    pub fn with_cell_size(cell_size: f32) -> Self {
+        assert!(cell_size > 0.0, "Cell size must be positive");
+        Self { cell_size, cells: HashMap::new(), oversized: Vec::new(), bounds: Vec::new() }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    // Number of cells holding at least one body after the last call
+    pub fn occupied_cells(&self) -> usize {
+        self.cells.len()
+    }
+
+    // Bodies that were too large to bucket in the last call, unbounded ones included
+    pub fn oversized(&self) -> &[usize] {
+        &self.oversized
+    }
+
+    // Range of cells a box touches, or None if it is unbounded or spans too many
+    fn cell_range(&self, min: Vec3, max: Vec3) -> Option<(Cell, Cell)> {
+        if !is_bounded(min, max) {
+            return None;
+        }
+        let cell = |v: f32| (v / self.cell_size).floor() as i32;
+        let low = (cell(min.x), cell(min.y), cell(min.z));
+        let high = (cell(max.x), cell(max.y), cell(max.z));
+        let span = |a: i32, b: i32| b as i64 - a as i64 + 1;
+        let count = span(low.0, high.0) * span(low.1, high.1) * span(low.2, high.2);
+        (count <= MAX_CELLS_PER_BODY).then_some((low, high))
+    }
+
+    // Refills the cells if the bounds differ from the ones they hold
+    // Refills the cells, keeping their allocations
+    fn rebuild(&mut self, bounds: &[(Vec3, Vec3)]) {
+        self.cells.values_mut().for_each(Vec::clear);
+        self.oversized.clear();
+        for (body, &(min, max)) in bounds.iter().enumerate() {
+            let Some((low, high)) = self.cell_range(min, max) else {
+                self.oversized.push(body);
+                continue;
+            };
+            for x in low.0..=high.0 {
+                for y in low.1..=high.1 {
+                    for z in low.2..=high.2 {
+                        self.cells.entry((x, y, z)).or_default().push(body);
+                    }
+                }
+            }
+        }
+        self.cells.retain(|_, bodies| !bodies.is_empty());
+    }
+}
+
+impl Broadphase for SpatialHashGrid {
+    fn find_pairs(&mut self, bounds: &[(Vec3, Vec3)]) -> Vec<(usize, usize)> {
+        self.sync(bounds);
+
+        let mut pairs = Vec::new();
+        // Bodies are pushed in index order, so each bucket is sorted
+        for bodies in self.cells.values() {
+            for (slot, &i) in bodies.iter().enumerate() {
+                for &j in &bodies[slot + 1..] {
+                    if overlaps(bounds[i].0, bounds[i].1, bounds[j].0, bounds[j].1) {
+                        pairs.push((i, j));
+                    }
+                }
+            }
+        }
+        for &body in &self.oversized {
+            for other in (0..bounds.len()).filter(|&other| other != body) {
+                if overlaps(bounds[body].0, bounds[body].1, bounds[other].0, bounds[other].1) {
+                    pairs.push((body.min(other), body.max(other)));
+                }
+            }
+        }
+        // Bodies sharing several cells are found once per cell
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+
+    fn sync(&mut self, bounds: &[(Vec3, Vec3)]) {
+        if self.bounds != bounds {
+            self.rebuild(bounds);
+            self.bounds = bounds.to_vec();
+        }
+    }
+
+    fn query_bounds(&self, bounds: &[(Vec3, Vec3)], min: Vec3, max: Vec3) -> Vec<usize> {
+        let cells = self.cell_range(min, max).filter(|_| self.bounds == bounds);
+        let Some((low, high)) = cells else {
+            return (0..bounds.len()).filter(|&body| overlaps(bounds[body].0, bounds[body].1, min, max)).collect();
+        };
+
+        let mut found = self.oversized.clone();
+        for x in low.0..=high.0 {
+            for y in low.1..=high.1 {
+                for z in low.2..=high.2 {
+                    found.extend(self.cells.get(&(x, y, z)).into_iter().flatten());
+                }
+            }
+        }
+        found.retain(|&body| overlaps(bounds[body].0, bounds[body].1, min, max));
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+}
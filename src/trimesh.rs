@@ -0,0 +1,227 @@
+use crate::broadphase::{component_max, component_min, overlaps};
+use crate::vector::Vec3;
+use std::collections::HashMap;
+
+// Static triangle mesh in its body's local frame, with a bounding volume
+// hierarchy over the triangles so a contact query only visits the few
+// triangles near the other shape
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriMesh {
+    vertices: Vec<Vec3>,
+    triangles: Vec<[usize; 3]>,
+    // Per triangle, whether each edge (v0-v1, v1-v2, v2-v0) can produce an
+    // edge contact. Edges shared with a coplanar or concave neighbour are
+    // inactive: touching one is really touching the neighbouring face, and
+    // treating it as an edge would make objects bump as they cross it.
+    active_edges: Vec<[bool; 3]>,
+    nodes: Vec<BvhNode>,
+    // Triangle indices in the order the BVH leaves refer to them
+    leaf_triangles: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct BvhNode {
+    min: Vec3,
+    max: Vec3,
+    content: BvhContent,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum BvhContent {
+    // Range into `leaf_triangles`
+    Leaf { first: usize, count: usize },
+    Branch { left: usize, right: usize },
+}
+
+const LEAF_SIZE: usize = 4;
+
+// A neighbour whose far vertex is off this triangle's plane by less than this
+// fraction of its distance counts as coplanar
+const INTERNAL_EDGE_TOLERANCE: f32 = 1.0e-3;
+
+impl TriMesh {
+    // Triangles are wound counter-clockwise when seen from the side objects
+    // rest on. Triangles sharing an edge are matched by vertex position, so
+    // meshes with duplicated vertices (e.g. split for UVs) still connect.
+    pub fn new(vertices: Vec<Vec3>, triangles: Vec<[usize; 3]>) -> Self {
+        let active_edges = compute_active_edges(&vertices, &triangles);
+
+        let mut mesh = Self {
+            vertices,
+            triangles,
+            active_edges,
+            nodes: Vec::new(),
+            leaf_triangles: Vec::new(),
+        };
+        if !mesh.triangles.is_empty() {
+            let mut order: Vec<usize> = (0..mesh.triangles.len()).collect();
+            mesh.build_node(&mut order, 0);
+            mesh.leaf_triangles = order;
+        }
+        mesh
+    }
+
+    pub fn vertices(&self) -> &[Vec3] {
+        &self.vertices
+    }
+
+    pub fn triangles(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+
+    pub fn triangle(&self, index: usize) -> [Vec3; 3] {
+        let [a, b, c] = self.triangles[index];
+        [self.vertices[a], self.vertices[b], self.vertices[c]]
+    }
+
+    pub fn active_edges(&self, index: usize) -> [bool; 3] {
+        self.active_edges[index]
+    }
+
+    // Local-space bounds of the whole mesh
+    pub fn bounds(&self) -> Option<(Vec3, Vec3)> {
+        self.nodes.first().map(|root| (root.min, root.max))
+    }
+
+    // Indices of the triangles whose bounds overlap the local-space box
+    pub fn query_aabb(&self, min: Vec3, max: Vec3) -> Vec<usize> {
+        let mut found = Vec::new();
+        if self.nodes.is_empty() {
+            return found;
+        }
+
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !overlaps(node.min, node.max, min, max) {
+                continue;
+            }
+            match node.content {
+                BvhContent::Leaf { first, count } => {
+                    for &triangle in &self.leaf_triangles[first..first + count] {
+                        let (tri_min, tri_max) = triangle_bounds(&self.triangle(triangle));
+                        if overlaps(tri_min, tri_max, min, max) {
+                            found.push(triangle);
+                        }
+                    }
+                }
+                BvhContent::Branch { left, right } => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+            }
+        }
+        found
+    }
+
+    // Top-down build: split the triangles at the median centroid along the
+    // longest axis of their centroid bounds. `order` is the slice of
+    // triangle indices starting at `first` in the final leaf order.
+    fn build_node(&mut self, order: &mut [usize], first: usize) -> usize {
+        let (mut min, mut max) = triangle_bounds(&self.triangle(order[0]));
+        let (mut centroid_min, mut centroid_max) = (Vec3::zero(), Vec3::zero());
+        for (i, &triangle) in order.iter().enumerate() {
+            let (tri_min, tri_max) = triangle_bounds(&self.triangle(triangle));
+            min = component_min(min, tri_min);
+            max = component_max(max, tri_max);
+            let centroid = self.centroid(triangle);
+            if i == 0 {
+                (centroid_min, centroid_max) = (centroid, centroid);
+            } else {
+                centroid_min = component_min(centroid_min, centroid);
+                centroid_max = component_max(centroid_max, centroid);
+            }
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(BvhNode { min, max, content: BvhContent::Leaf { first, count: order.len() } });
+        if order.len() <= LEAF_SIZE {
+            return index;
+        }
+
+        let extent = centroid_max - centroid_min;
+        let axis_value = |v: Vec3| {
+            if extent.x >= extent.y && extent.x >= extent.z {
+                v.x
+            } else if extent.y >= extent.z {
+                v.y
+            } else {
+                v.z
+            }
+        };
+        order.sort_by(|&i, &j| axis_value(self.centroid(i)).total_cmp(&axis_value(self.centroid(j))));
+
+        let middle = order.len() / 2;
+        let (left_order, right_order) = order.split_at_mut(middle);
+        let left = self.build_node(left_order, first);
+        let right = self.build_node(right_order, first + middle);
+        self.nodes[index].content = BvhContent::Branch { left, right };
+        index
+    }
+
+    fn centroid(&self, index: usize) -> Vec3 {
+        let [a, b, c] = self.triangle(index);
+        (a + b + c) * (1.0 / 3.0)
+    }
+}
+
+// Exact bit pattern of a vertex position, for matching shared edges
+type VertexKey = [u32; 3];
+
+// Static geometry made of triangles, queried in its own local frame
+pub(crate) trait TriangleSource {
+    // Triangles whose bounds overlap the local-space box, each with its
+    // active edges
+    fn triangles_near(&self, min: Vec3, max: Vec3) -> Vec<([Vec3; 3], [bool; 3])>;
+}
+
+impl TriangleSource for TriMesh {
+    fn triangles_near(&self, min: Vec3, max: Vec3) -> Vec<([Vec3; 3], [bool; 3])> {
+        self.query_aabb(min, max)
+            .into_iter()
+            .map(|index| (self.triangle(index), self.active_edges(index)))
+            .collect()
+    }
+}
+
+fn compute_active_edges(vertices: &[Vec3], triangles: &[[usize; 3]]) -> Vec<[bool; 3]> {
+    let key = |index: usize| -> VertexKey {
+        let v = vertices[index];
+        [v.x.to_bits(), v.y.to_bits(), v.z.to_bits()]
+    };
+
+    // Undirected edge (by endpoint position) -> every (triangle, edge) using it
+    let mut edges: HashMap<(VertexKey, VertexKey), Vec<(usize, usize)>> = HashMap::new();
+    for (t, triangle) in triangles.iter().enumerate() {
+        for k in 0..3 {
+            let (p, q) = (key(triangle[k]), key(triangle[(k + 1) % 3]));
+            edges.entry(if p < q { (p, q) } else { (q, p) }).or_default().push((t, k));
+        }
+    }
+
+    let mut active = vec![[true; 3]; triangles.len()];
+    for users in edges.values() {
+        // Boundary edges and non-manifold fans stay active
+        let &[(t0, k0), (t1, k1)] = users.as_slice() else {
+            continue;
+        };
+        let triangle = |t: usize| triangles[t].map(|i| vertices[i]);
+        active[t0][k0] = is_convex_edge(triangle(t0), vertices[triangles[t1][(k1 + 2) % 3]]);
+        active[t1][k1] = is_convex_edge(triangle(t1), vertices[triangles[t0][(k0 + 2) % 3]]);
+    }
+    active
+}
+
+// An edge is convex when the neighbouring triangle's far vertex lies clearly
+// behind this triangle's plane
+pub(crate) fn is_convex_edge(triangle: [Vec3; 3], neighbour_far_vertex: Vec3) -> bool {
+    let [a, b, c] = triangle;
+    let normal = (b - a).cross(&(c - a)).normalize();
+    let offset = neighbour_far_vertex - a;
+    normal.dot(&offset) < -INTERNAL_EDGE_TOLERANCE * offset.magnitude()
+}
+
+pub(crate) fn triangle_bounds(triangle: &[Vec3; 3]) -> (Vec3, Vec3) {
+    let [a, b, c] = *triangle;
+    (component_min(component_min(a, b), c), component_max(component_max(a, b), c))
+}
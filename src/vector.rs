@@ -117,3 +117,288 @@ impl Neg for Vec3 {
         }
     }
 }
+
+// Unit quaternion representing a 3D orientation (w + xi + yj + zk)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quat {
+    pub w: f32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Quat {
+    pub fn new(w: f32, x: f32, y: f32, z: f32) -> Self {
+        Self { w, x, y, z }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(1.0, 0.0, 0.0, 0.0)
+    }
+
+    // Rotation of `angle` radians around `axis` (does not need to be unit length)
+    pub fn from_axis_angle(axis: Vec3, angle: f32) -> Self {
+        let axis = axis.normalize();
+        let half = angle * 0.5;
+        let s = half.sin();
+        Self::new(half.cos(), axis.x * s, axis.y * s, axis.z * s)
+    }
+
+    pub fn magnitude(&self) -> f32 {
+        self.magnitude_squared().sqrt()
+    }
+
+    pub fn magnitude_squared(&self) -> f32 {
+        self.w * self.w + self.x * self.x + self.y * self.y + self.z * self.z
+    }
+
+    pub fn normalize(&self) -> Quat {
+        let mag = self.magnitude();
+        if mag == 0.0 {
+            Quat::identity()
+        } else {
+            Quat::new(self.w / mag, self.x / mag, self.y / mag, self.z / mag)
+        }
+    }
+
+    pub fn conjugate(&self) -> Quat {
+        Quat::new(self.w, -self.x, -self.y, -self.z)
+    }
+
+    // Inverse rotation; for unit quaternions this is the conjugate
+    pub fn inverse(&self) -> Quat {
+        let mag_sq = self.magnitude_squared();
+        if mag_sq == 0.0 {
+            Quat::identity()
+        } else {
+            let c = self.conjugate();
+            Quat::new(c.w / mag_sq, c.x / mag_sq, c.y / mag_sq, c.z / mag_sq)
+        }
+    }
+
+    pub fn dot(&self, other: &Quat) -> f32 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    // Rotates `v` by this quaternion: q * v * q^-1
+    pub fn rotate(&self, v: Vec3) -> Vec3 {
+        // Optimised form of the sandwich product: v + 2w(u x v) + 2u x (u x v)
+        let u = Vec3::new(self.x, self.y, self.z);
+        let t = u.cross(&v) * 2.0;
+        v + t * self.w + u.cross(&t)
+    }
+
+    // Spherical linear interpolation along the shortest arc
+    pub fn slerp(&self, other: &Quat, t: f32) -> Quat {
+        let mut end = *other;
+        let mut cos_theta = self.dot(other);
+
+        // q and -q are the same rotation; take the short way around
+        if cos_theta < 0.0 {
+            end = Quat::new(-end.w, -end.x, -end.y, -end.z);
+            cos_theta = -cos_theta;
+        }
+
+        // Nearly parallel: fall back to normalised lerp to avoid dividing by ~0
+        if cos_theta > 0.9995 {
+            return Quat::new(
+                self.w + (end.w - self.w) * t,
+                self.x + (end.x - self.x) * t,
+                self.y + (end.y - self.y) * t,
+                self.z + (end.z - self.z) * t,
+            )
+            .normalize();
+        }
+
+        let theta = cos_theta.acos();
+        let sin_theta = theta.sin();
+        let a = ((1.0 - t) * theta).sin() / sin_theta;
+        let b = (t * theta).sin() / sin_theta;
+
+        Quat::new(
+            self.w * a + end.w * b,
+            self.x * a + end.x * b,
+            self.y * a + end.y * b,
+            self.z * a + end.z * b,
+        )
+    }
+
+    // Advances the orientation by a world-space angular velocity over `dt`:
+    // q' = q + 0.5 * dt * (0, omega) * q, renormalised to stay a unit rotation
+    pub fn integrate(&self, angular_velocity: Vec3, dt: f32) -> Quat {
+        let omega = Quat::new(0.0, angular_velocity.x, angular_velocity.y, angular_velocity.z);
+        let spin = omega * *self;
+        let h = 0.5 * dt;
+        Quat::new(
+            self.w + spin.w * h,
+            self.x + spin.x * h,
+            self.y + spin.y * h,
+            self.z + spin.z * h,
+        )
+        .normalize()
+    }
+}
+
+impl Mul for Quat {
+    type Output = Quat;
+
+    // Hamilton product: applying the result rotates by `other` first, then `self`
+    fn mul(self, other: Quat) -> Quat {
+        Quat {
+            w: self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+            x: self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            y: self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            z: self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+        }
+    }
+}
+
+impl Mul<Vec3> for Quat {
+    type Output = Vec3;
+
+    fn mul(self, v: Vec3) -> Vec3 {
+        self.rotate(v)
+    }
+}
+
+// Row-major 3x3 matrix, used for inertia tensors and rotations
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat3 {
+    pub rows: [Vec3; 3],
+}
+
+impl Mat3 {
+    pub fn new(row0: Vec3, row1: Vec3, row2: Vec3) -> Self {
+        Self { rows: [row0, row1, row2] }
+    }
+
+    pub fn zero() -> Self {
+        Self::new(Vec3::zero(), Vec3::zero(), Vec3::zero())
+    }
+
+    pub fn identity() -> Self {
+        Self::from_diagonal(Vec3::new(1.0, 1.0, 1.0))
+    }
+
+    pub fn from_diagonal(diagonal: Vec3) -> Self {
+        Self::new(
+            Vec3::new(diagonal.x, 0.0, 0.0),
+            Vec3::new(0.0, diagonal.y, 0.0),
+            Vec3::new(0.0, 0.0, diagonal.z),
+        )
+    }
+
+    // Cross-product matrix: skew_symmetric(a) * b == a.cross(&b)
+    pub fn skew_symmetric(v: Vec3) -> Self {
+        Self::new(
+            Vec3::new(0.0, -v.z, v.y),
+            Vec3::new(v.z, 0.0, -v.x),
+            Vec3::new(-v.y, v.x, 0.0),
+        )
+    }
+
+    // Rotation matrix for a unit quaternion
+    pub fn from_quat(q: Quat) -> Self {
+        let (w, x, y, z) = (q.w, q.x, q.y, q.z);
+        Self::new(
+            Vec3::new(1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z), 2.0 * (x * z + w * y)),
+            Vec3::new(2.0 * (x * y + w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x)),
+            Vec3::new(2.0 * (x * z - w * y), 2.0 * (y * z + w * x), 1.0 - 2.0 * (x * x + y * y)),
+        )
+    }
+
+    // a * b^T
+    pub fn outer_product(a: Vec3, b: Vec3) -> Self {
+        Self::new(b * a.x, b * a.y, b * a.z)
+    }
+
+    pub fn column(&self, index: usize) -> Vec3 {
+        match index {
+            0 => Vec3::new(self.rows[0].x, self.rows[1].x, self.rows[2].x),
+            1 => Vec3::new(self.rows[0].y, self.rows[1].y, self.rows[2].y),
+            _ => Vec3::new(self.rows[0].z, self.rows[1].z, self.rows[2].z),
+        }
+    }
+
+    pub fn transpose(&self) -> Mat3 {
+        Mat3::new(self.column(0), self.column(1), self.column(2))
+    }
+
+    pub fn trace(&self) -> f32 {
+        self.rows[0].x + self.rows[1].y + self.rows[2].z
+    }
+
+    pub fn determinant(&self) -> f32 {
+        let [a, b, c] = self.rows;
+        a.dot(&b.cross(&c))
+    }
+
+    // Returns the zero matrix when the matrix is singular, so an infinite or
+    // degenerate inertia simply produces no angular response
+    pub fn inverse(&self) -> Mat3 {
+        let det = self.determinant();
+        if det == 0.0 {
+            return Mat3::zero();
+        }
+        let [a, b, c] = self.rows;
+        // The adjugate's columns are the cross products of pairs of rows
+        let adjugate = Mat3::new(b.cross(&c), c.cross(&a), a.cross(&b)).transpose();
+        adjugate * (1.0 / det)
+    }
+
+    // Re-expresses a tensor (e.g. a body-space inertia tensor) in the frame
+    // given by `rotation`: R * I * R^T
+    pub fn rotate_tensor(&self, rotation: &Mat3) -> Mat3 {
+        *rotation * *self * rotation.transpose()
+    }
+}
+
+impl Mul<Vec3> for Mat3 {
+    type Output = Vec3;
+
+    fn mul(self, v: Vec3) -> Vec3 {
+        Vec3::new(self.rows[0].dot(&v), self.rows[1].dot(&v), self.rows[2].dot(&v))
+    }
+}
+
+impl Mul for Mat3 {
+    type Output = Mat3;
+
+    fn mul(self, other: Mat3) -> Mat3 {
+        let columns = [other.column(0), other.column(1), other.column(2)];
+        let row = |r: Vec3| Vec3::new(r.dot(&columns[0]), r.dot(&columns[1]), r.dot(&columns[2]));
+        Mat3::new(row(self.rows[0]), row(self.rows[1]), row(self.rows[2]))
+    }
+}
+
+impl Mul<f32> for Mat3 {
+    type Output = Mat3;
+
+    fn mul(self, scalar: f32) -> Mat3 {
+        Mat3::new(self.rows[0] * scalar, self.rows[1] * scalar, self.rows[2] * scalar)
+    }
+}
+
+impl Add for Mat3 {
+    type Output = Mat3;
+
+    fn add(self, other: Mat3) -> Mat3 {
+        Mat3::new(
+            self.rows[0] + other.rows[0],
+            self.rows[1] + other.rows[1],
+            self.rows[2] + other.rows[2],
+        )
+    }
+}
+
+impl Sub for Mat3 {
+    type Output = Mat3;
+
+    fn sub(self, other: Mat3) -> Mat3 {
+        Mat3::new(
+            self.rows[0] - other.rows[0],
+            self.rows[1] - other.rows[1],
+            self.rows[2] - other.rows[2],
+        )
+    }
+}
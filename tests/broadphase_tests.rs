@@ -0,0 +1,327 @@
+mod common;
+
+use common::{assert_vec_near, Lcg};
+use physics::{
+    Body, Broadphase, BruteForce, CollisionShape, DynamicAabbTree, LocalTransform, PhysicsWorld, Quat, RigidBody,
+    SpatialHashGrid, StaticBody, SweepAndPrune, Vec3,
+};
+
+fn random_bounds(rng: &mut Lcg, count: usize, spread: Vec3) -> Vec<(Vec3, Vec3)> {
+    (0..count)
+        .map(|_| {
+            let center = Vec3::new(rng.range(0.0, spread.x), rng.range(0.0, spread.y), rng.range(0.0, spread.z));
+            let half = Vec3::new(rng.range(0.1, 1.0), rng.range(0.1, 1.0), rng.range(0.1, 1.0));
+            (center - half, center + half)
+        })
+        .collect()
+}
+
+// Bounds
+#[test]
+fn test_world_bounds_of_rotated_box() {
+    let shape = CollisionShape::Box { half_extents: Vec3::new(1.0, 0.5, 0.5) };
+    let quarter_turn = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), std::f32::consts::FRAC_PI_2);
+    let (min, max) = shape.world_bounds(Vec3::new(2.0, 0.0, 0.0), quarter_turn);
+    assert_vec_near(min, Vec3::new(1.5, -1.0, -0.5));
+    assert_vec_near(max, Vec3::new(2.5, 1.0, 0.5));
+}
+
+#[test]
+fn test_world_bounds_of_sphere_and_plane() {
+    let (min, max) = CollisionShape::Sphere { radius: 0.5 }.world_bounds(Vec3::new(0.0, 1.0, 0.0), Quat::identity());
+    assert_vec_near(min, Vec3::new(-0.5, 0.5, -0.5));
+    assert_vec_near(max, Vec3::new(0.5, 1.5, 0.5));
+
+    let plane = CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 0.0 };
+    let (min, max) = plane.world_bounds(Vec3::zero(), Quat::identity());
+    assert!(min.x.is_infinite() && min.x < 0.0 && max.y.is_infinite() && max.y > 0.0);
+}
+
+#[test]
+fn test_world_bounds_of_compound_cover_children() {
+    let block = CollisionShape::Box { half_extents: Vec3::new(0.5, 0.5, 0.5) };
+    let pair = CollisionShape::Compound(vec![
+        (LocalTransform::from_position(Vec3::new(-1.0, 0.0, 0.0)), block.clone()),
+        (LocalTransform::from_position(Vec3::new(1.0, 0.0, 0.0)), block),
+    ]);
+    let (min, max) = pair.world_bounds(Vec3::new(0.0, 2.0, 0.0), Quat::identity());
+    assert_vec_near(min, Vec3::new(-1.5, 1.5, -0.5));
+    assert_vec_near(max, Vec3::new(1.5, 2.5, 0.5));
+}
+
+// Pairs
+#[test]
+fn test_sweep_and_prune_matches_brute_force() {
+    let mut rng = Lcg(7);
+    let mut sweep = SweepAndPrune::new();
+    let mut bounds = random_bounds(&mut rng, 200, Vec3::new(40.0, 10.0, 10.0));
+    for _ in 0..5 {
+        assert_eq!(sweep.find_pairs(&bounds), BruteForce.find_pairs(&bounds));
+        // Move everything a little so the kept order needs fixing up
+        for (min, max) in &mut bounds {
+            let offset = Vec3::new(rng.range(-0.5, 0.5), rng.range(-0.5, 0.5), rng.range(-0.5, 0.5));
+            *min += offset;
+            *max += offset;
+        }
+    }
+}
+
+#[test]
+fn test_sweep_and_prune_handles_added_and_removed_bodies() {
+    let mut rng = Lcg(11);
+    let mut sweep = SweepAndPrune::new();
+    let mut bounds = random_bounds(&mut rng, 50, Vec3::new(10.0, 10.0, 10.0));
+    assert_eq!(sweep.find_pairs(&bounds), BruteForce.find_pairs(&bounds));
+
+    bounds.extend(random_bounds(&mut rng, 30, Vec3::new(10.0, 10.0, 10.0)));
+    assert_eq!(sweep.find_pairs(&bounds), BruteForce.find_pairs(&bounds));
+
+    bounds.truncate(20);
+    assert_eq!(sweep.find_pairs(&bounds), BruteForce.find_pairs(&bounds));
+}
+
+#[test]
+fn test_sweep_and_prune_pairs_unbounded_shapes_with_everything() {
+    let mut bounds = random_bounds(&mut Lcg(3), 10, Vec3::new(100.0, 1.0, 1.0));
+    let infinite = Vec3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+    bounds.push((-infinite, infinite));
+    let pairs = SweepAndPrune::new().find_pairs(&bounds);
+    assert!((0..10).all(|i| pairs.contains(&(i, 10))));
+    assert_eq!(pairs, BruteForce.find_pairs(&bounds));
+}
+
+#[test]
+fn test_sweep_and_prune_uses_axis_of_largest_variance() {
+    let mut rng = Lcg(5);
+    let mut sweep = SweepAndPrune::new();
+    sweep.find_pairs(&random_bounds(&mut rng, 40, Vec3::new(1.0, 1.0, 50.0)));
+    assert_eq!(sweep.axis(), 2);
+    sweep.find_pairs(&random_bounds(&mut rng, 40, Vec3::new(1.0, 50.0, 1.0)));
+    assert_eq!(sweep.axis(), 1);
+}
+
+// Dynamic AABB tree
+#[test]
+fn test_tree_matches_brute_force_with_fast_movers() {
+    let mut rng = Lcg(13);
+    let mut tree = DynamicAabbTree::new();
+    let mut bounds = random_bounds(&mut rng, 200, Vec3::new(20.0, 20.0, 20.0));
+    for _ in 0..10 {
+        assert_eq!(tree.find_pairs(&bounds), BruteForce.find_pairs(&bounds));
+        // Large jumps in every direction
+        for (min, max) in &mut bounds {
+            let offset = Vec3::new(rng.range(-3.0, 3.0), rng.range(-3.0, 3.0), rng.range(-3.0, 3.0));
+            *min += offset;
+            *max += offset;
+        }
+    }
+
+    let infinite = Vec3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+    bounds.push((-infinite, infinite));
+    assert_eq!(tree.find_pairs(&bounds), BruteForce.find_pairs(&bounds));
+    bounds.truncate(120);
+    assert_eq!(tree.find_pairs(&bounds), BruteForce.find_pairs(&bounds));
+    assert_eq!(tree.len(), 120);
+}
+
+#[test]
+fn test_tree_stays_balanced() {
+    // Inserting bodies in order along a line would make a list of an unbalanced tree
+    let mut tree = DynamicAabbTree::new();
+    for index in 0..1024 {
+        let center = Vec3::new(index as f32, 0.0, 0.0);
+        let half = Vec3::new(0.4, 0.4, 0.4);
+        tree.insert(index, center - half, center + half);
+    }
+    assert_eq!(tree.len(), 1024);
+    assert!(tree.height() <= 20, "Tree height {}", tree.height());
+
+    for index in (0..1024).step_by(2) {
+        tree.remove(index);
+    }
+    assert_eq!(tree.len(), 512);
+    assert!(tree.height() <= 18, "Tree height {}", tree.height());
+}
+
+#[test]
+fn test_tree_leaves_are_fattened() {
+    let mut tree = DynamicAabbTree::with_margin(0.2);
+    let half = Vec3::new(0.5, 0.5, 0.5);
+    tree.insert(0, -half, half);
+    let (min, max) = tree.fat_bounds(0).unwrap();
+    assert_vec_near(min, Vec3::new(-0.7, -0.7, -0.7));
+    assert_vec_near(max, Vec3::new(0.7, 0.7, 0.7));
+
+    // Small moves stay inside the leaf, larger ones reinsert it
+    let nudge = Vec3::new(0.1, 0.0, 0.0);
+    assert!(!tree.update(0, nudge - half, nudge + half));
+    let jump = Vec3::new(1.0, 0.0, 0.0);
+    assert!(tree.update(0, jump - half, jump + half));
+    assert_vec_near(tree.fat_bounds(0).unwrap().0, Vec3::new(0.3, -0.7, -0.7));
+}
+
+#[test]
+fn test_tree_queries() {
+    let mut tree = DynamicAabbTree::with_margin(0.0);
+    let half = Vec3::new(0.5, 0.5, 0.5);
+    for index in 0..10 {
+        let center = Vec3::new(index as f32 * 2.0, 0.0, 0.0);
+        tree.insert(index, center - half, center + half);
+    }
+    let mut found = tree.query_aabb(Vec3::new(3.0, -1.0, -1.0), Vec3::new(7.0, 1.0, 1.0));
+    found.sort();
+    assert_eq!(found, vec![2, 3]);
+
+    tree.remove(2);
+    assert_eq!(tree.query_aabb(Vec3::new(3.0, -1.0, -1.0), Vec3::new(7.0, 1.0, 1.0)), vec![3]);
+    assert!(tree.fat_bounds(2).is_none());
+}
+
+// Spatial hash grid
+#[test]
+fn test_grid_matches_brute_force() {
+    let mut rng = Lcg(17);
+    let mut grid = SpatialHashGrid::with_cell_size(1.5);
+    let mut bounds = random_bounds(&mut rng, 200, Vec3::new(15.0, 15.0, 15.0));
+    // Bodies spanning several cells, one too large to bucket, and a plane
+    bounds.push((Vec3::new(2.0, 2.0, 2.0), Vec3::new(6.0, 4.0, 5.0)));
+    bounds.push((Vec3::new(-5.0, -5.0, -5.0), Vec3::new(25.0, 25.0, 25.0)));
+    let infinite = Vec3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+    bounds.push((-infinite, infinite));
+    for _ in 0..5 {
+        assert_eq!(grid.find_pairs(&bounds), BruteForce.find_pairs(&bounds));
+        for (min, max) in &mut bounds {
+            let offset = Vec3::new(rng.range(-2.0, 2.0), rng.range(-2.0, 2.0), rng.range(-2.0, 2.0));
+            *min += offset;
+            *max += offset;
+        }
+    }
+    assert_eq!(grid.oversized(), &[201, 202]);
+}
+
+#[test]
+fn test_grid_inserts_large_bodies_into_every_cell_they_touch() {
+    let mut grid = SpatialHashGrid::with_cell_size(1.0);
+    let bounds = [
+        (Vec3::new(0.1, 0.1, 0.1), Vec3::new(2.9, 1.9, 0.9)),
+        (Vec3::new(2.5, 1.5, 0.5), Vec3::new(2.7, 1.7, 0.7)),
+    ];
+    assert_eq!(grid.find_pairs(&bounds), vec![(0, 1)]);
+    // Three by two cells for the first body; the second shares one of them
+    assert_eq!(grid.occupied_cells(), 6);
+    assert!(grid.oversized().is_empty());
+}
+
+#[test]
+fn test_grid_queries() {
+    let mut grid = SpatialHashGrid::with_cell_size(2.0);
+    let half = Vec3::new(0.5, 0.5, 0.5);
+    let bounds: Vec<(Vec3, Vec3)> = (0..10)
+        .map(|index| {
+            let center = Vec3::new(index as f32 * 2.0, 0.0, 0.0);
+            (center - half, center + half)
+        })
+        .collect();
+    grid.sync(&bounds);
+    let occupied = grid.occupied_cells();
+    assert_eq!(grid.query_bounds(&bounds, Vec3::new(3.0, -1.0, -1.0), Vec3::new(7.0, 1.0, 1.0)), vec![2, 3]);
+    // Queries with the bounds the grid was filled from read its cells; moved
+    // bounds are checked one by one, leaving the grid as it was
+    let mut moved = bounds.clone();
+    moved[2] = (moved[2].0 + Vec3::new(10.0, 0.0, 0.0), moved[2].1 + Vec3::new(10.0, 0.0, 0.0));
+    assert_eq!(grid.query_bounds(&moved, Vec3::new(3.0, -1.0, -1.0), Vec3::new(7.0, 1.0, 1.0)), vec![3]);
+    assert_eq!(grid.query_bounds(&moved, Vec3::new(13.0, -1.0, -1.0), Vec3::new(15.0, 1.0, 1.0)), vec![2, 7]);
+    assert_eq!(grid.occupied_cells(), occupied);
+
+    // A query too large to walk cell by cell checks every body
+    let far = Vec3::new(1000.0, 1000.0, 1000.0);
+    assert_eq!(grid.query_bounds(&bounds, -far, far), (0..10).collect::<Vec<_>>());
+}
+
+// World
+fn scattered_world(broadphase: Box<dyn Broadphase>) -> PhysicsWorld {
+    let mut rng = Lcg(42);
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.broadphase = broadphase;
+    world.add_body(Body::Static(StaticBody::new(
+        Vec3::zero(),
+        CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 0.0 },
+    )));
+    world.add_body(Body::Static(StaticBody::new(
+        Vec3::new(0.0, 1.0, 0.0),
+        CollisionShape::Box { half_extents: Vec3::new(2.0, 1.0, 2.0) },
+    )));
+    for index in 0..120 {
+        let position = Vec3::new(rng.range(-6.0, 6.0), rng.range(0.5, 8.0), rng.range(-6.0, 6.0));
+        let shape = match index % 3 {
+            0 => CollisionShape::Sphere { radius: rng.range(0.2, 0.6) },
+            1 => CollisionShape::Box { half_extents: Vec3::new(0.3, 0.3, 0.3) },
+            _ => CollisionShape::Capsule { half_height: 0.3, radius: 0.2 },
+        };
+        world.add_body(Body::Rigid(RigidBody::with_density(position, 1.0, shape)));
+    }
+    world
+}
+
+fn assert_same_as_brute_force(broadphase: Box<dyn Broadphase>) {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = scattered_world(broadphase);
+    let mut brute = scattered_world(Box::new(BruteForce));
+    for _ in 0..120 {
+        world.update(fixed_timestep);
+        brute.update(fixed_timestep);
+    }
+
+    assert_eq!(world.contacts().len(), brute.contacts().len());
+    for (a, b) in world.bodies.iter().zip(&brute.bodies) {
+        assert_eq!(a.position(), b.position());
+        assert_eq!(a.orientation(), b.orientation());
+    }
+}
+
+#[test]
+fn test_world_matches_brute_force() {
+    assert_same_as_brute_force(Box::new(SweepAndPrune::new()));
+}
+
+#[test]
+fn test_world_with_tree_matches_brute_force() {
+    assert_same_as_brute_force(Box::new(DynamicAabbTree::new()));
+}
+
+#[test]
+fn test_world_with_grid_matches_brute_force() {
+    assert_same_as_brute_force(Box::new(SpatialHashGrid::with_cell_size(1.0)));
+}
+
+#[test]
+fn test_world_queries_go_through_the_broadphase() {
+    let mut sweep = scattered_world(Box::new(SweepAndPrune::new()));
+    let mut tree = scattered_world(Box::new(DynamicAabbTree::new()));
+    sweep.update(1.0 / 60.0);
+    tree.update(1.0 / 60.0);
+
+    let (min, max) = (Vec3::new(-2.0, 0.0, -2.0), Vec3::new(2.0, 3.0, 2.0));
+    let found = tree.query_aabb(min, max);
+    assert_eq!(found, sweep.query_aabb(min, max));
+    // The ground plane and the static box always overlap this region
+    assert!(found.contains(&0) && found.contains(&1));
+}
+
+#[test]
+fn test_world_queries_find_bodies_moved_since_the_step() {
+    let broadphases: [Box<dyn Broadphase>; 3] =
+        [Box::new(SweepAndPrune::new()), Box::new(DynamicAabbTree::new()), Box::new(SpatialHashGrid::new())];
+    for broadphase in broadphases {
+        let mut world = scattered_world(broadphase);
+        world.update(1.0 / 60.0);
+
+        let far = Vec3::new(50.0, 20.0, 50.0);
+        let found = world.query_aabb(far, far);
+        assert!(!found.contains(&1) && !found.contains(&10));
+        world.bodies[10].as_rigid_body_mut().unwrap().position = far;
+        world.bodies[1].as_static_body_mut().unwrap().position = far;
+        let found = world.query_aabb(far, far);
+        assert!(found.contains(&1) && found.contains(&10), "Found {:?}", found);
+    }
+}
@@ -0,0 +1,543 @@
+use physics::{
+    aabb_vs_aabb, aabb_vs_plane, box_vs_box, box_vs_plane, capsule_vs_box, capsule_vs_capsule,
+    capsule_vs_plane, closest_points_segment_segment, plane_world_distance, sphere_vs_capsule, Quat, sphere_vs_aabb, sphere_vs_box, sphere_vs_plane, Vec3,
+};
+
+// Sphere vs Plane Tests
+#[test]
+fn test_sphere_vs_plane_separated() {
+    let info = sphere_vs_plane(Vec3::new(0.0, 2.0, 0.0), 0.5, Vec3::new(0.0, 1.0, 0.0), 0.0);
+    assert!(!info.is_colliding);
+}
+
+#[test]
+fn test_sphere_vs_plane_touching() {
+    let info = sphere_vs_plane(Vec3::new(0.0, 0.5, 0.0), 0.5, Vec3::new(0.0, 1.0, 0.0), 0.0);
+    assert!(info.is_colliding);
+    assert!(info.penetration_depth.abs() < 0.0001);
+}
+
+#[test]
+fn test_sphere_vs_plane_penetrating() {
+    let info = sphere_vs_plane(Vec3::new(1.0, 0.3, -2.0), 0.5, Vec3::new(0.0, 1.0, 0.0), 0.0);
+    assert!(info.is_colliding);
+    // Normal points from the sphere into the plane
+    assert_eq!(info.normal, Vec3::new(0.0, -1.0, 0.0));
+    assert!((info.penetration_depth - 0.2).abs() < 0.0001);
+    // Contact point is the sphere centre projected onto the plane
+    assert!((info.contact_point - Vec3::new(1.0, 0.0, -2.0)).magnitude() < 0.0001);
+}
+
+#[test]
+fn test_sphere_vs_plane_centre_behind_plane() {
+    let info = sphere_vs_plane(Vec3::new(0.0, -0.25, 0.0), 0.5, Vec3::new(0.0, 1.0, 0.0), 0.0);
+    assert!(info.is_colliding);
+    assert_eq!(info.normal, Vec3::new(0.0, -1.0, 0.0));
+    // Depth is measured to the far side of the sphere: 0.25 + 0.5
+    assert!((info.penetration_depth - 0.75).abs() < 0.0001);
+    assert!((info.contact_point - Vec3::new(0.0, 0.0, 0.0)).magnitude() < 0.0001);
+}
+
+#[test]
+fn test_sphere_vs_plane_distance() {
+    // Plane y = 2
+    let info = sphere_vs_plane(Vec3::new(0.0, 2.4, 0.0), 0.5, Vec3::new(0.0, 1.0, 0.0), 2.0);
+    assert!(info.is_colliding);
+    assert!((info.penetration_depth - 0.1).abs() < 0.0001);
+    assert!((info.contact_point.y - 2.0).abs() < 0.0001);
+}
+
+#[test]
+fn test_sphere_vs_plane_tilted_normal() {
+    // 45 degree slope through the origin, normal given unnormalized
+    let info = sphere_vs_plane(Vec3::new(0.0, 0.5, 0.0), 0.5, Vec3::new(1.0, 1.0, 0.0), 0.0);
+    let expected_normal = -Vec3::new(1.0, 1.0, 0.0).normalize();
+    let signed_distance = 0.5 / 2.0_f32.sqrt();
+    assert!(info.is_colliding);
+    assert!((info.normal - expected_normal).magnitude() < 0.0001);
+    assert!((info.penetration_depth - (0.5 - signed_distance)).abs() < 0.0001);
+}
+
+#[test]
+fn test_plane_world_distance_applies_body_offset() {
+    let normal = Vec3::new(0.0, 1.0, 0.0);
+    assert_eq!(plane_world_distance(Vec3::new(5.0, 3.0, -1.0), normal, 1.0), 4.0);
+    assert_eq!(plane_world_distance(Vec3::zero(), normal, -2.0), -2.0);
+}
+
+// AABB vs AABB Tests
+#[test]
+fn test_aabb_vs_aabb_separated() {
+    let info = aabb_vs_aabb(
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(1.0, 1.0, 1.0),
+        Vec3::new(2.0, 0.0, 0.0),
+        Vec3::new(3.0, 1.0, 1.0),
+    );
+    assert!(!info.is_colliding);
+}
+
+#[test]
+fn test_aabb_vs_aabb_minimum_axis() {
+    // Overlap of 0.2 on x, 1.0 on y and z
+    let info = aabb_vs_aabb(
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(1.0, 1.0, 1.0),
+        Vec3::new(0.8, 0.0, 0.0),
+        Vec3::new(1.8, 1.0, 1.0),
+    );
+    assert!(info.is_colliding);
+    assert_eq!(info.normal, Vec3::new(1.0, 0.0, 0.0));
+    assert!((info.penetration_depth - 0.2).abs() < 0.0001);
+    assert!((info.contact_point - Vec3::new(0.9, 0.5, 0.5)).magnitude() < 0.0001);
+}
+
+#[test]
+fn test_aabb_vs_aabb_normal_points_from_a_to_b() {
+    // B sits slightly below A, overlapping by 0.1 on y
+    let info = aabb_vs_aabb(
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(1.0, 1.0, 1.0),
+        Vec3::new(0.0, -0.9, 0.0),
+        Vec3::new(1.0, 0.1, 1.0),
+    );
+    assert!(info.is_colliding);
+    assert_eq!(info.normal, Vec3::new(0.0, -1.0, 0.0));
+    assert!((info.penetration_depth - 0.1).abs() < 0.0001);
+}
+
+// Sphere vs AABB Tests
+#[test]
+fn test_sphere_vs_aabb_separated() {
+    let info = sphere_vs_aabb(
+        Vec3::new(0.0, 2.0, 0.0),
+        0.5,
+        Vec3::new(-1.0, -1.0, -1.0),
+        Vec3::new(1.0, 1.0, 1.0),
+    );
+    assert!(!info.is_colliding);
+}
+
+#[test]
+fn test_sphere_vs_aabb_face_contact() {
+    let info = sphere_vs_aabb(
+        Vec3::new(0.2, 1.3, 0.0),
+        0.5,
+        Vec3::new(-1.0, -1.0, -1.0),
+        Vec3::new(1.0, 1.0, 1.0),
+    );
+    assert!(info.is_colliding);
+    assert!((info.normal - Vec3::new(0.0, -1.0, 0.0)).magnitude() < 0.0001);
+    assert!((info.penetration_depth - 0.2).abs() < 0.0001);
+    assert!((info.contact_point - Vec3::new(0.2, 1.0, 0.0)).magnitude() < 0.0001);
+}
+
+#[test]
+fn test_sphere_vs_aabb_corner_contact() {
+    let corner = Vec3::new(1.0, 1.0, 1.0);
+    let direction = Vec3::new(1.0, 1.0, 1.0).normalize();
+    let info = sphere_vs_aabb(
+        corner + direction * 0.4,
+        0.5,
+        Vec3::new(-1.0, -1.0, -1.0),
+        corner,
+    );
+    assert!(info.is_colliding);
+    assert!((info.normal + direction).magnitude() < 0.0001);
+    assert!((info.penetration_depth - 0.1).abs() < 0.0001);
+    assert!((info.contact_point - corner).magnitude() < 0.0001);
+}
+
+#[test]
+fn test_sphere_vs_aabb_centre_inside() {
+    // Centre 0.1 below the top face of the box
+    let info = sphere_vs_aabb(
+        Vec3::new(0.0, 0.9, 0.0),
+        0.5,
+        Vec3::new(-1.0, -1.0, -1.0),
+        Vec3::new(1.0, 1.0, 1.0),
+    );
+    assert!(info.is_colliding);
+    // Sphere is pushed out through the top face
+    assert_eq!(info.normal, Vec3::new(0.0, -1.0, 0.0));
+    assert!((info.penetration_depth - 0.6).abs() < 0.0001);
+    assert!((info.contact_point - Vec3::new(0.0, 1.0, 0.0)).magnitude() < 0.0001);
+}
+
+// AABB vs Plane Tests
+#[test]
+fn test_aabb_vs_plane_separated() {
+    let info = aabb_vs_plane(
+        Vec3::new(-0.5, 0.1, -0.5),
+        Vec3::new(0.5, 1.1, 0.5),
+        Vec3::new(0.0, 1.0, 0.0),
+        0.0,
+    );
+    assert!(!info.is_colliding);
+}
+
+#[test]
+fn test_aabb_vs_plane_flat_on_ground() {
+    let info = aabb_vs_plane(
+        Vec3::new(1.5, -0.1, -0.5),
+        Vec3::new(2.5, 0.9, 0.5),
+        Vec3::new(0.0, 1.0, 0.0),
+        0.0,
+    );
+    assert!(info.is_colliding);
+    assert_eq!(info.normal, Vec3::new(0.0, -1.0, 0.0));
+    assert!((info.penetration_depth - 0.1).abs() < 0.0001);
+    // Four bottom corners average to the centre of the bottom face, on the plane
+    assert!((info.contact_point - Vec3::new(2.0, 0.0, 0.0)).magnitude() < 0.0001);
+}
+
+#[test]
+fn test_aabb_vs_plane_single_corner() {
+    // Tilted plane x + y = 0.2 (normalized), only the (0, 0, z) edge penetrates
+    let normal = Vec3::new(1.0, 1.0, 0.0).normalize();
+    let info = aabb_vs_plane(Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 1.0, 1.0), normal, 0.1);
+    assert!(info.is_colliding);
+    assert!((info.normal + normal).magnitude() < 0.0001);
+    assert!((info.penetration_depth - 0.1).abs() < 0.0001);
+    assert!((info.contact_point.dot(&normal) - 0.1).abs() < 0.0001);
+    assert!((info.contact_point.z - 0.5).abs() < 0.0001);
+}
+
+// Box (SAT) Tests
+#[test]
+fn test_box_vs_box_separated() {
+    let half = Vec3::new(0.5, 0.5, 0.5);
+    let info = box_vs_box(Vec3::zero(), Quat::identity(), half, Vec3::new(1.1, 0.0, 0.0), Quat::identity(), half);
+    assert!(!info.is_colliding);
+}
+
+#[test]
+fn test_box_vs_box_face_overlap() {
+    let half = Vec3::new(0.5, 0.5, 0.5);
+    let info = box_vs_box(Vec3::zero(), Quat::identity(), half, Vec3::new(0.1, 0.9, 0.0), Quat::identity(), half);
+    assert!(info.is_colliding);
+    assert!((info.normal - Vec3::new(0.0, 1.0, 0.0)).magnitude() < 0.0001);
+    assert!((info.penetration_depth - 0.1).abs() < 0.0001);
+    // Contact lies on the shared face, under the overlapping footprint
+    assert!((info.contact_point.y - 0.5).abs() < 0.0001);
+    assert!((info.contact_point.x - 0.05).abs() < 0.0001);
+}
+
+#[test]
+fn test_box_vs_box_rotated_gap_is_separated() {
+    // A box rotated 45 degrees about z reaches sqrt(2)/2 along x; its AABBs would
+    // overlap but the boxes themselves do not
+    let half = Vec3::new(0.5, 0.5, 0.5);
+    let rotation = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), std::f32::consts::FRAC_PI_4);
+    let info = box_vs_box(Vec3::zero(), rotation, half, Vec3::new(1.15, 1.15, 0.0), Quat::identity(), half);
+    assert!(!info.is_colliding);
+}
+
+#[test]
+fn test_box_vs_box_rotated_corner_penetration() {
+    let half = Vec3::new(0.5, 0.5, 0.5);
+    let rotation = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), std::f32::consts::FRAC_PI_4);
+    // Diamond standing on its corner on top of an axis-aligned box
+    let corner_reach = 0.5 * 2.0_f32.sqrt();
+    let info = box_vs_box(
+        Vec3::zero(),
+        Quat::identity(),
+        half,
+        Vec3::new(0.0, 0.5 + corner_reach - 0.05, 0.0),
+        rotation,
+        half,
+    );
+    assert!(info.is_colliding);
+    assert!((info.normal - Vec3::new(0.0, 1.0, 0.0)).magnitude() < 0.0001);
+    assert!((info.penetration_depth - 0.05).abs() < 0.0001);
+    assert!((info.contact_point - Vec3::new(0.0, 0.5, 0.0)).magnitude() < 0.0001);
+}
+
+#[test]
+fn test_box_vs_box_edge_edge() {
+    // Two long bars crossing at right angles, each rotated 45 degrees about its
+    // own length so they meet edge to edge
+    let half = Vec3::new(2.0, 0.5, 0.5);
+    let quarter = std::f32::consts::FRAC_PI_4;
+    let rotation_a = Quat::from_axis_angle(Vec3::new(1.0, 0.0, 0.0), quarter);
+    let rotation_b = Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), std::f32::consts::FRAC_PI_2)
+        * Quat::from_axis_angle(Vec3::new(1.0, 0.0, 0.0), quarter);
+    let reach = 0.5 * 2.0_f32.sqrt();
+    let info = box_vs_box(
+        Vec3::zero(),
+        rotation_a,
+        half,
+        Vec3::new(0.0, 2.0 * reach - 0.1, 0.0),
+        rotation_b,
+        half,
+    );
+    assert!(info.is_colliding);
+    assert!((info.normal - Vec3::new(0.0, 1.0, 0.0)).magnitude() < 0.0001);
+    assert!((info.penetration_depth - 0.1).abs() < 0.0001);
+    assert!((info.contact_point - Vec3::new(0.0, reach - 0.05, 0.0)).magnitude() < 0.0001);
+}
+
+#[test]
+fn test_box_vs_box_normal_points_from_a_to_b() {
+    let half = Vec3::new(0.5, 0.5, 0.5);
+    let info = box_vs_box(Vec3::zero(), Quat::identity(), half, Vec3::new(-0.8, 0.0, 0.0), Quat::identity(), half);
+    assert!(info.is_colliding);
+    assert!((info.normal - Vec3::new(-1.0, 0.0, 0.0)).magnitude() < 0.0001);
+    assert!((info.penetration_depth - 0.2).abs() < 0.0001);
+}
+
+#[test]
+fn test_sphere_vs_box_rotated() {
+    // Box rotated 45 degrees about z: its corner points straight up at y = sqrt(2)/2
+    let half = Vec3::new(0.5, 0.5, 0.5);
+    let rotation = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), std::f32::consts::FRAC_PI_4);
+    let reach = 0.5 * 2.0_f32.sqrt();
+    let info = sphere_vs_box(Vec3::new(0.0, reach + 0.4, 0.0), 0.5, Vec3::zero(), rotation, half);
+    assert!(info.is_colliding);
+    assert!((info.normal - Vec3::new(0.0, -1.0, 0.0)).magnitude() < 0.0001);
+    assert!((info.penetration_depth - 0.1).abs() < 0.0001);
+    assert!((info.contact_point - Vec3::new(0.0, reach, 0.0)).magnitude() < 0.0001);
+
+    // The same sphere would hit the unrotated box's top face at 0.5 only if much lower
+    let info = sphere_vs_box(Vec3::new(0.0, reach + 0.4, 0.0), 0.5, Vec3::zero(), Quat::identity(), half);
+    assert!(!info.is_colliding);
+}
+
+#[test]
+fn test_box_vs_plane_tilted_box() {
+    let half = Vec3::new(0.5, 0.5, 0.5);
+    let rotation = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), std::f32::consts::FRAC_PI_4);
+    let reach = 0.5 * 2.0_f32.sqrt();
+    let info = box_vs_plane(Vec3::new(3.0, reach - 0.1, 0.0), rotation, half, Vec3::new(0.0, 1.0, 0.0), 0.0);
+    assert!(info.is_colliding);
+    assert_eq!(info.normal, Vec3::new(0.0, -1.0, 0.0));
+    assert!((info.penetration_depth - 0.1).abs() < 0.0001);
+    // Only the bottom edge penetrates; its midpoint sits under the centre
+    assert!((info.contact_point - Vec3::new(3.0, 0.0, 0.0)).magnitude() < 0.0001);
+}
+
+// Segment Closest Point Tests
+#[test]
+fn test_closest_points_crossing_segments() {
+    let (p, q) = closest_points_segment_segment(
+        Vec3::new(-1.0, 0.0, 0.0),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, -1.0),
+        Vec3::new(0.0, 1.0, 1.0),
+    );
+    assert!((p - Vec3::zero()).magnitude() < 0.0001);
+    assert!((q - Vec3::new(0.0, 1.0, 0.0)).magnitude() < 0.0001);
+}
+
+#[test]
+fn test_closest_points_clamped_to_endpoints() {
+    let (p, q) = closest_points_segment_segment(
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(3.0, 1.0, 0.0),
+        Vec3::new(3.0, 2.0, 0.0),
+    );
+    assert!((p - Vec3::new(1.0, 0.0, 0.0)).magnitude() < 0.0001);
+    assert!((q - Vec3::new(3.0, 1.0, 0.0)).magnitude() < 0.0001);
+}
+
+#[test]
+fn test_closest_points_degenerate_segments() {
+    let (p, q) = closest_points_segment_segment(
+        Vec3::new(1.0, 1.0, 1.0),
+        Vec3::new(1.0, 1.0, 1.0),
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(2.0, 0.0, 0.0),
+    );
+    assert_eq!(p, Vec3::new(1.0, 1.0, 1.0));
+    assert!((q - Vec3::new(1.0, 0.0, 0.0)).magnitude() < 0.0001);
+}
+
+#[test]
+fn test_closest_points_parallel_overlapping_segments() {
+    // Side by side, overlapping on x in [1, 2]: the pair sits mid-overlap
+    let (p, q) = closest_points_segment_segment(
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(2.0, 0.0, 0.0),
+        Vec3::new(1.0, 1.0, 0.0),
+        Vec3::new(3.0, 1.0, 0.0),
+    );
+    assert!((p - Vec3::new(1.5, 0.0, 0.0)).magnitude() < 0.0001);
+    assert!((q - Vec3::new(1.5, 1.0, 0.0)).magnitude() < 0.0001);
+}
+
+#[test]
+fn test_closest_points_parallel_reversed_segments() {
+    // Same line direction reversed on the second segment
+    let (p, q) = closest_points_segment_segment(
+        Vec3::new(-1.0, 0.0, 0.0),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(1.0, 0.0, 2.0),
+        Vec3::new(-1.0, 0.0, 2.0),
+    );
+    assert!((p - Vec3::new(0.0, 0.0, 0.0)).magnitude() < 0.0001);
+    assert!((q - Vec3::new(0.0, 0.0, 2.0)).magnitude() < 0.0001);
+}
+
+#[test]
+fn test_closest_points_parallel_disjoint_segments() {
+    // Collinear with a gap: the facing end points are closest
+    let (p, q) = closest_points_segment_segment(
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(3.0, 0.0, 0.0),
+        Vec3::new(2.0, 0.0, 0.0),
+    );
+    assert!((p - Vec3::new(1.0, 0.0, 0.0)).magnitude() < 0.0001);
+    assert!((q - Vec3::new(2.0, 0.0, 0.0)).magnitude() < 0.0001);
+}
+
+// Capsule Tests
+fn lying_along_x() -> Quat {
+    // Capsules are aligned to local y; a quarter turn about z lays them along x
+    Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), std::f32::consts::FRAC_PI_2)
+}
+
+#[test]
+fn test_sphere_vs_capsule_side() {
+    let info = sphere_vs_capsule(Vec3::new(0.8, 0.5, 0.0), 0.5, Vec3::zero(), Quat::identity(), 1.0, 0.4);
+    assert!(info.is_colliding);
+    assert!((info.normal - Vec3::new(-1.0, 0.0, 0.0)).magnitude() < 0.0001);
+    assert!((info.penetration_depth - 0.1).abs() < 0.0001);
+}
+
+#[test]
+fn test_sphere_vs_capsule_cap() {
+    // Above the top cap: the closest segment point is the top end
+    let info = sphere_vs_capsule(Vec3::new(0.0, 1.8, 0.0), 0.5, Vec3::zero(), Quat::identity(), 1.0, 0.4);
+    assert!(info.is_colliding);
+    assert!((info.normal - Vec3::new(0.0, -1.0, 0.0)).magnitude() < 0.0001);
+    assert!((info.penetration_depth - 0.1).abs() < 0.0001);
+}
+
+#[test]
+fn test_sphere_vs_capsule_separated() {
+    let info = sphere_vs_capsule(Vec3::new(0.0, 3.0, 0.0), 0.5, Vec3::zero(), Quat::identity(), 1.0, 0.4);
+    assert!(!info.is_colliding);
+}
+
+#[test]
+fn test_capsule_vs_capsule_crossing() {
+    // One upright, one lying along x just above the first one's top end
+    let info = capsule_vs_capsule(
+        Vec3::zero(),
+        Quat::identity(),
+        1.0,
+        0.5,
+        Vec3::new(0.0, 1.9, 0.0),
+        lying_along_x(),
+        1.0,
+        0.5,
+    );
+    assert!(info.is_colliding);
+    assert!((info.normal - Vec3::new(0.0, 1.0, 0.0)).magnitude() < 0.0001);
+    assert!((info.penetration_depth - 0.1).abs() < 0.0001);
+}
+
+#[test]
+fn test_capsule_vs_capsule_parallel_side_by_side() {
+    // Two parallel upright capsules, offset along their axis; the contact must be
+    // in the middle of the overlapping stretch, not at either end
+    let info = capsule_vs_capsule(
+        Vec3::zero(),
+        Quat::identity(),
+        1.0,
+        0.5,
+        Vec3::new(0.9, 0.5, 0.0),
+        Quat::identity(),
+        1.0,
+        0.5,
+    );
+    assert!(info.is_colliding);
+    assert!((info.normal - Vec3::new(1.0, 0.0, 0.0)).magnitude() < 0.0001);
+    assert!((info.penetration_depth - 0.1).abs() < 0.0001);
+    assert!((info.contact_point.y - 0.25).abs() < 0.0001);
+}
+
+#[test]
+fn test_capsule_vs_capsule_collinear_end_to_end() {
+    let info = capsule_vs_capsule(
+        Vec3::zero(),
+        Quat::identity(),
+        1.0,
+        0.5,
+        Vec3::new(0.0, 2.9, 0.0),
+        Quat::identity(),
+        1.0,
+        0.5,
+    );
+    assert!(info.is_colliding);
+    assert!((info.normal - Vec3::new(0.0, 1.0, 0.0)).magnitude() < 0.0001);
+    assert!((info.penetration_depth - 0.1).abs() < 0.0001);
+}
+
+#[test]
+fn test_capsule_vs_plane_upright() {
+    let info = capsule_vs_plane(Vec3::new(0.0, 1.4, 0.0), Quat::identity(), 1.0, 0.5, Vec3::new(0.0, 1.0, 0.0), 0.0);
+    assert!(info.is_colliding);
+    assert_eq!(info.normal, Vec3::new(0.0, -1.0, 0.0));
+    assert!((info.penetration_depth - 0.1).abs() < 0.0001);
+    assert!((info.contact_point - Vec3::new(0.0, 0.0, 0.0)).magnitude() < 0.0001);
+}
+
+#[test]
+fn test_capsule_vs_plane_lying_flat() {
+    let info = capsule_vs_plane(Vec3::new(2.0, 0.4, 0.0), lying_along_x(), 1.0, 0.5, Vec3::new(0.0, 1.0, 0.0), 0.0);
+    assert!(info.is_colliding);
+    assert!((info.penetration_depth - 0.1).abs() < 0.0001);
+    // Both ends touch, so the contact is under the middle
+    assert!((info.contact_point - Vec3::new(2.0, 0.0, 0.0)).magnitude() < 0.0001);
+}
+
+#[test]
+fn test_capsule_vs_box_lying_flat() {
+    let info = capsule_vs_box(
+        Vec3::new(0.3, 0.9, 0.0),
+        lying_along_x(),
+        1.0,
+        0.5,
+        Vec3::zero(),
+        Quat::identity(),
+        Vec3::new(2.0, 0.5, 2.0),
+    );
+    assert!(info.is_colliding);
+    assert!((info.normal - Vec3::new(0.0, -1.0, 0.0)).magnitude() < 0.0001);
+    assert!((info.penetration_depth - 0.1).abs() < 0.0001);
+    // Parallel to the top face: supported under its centre
+    assert!((info.contact_point - Vec3::new(0.3, 0.5, 0.0)).magnitude() < 0.001);
+}
+
+#[test]
+fn test_capsule_vs_box_end_on() {
+    let info = capsule_vs_box(
+        Vec3::new(0.0, 1.9, 0.0),
+        Quat::identity(),
+        1.0,
+        0.5,
+        Vec3::zero(),
+        Quat::identity(),
+        Vec3::new(0.5, 0.5, 0.5),
+    );
+    assert!(info.is_colliding);
+    assert!((info.normal - Vec3::new(0.0, -1.0, 0.0)).magnitude() < 0.0001);
+    assert!((info.penetration_depth - 0.1).abs() < 0.0001);
+}
+
+#[test]
+fn test_capsule_vs_box_separated() {
+    let info = capsule_vs_box(
+        Vec3::new(2.0, 0.0, 0.0),
+        Quat::identity(),
+        1.0,
+        0.5,
+        Vec3::zero(),
+        Quat::identity(),
+        Vec3::new(0.5, 0.5, 0.5),
+    );
+    assert!(!info.is_colliding);
+}
@@ -0,0 +1,48 @@
+// Helpers shared by the integration tests. Each test file builds this module
+// on its own and uses only part of it.
+#![allow(dead_code)]
+
+use physics::{Body, CollisionShape, PhysicsWorld, RigidBody, StaticBody, Vec3};
+
+pub fn assert_near(a: f32, b: f32) {
+    assert!((a - b).abs() < 0.0001 * b.abs().max(1.0), "{} != {}", a, b);
+}
+
+pub fn assert_vec_near(a: Vec3, b: Vec3) {
+    assert!((a - b).magnitude() < 1.0e-4, "{:?} != {:?}", a, b);
+}
+
+// Static plane at y = 0 facing up
+pub fn ground() -> Body {
+    Body::Static(StaticBody::new(
+        Vec3::zero(),
+        CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 0.0 },
+    ))
+}
+
+pub fn unit_box(position: Vec3) -> Body {
+    Body::Rigid(RigidBody::with_density(position, 1.0, CollisionShape::Box { half_extents: Vec3::new(0.5, 0.5, 0.5) }))
+}
+
+// Ground with a unit box standing on it as body 1, and no bounce
+pub fn resting_box_world() -> PhysicsWorld {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.restitution = 0.0;
+    world.add_body(ground());
+    world.add_body(unit_box(Vec3::new(0.0, 0.5, 0.0)));
+    world
+}
+
+// Small deterministic generator so the scenes are the same on every run
+pub struct Lcg(pub u32);
+
+impl Lcg {
+    pub fn next(&mut self) -> f32 {
+        self.0 = self.0.wrapping_mul(1664525).wrapping_add(1013904223);
+        (self.0 >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    pub fn range(&mut self, low: f32, high: f32) -> f32 {
+        low + (high - low) * self.next()
+    }
+}
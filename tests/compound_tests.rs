@@ -0,0 +1,170 @@
+mod common;
+
+use common::{assert_near, ground};
+use physics::{
+    collide, collide_contacts, Body, CollisionShape, LocalTransform, MassProperties, PhysicsWorld, Quat, RigidBody,
+    Vec3,
+};
+use std::f32::consts::PI;
+
+// Two balls of radius 0.25 joined by a thin bar along x
+fn dumbbell() -> CollisionShape {
+    CollisionShape::Compound(vec![
+        (LocalTransform::from_position(Vec3::new(-0.75, 0.0, 0.0)), CollisionShape::Sphere { radius: 0.25 }),
+        (LocalTransform::from_position(Vec3::new(0.75, 0.0, 0.0)), CollisionShape::Sphere { radius: 0.25 }),
+        (
+            LocalTransform::new(Vec3::zero(), Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), PI / 2.0)),
+            CollisionShape::Capsule { half_height: 0.5, radius: 0.05 },
+        ),
+    ])
+}
+
+// Mass properties
+#[test]
+fn test_compound_of_two_spheres_uses_parallel_axis() {
+    let sphere = CollisionShape::Sphere { radius: 0.5 };
+    let shape = CollisionShape::Compound(vec![
+        (LocalTransform::from_position(Vec3::new(-1.0, 0.0, 0.0)), sphere.clone()),
+        (LocalTransform::from_position(Vec3::new(1.0, 0.0, 0.0)), sphere.clone()),
+    ]);
+    let single = MassProperties::from_shape(&sphere, 2.0);
+    let props = MassProperties::from_shape(&shape, 2.0);
+
+    let i = single.local_inertia.rows[0].x;
+    assert_near(props.mass, 2.0 * single.mass);
+    assert!(props.center_of_mass.magnitude() < 1.0e-6);
+    // Along the bar: no offset; across it: each ball is 1 away
+    assert_near(props.local_inertia.rows[0].x, 2.0 * i);
+    assert_near(props.local_inertia.rows[1].y, 2.0 * (i + single.mass));
+    assert_near(props.local_inertia.rows[2].z, 2.0 * (i + single.mass));
+}
+
+#[test]
+fn test_compound_centre_of_mass_follows_heavier_child() {
+    let shape = CollisionShape::Compound(vec![
+        (LocalTransform::identity(), CollisionShape::Box { half_extents: Vec3::new(0.5, 0.5, 0.5) }),
+        (
+            LocalTransform::from_position(Vec3::new(0.0, 3.0, 0.0)),
+            CollisionShape::Box { half_extents: Vec3::new(1.0, 0.5, 0.5) },
+        ),
+    ]);
+    let props = MassProperties::from_shape(&shape, 1.0);
+    // Masses 1 and 2: centre two thirds of the way up
+    assert_near(props.mass, 3.0);
+    assert!((props.center_of_mass - Vec3::new(0.0, 2.0, 0.0)).magnitude() < 1.0e-5);
+}
+
+#[test]
+fn test_rotated_child_inertia() {
+    // A long box turned to lie along z has its small moment about z
+    let along_z = CollisionShape::Compound(vec![(
+        LocalTransform::new(Vec3::zero(), Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), PI / 2.0)),
+        CollisionShape::Box { half_extents: Vec3::new(2.0, 0.5, 0.5) },
+    )]);
+    let props = MassProperties::from_shape(&along_z, 1.0);
+    let body = RigidBody::with_density(Vec3::zero(), 1.0, along_z);
+
+    assert_near(props.local_inertia.rows[2].z, 4.0 * (1.0 + 1.0) / 12.0);
+    assert_near(props.local_inertia.rows[0].x, 4.0 * (16.0 + 1.0) / 12.0);
+    assert_eq!(body.inertia_tensor, props.local_inertia);
+    assert_eq!(body.mass, props.mass);
+}
+
+// Narrowphase
+#[test]
+fn test_compound_reports_per_child_contacts() {
+    let plane = CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 0.0 };
+    let contacts = collide_contacts(&dumbbell(), Vec3::new(0.0, 0.2, 0.0), Quat::identity(), &plane, Vec3::zero(), Quat::identity());
+
+    // Both balls touch; the bar is clear of the ground
+    assert_eq!(contacts.len(), 2);
+    let mut xs: Vec<f32> = contacts.iter().map(|c| c.contact_point.x).collect();
+    xs.sort_by(f32::total_cmp);
+    assert_near(xs[0], -0.75);
+    assert_near(xs[1], 0.75);
+    for contact in &contacts {
+        assert_near(contact.penetration_depth, 0.05);
+        assert_eq!(contact.normal, Vec3::new(0.0, -1.0, 0.0));
+    }
+}
+
+#[test]
+fn test_compound_children_follow_body_rotation() {
+    let plane = CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 0.0 };
+    // Stood on end: only the lower ball reaches the ground
+    let upright = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), PI / 2.0);
+    let contacts = collide_contacts(&dumbbell(), Vec3::new(0.0, 0.9, 0.0), upright, &plane, Vec3::zero(), Quat::identity());
+
+    assert_eq!(contacts.len(), 1);
+    assert_near(contacts[0].penetration_depth, 0.1);
+    assert!((contacts[0].contact_point - Vec3::new(0.0, 0.0, 0.0)).magnitude() < 1.0e-5);
+}
+
+#[test]
+fn test_collide_returns_deepest_child_with_flipped_normal() {
+    let ball = CollisionShape::Sphere { radius: 0.5 };
+    let info = collide(
+        &ball,
+        Vec3::new(0.75, 0.6, 0.0),
+        Quat::identity(),
+        &dumbbell(),
+        Vec3::zero(),
+        Quat::identity(),
+    )
+    .unwrap();
+
+    // Ball above the right-hand end: pushed up, towards the dumbbell is down
+    assert!(info.is_colliding);
+    assert!((info.normal - Vec3::new(0.0, -1.0, 0.0)).magnitude() < 1.0e-5);
+    assert_near(info.penetration_depth, 0.15);
+}
+
+// World
+#[test]
+fn test_dumbbell_rests_level_on_ground() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.0;
+    world.add_body(ground());
+    world.add_body(Body::Rigid(RigidBody::with_density(Vec3::new(0.0, 2.0, 0.0), 1.0, dumbbell())));
+
+    for _ in 0..300 {
+        world.update(fixed_timestep);
+    }
+
+    let rigid = world.bodies[1].as_rigid_body().unwrap();
+    assert!((rigid.position.y - 0.25).abs() < 0.02, "Dumbbell did not rest on its ends: y = {}", rigid.position.y);
+    let bar = rigid.orientation.rotate(Vec3::new(1.0, 0.0, 0.0));
+    assert!(bar.y.abs() < 0.01, "Dumbbell tilted: {:?}", bar);
+}
+
+#[test]
+fn test_table_stands_on_its_legs() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.0;
+    world.add_body(ground());
+
+    let leg = CollisionShape::Box { half_extents: Vec3::new(0.05, 0.4, 0.05) };
+    let mut children = vec![(
+        LocalTransform::from_position(Vec3::new(0.0, 0.45, 0.0)),
+        CollisionShape::Box { half_extents: Vec3::new(1.0, 0.05, 0.6) },
+    )];
+    for (x, z) in [(-0.9, -0.5), (0.9, -0.5), (-0.9, 0.5), (0.9, 0.5)] {
+        children.push((LocalTransform::from_position(Vec3::new(x, 0.0, z)), leg.clone()));
+    }
+    world.add_body(Body::Rigid(RigidBody::with_density(
+        Vec3::new(0.0, 1.0, 0.0),
+        1.0,
+        CollisionShape::Compound(children),
+    )));
+
+    for _ in 0..300 {
+        world.update(fixed_timestep);
+    }
+
+    let table = world.bodies[1].as_rigid_body().unwrap();
+    // Legs reach 0.4 below the body origin
+    assert!((table.position.y - 0.4).abs() < 0.02, "Table did not stand: y = {}", table.position.y);
+    assert!(table.orientation.rotate(Vec3::new(0.0, 1.0, 0.0)).y > 0.999, "Table tipped over");
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use common::{ground, resting_box_world};
+use physics::{
+    box_vs_box_manifold, collide_manifolds, Body, CollisionShape, ContactCache, LocalTransform, PhysicsWorld, Quat,
+    RigidBody, Vec3,
+};
+use std::collections::HashSet;
+
+// Feature ids
+#[test]
+fn test_manifold_ids_are_distinct() {
+    let twist = Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), 0.4);
+    let manifold = box_vs_box_manifold(
+        Vec3::zero(),
+        Quat::identity(),
+        Vec3::new(1.0, 0.5, 1.0),
+        Vec3::new(0.3, 0.9, 0.0),
+        twist,
+        Vec3::new(1.0, 0.5, 1.0),
+    )
+    .unwrap();
+    let ids: HashSet<u32> = manifold.points.iter().map(|contact| contact.id).collect();
+    assert_eq!(ids.len(), manifold.points.len());
+}
+
+#[test]
+fn test_ids_survive_small_motion() {
+    // A narrower box hanging over the edge of a wider one
+    let (lower, upper) = (Vec3::new(0.5, 0.5, 0.5), Vec3::new(0.5, 0.5, 0.3));
+    let manifold_at = |center: Vec3| {
+        box_vs_box_manifold(Vec3::zero(), Quat::identity(), lower, center, Quat::identity(), upper).unwrap()
+    };
+    let before = manifold_at(Vec3::new(0.7, 0.95, 0.0));
+    let after = manifold_at(Vec3::new(0.71, 0.96, 0.01));
+    let mut before_ids: Vec<u32> = before.points.iter().map(|contact| contact.id).collect();
+    let mut after_ids: Vec<u32> = after.points.iter().map(|contact| contact.id).collect();
+    before_ids.sort();
+    after_ids.sort();
+    assert_eq!(before_ids, after_ids);
+}
+
+#[test]
+fn test_compound_children_have_separate_ids() {
+    let block = CollisionShape::Box { half_extents: Vec3::new(0.5, 0.5, 0.5) };
+    let pair = CollisionShape::Compound(vec![
+        (LocalTransform::from_position(Vec3::new(-1.0, 0.0, 0.0)), block.clone()),
+        (LocalTransform::from_position(Vec3::new(1.0, 0.0, 0.0)), block),
+    ]);
+    let floor = CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 0.0 };
+    let manifolds =
+        collide_manifolds(&pair, Vec3::new(0.0, 0.45, 0.0), Quat::identity(), &floor, Vec3::zero(), Quat::identity());
+    let ids: HashSet<u32> = manifolds.iter().flat_map(|manifold| manifold.points.iter().map(|c| c.id)).collect();
+    assert_eq!(ids.len(), 8);
+}
+
+// Cache
+#[test]
+fn test_new_cache_is_empty() {
+    let cache = ContactCache::new();
+    assert!(cache.is_empty());
+    assert_eq!(cache.touching_steps(0, 1), 0);
+    assert!(cache.pair(0, 1).is_none());
+}
+
+#[test]
+fn test_resting_box_contacts_persist() {
+    let mut world = resting_box_world();
+    let fixed_timestep = 1.0 / 60.0;
+    world.update(fixed_timestep);
+    let first_ids: HashSet<u32> = world.contacts().pair(0, 1).unwrap().contacts.iter().map(|c| c.id).collect();
+
+    for _ in 0..9 {
+        world.update(fixed_timestep);
+    }
+
+    let contacts = world.contacts();
+    assert_eq!(contacts.len(), 1);
+    assert_eq!(contacts.touching_steps(0, 1), 10);
+    // Either order of the bodies names the same pair
+    assert_eq!(contacts.touching_steps(1, 0), 10);
+
+    let pair = contacts.pair(0, 1).unwrap();
+    assert_eq!(pair.contacts.len(), 4);
+    for contact in &pair.contacts {
+        assert!(first_ids.contains(&contact.id), "contact {} is new", contact.id);
+        assert_eq!(contact.age, 10);
+        // The ground holds the box up, so the stored impulses push apart
+        assert!(contact.normal_impulse <= 0.0);
+    }
+    let total: f32 = pair.contacts.iter().map(|contact| contact.normal_impulse).sum();
+    assert!(total < 0.0);
+}
+
+#[test]
+fn test_pairs_leave_the_cache_when_apart() {
+    let mut world = resting_box_world();
+    let fixed_timestep = 1.0 / 60.0;
+    for _ in 0..5 {
+        world.update(fixed_timestep);
+    }
+    assert!(world.contacts().touching_steps(0, 1) > 0);
+
+    // Throw the box upwards
+    world.bodies[1].as_rigid_body_mut().unwrap().velocity = Vec3::new(0.0, 5.0, 0.0);
+    world.update(fixed_timestep);
+    assert_eq!(world.contacts().touching_steps(0, 1), 0);
+    assert!(world.contacts().is_empty());
+}
+
+#[test]
+fn test_new_contacts_start_at_age_one() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.restitution = 0.0;
+    world.add_body(ground());
+    world.add_body(Body::Rigid(RigidBody::with_density(
+        Vec3::new(0.0, 0.5, 0.0),
+        1.0,
+        CollisionShape::Sphere { radius: 0.5 },
+    )));
+    world.update(1.0 / 60.0);
+    let pair = world.contacts().pair(0, 1).unwrap();
+    assert_eq!(pair.steps, 1);
+    assert_eq!(pair.contacts.len(), 1);
+    assert_eq!(pair.contacts[0].age, 1);
+}
@@ -0,0 +1,169 @@
+use physics::{
+    box_vs_box, collide, gjk_epa, gjk_intersect, sphere_vs_sphere, CollisionShape, ConvexHull, Quat,
+    ShapeSupport, Vec3,
+};
+use std::sync::Arc;
+
+fn cube_corners(half: f32) -> Vec<Vec3> {
+    let mut corners = Vec::new();
+    for x in [-half, half] {
+        for y in [-half, half] {
+            for z in [-half, half] {
+                corners.push(Vec3::new(x, y, z));
+            }
+        }
+    }
+    corners
+}
+
+fn support<'a>(shape: &'a CollisionShape, position: Vec3, rotation: Quat) -> ShapeSupport<'a> {
+    ShapeSupport::new(shape, position, rotation).unwrap()
+}
+
+// Hull construction
+#[test]
+fn test_hull_of_cube_drops_interior_points() {
+    let mut points = cube_corners(1.0);
+    points.push(Vec3::zero());
+    points.push(Vec3::new(0.3, -0.2, 0.5));
+    // Duplicates and points on a face are not vertices either
+    points.push(Vec3::new(1.0, 1.0, 1.0));
+    points.push(Vec3::new(1.0, 0.0, 0.0));
+
+    let hull = ConvexHull::from_points(&points);
+    assert_eq!(hull.vertices.len(), 8);
+    assert_eq!(hull.faces.len(), 12);
+}
+
+#[test]
+fn test_hull_faces_point_outwards() {
+    let hull = ConvexHull::from_points(&cube_corners(1.0));
+    for face in &hull.faces {
+        let (a, b, c) = (hull.vertices[face[0]], hull.vertices[face[1]], hull.vertices[face[2]]);
+        let normal = (b - a).cross(&(c - a));
+        assert!(normal.dot(&a) > 0.0, "face {:?} faces inwards", face);
+    }
+}
+
+#[test]
+fn test_flat_point_cloud_has_no_faces() {
+    let points = [
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 1.0),
+        Vec3::new(1.0, 0.0, 1.0),
+    ];
+    let hull = ConvexHull::from_points(&points);
+    assert!(hull.faces.is_empty());
+}
+
+#[test]
+fn test_cloned_hull_shapes_share_their_hull() {
+    let shape = CollisionShape::convex_hull(&cube_corners(1.0));
+    let copy = shape.clone();
+    match (&shape, &copy) {
+        (CollisionShape::ConvexHull(a), CollisionShape::ConvexHull(b)) => assert!(Arc::ptr_eq(a, b)),
+        _ => panic!("Not hulls"),
+    }
+    assert_eq!(shape, copy);
+}
+
+#[test]
+fn test_hull_support() {
+    let hull = ConvexHull::from_points(&cube_corners(1.0));
+    assert_eq!(hull.support(Vec3::new(1.0, 2.0, -3.0)), Vec3::new(1.0, 1.0, -1.0));
+}
+
+// GJK / EPA against the analytic routines
+#[test]
+fn test_gjk_separated_and_overlapping() {
+    let sphere = CollisionShape::Sphere { radius: 1.0 };
+    let a = support(&sphere, Vec3::zero(), Quat::identity());
+
+    assert!(!gjk_intersect(&a, &support(&sphere, Vec3::new(2.5, 0.0, 0.0), Quat::identity())));
+    assert!(gjk_intersect(&a, &support(&sphere, Vec3::new(1.5, 0.5, 0.0), Quat::identity())));
+}
+
+#[test]
+fn test_epa_matches_sphere_vs_sphere() {
+    let sphere = CollisionShape::Sphere { radius: 1.0 };
+    let pos_b = Vec3::new(1.2, 0.9, 0.0);
+    let info = gjk_epa(
+        &support(&sphere, Vec3::zero(), Quat::identity()),
+        &support(&sphere, pos_b, Quat::identity()),
+    );
+    let expected = sphere_vs_sphere(Vec3::zero(), 1.0, pos_b, 1.0);
+
+    assert!(info.is_colliding);
+    // Spheres have no flat faces for EPA to land on, so allow a little slack
+    assert!((info.penetration_depth - expected.penetration_depth).abs() < 0.01);
+    assert!((info.normal - expected.normal).magnitude() < 0.05);
+}
+
+#[test]
+fn test_epa_matches_box_vs_box() {
+    let half = Vec3::new(0.5, 0.5, 0.5);
+    let cube = CollisionShape::Box { half_extents: half };
+    let rot_b = Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), 0.3);
+    let pos_b = Vec3::new(0.2, 0.85, 0.1);
+
+    let info = gjk_epa(
+        &support(&cube, Vec3::zero(), Quat::identity()),
+        &support(&cube, pos_b, rot_b),
+    );
+    let expected = box_vs_box(Vec3::zero(), Quat::identity(), half, pos_b, rot_b, half);
+
+    assert!(info.is_colliding);
+    assert!((info.penetration_depth - expected.penetration_depth).abs() < 0.001);
+    assert!((info.normal - expected.normal).magnitude() < 0.001);
+}
+
+#[test]
+fn test_hull_matches_box() {
+    let hull = CollisionShape::convex_hull(&cube_corners(0.5));
+    let cube = CollisionShape::Box { half_extents: Vec3::new(0.5, 0.5, 0.5) };
+    let pos_b = Vec3::new(0.9, 0.1, 0.0);
+
+    let info = collide(&hull, Vec3::zero(), Quat::identity(), &cube, pos_b, Quat::identity()).unwrap();
+    assert!(info.is_colliding);
+    assert!((info.penetration_depth - 0.1).abs() < 0.001);
+    assert!((info.normal - Vec3::new(1.0, 0.0, 0.0)).magnitude() < 0.001);
+}
+
+// Generic dispatch
+#[test]
+fn test_collide_flips_normal_for_swapped_pair() {
+    let sphere = CollisionShape::Sphere { radius: 0.5 };
+    let plane = CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 0.0 };
+    let position = Vec3::new(0.0, 0.3, 0.0);
+
+    let sphere_first = collide(&sphere, position, Quat::identity(), &plane, Vec3::zero(), Quat::identity()).unwrap();
+    let plane_first = collide(&plane, Vec3::zero(), Quat::identity(), &sphere, position, Quat::identity()).unwrap();
+    assert_eq!(sphere_first.normal, -plane_first.normal);
+    assert_eq!(sphere_first.penetration_depth, plane_first.penetration_depth);
+}
+
+#[test]
+fn test_collide_planes_never_touch() {
+    let plane = CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 0.0 };
+    assert!(collide(&plane, Vec3::zero(), Quat::identity(), &plane, Vec3::zero(), Quat::identity()).is_none());
+}
+
+#[test]
+fn test_hull_vs_plane() {
+    // Tetrahedron with one vertex poking 0.2 below the ground
+    let hull = CollisionShape::convex_hull(&[
+        Vec3::new(0.0, -1.0, 0.0),
+        Vec3::new(1.0, 1.0, 0.0),
+        Vec3::new(-1.0, 1.0, 1.0),
+        Vec3::new(-1.0, 1.0, -1.0),
+    ]);
+    let plane = CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 0.0 };
+
+    let info = collide(&hull, Vec3::new(2.0, 0.8, 0.0), Quat::identity(), &plane, Vec3::zero(), Quat::identity())
+        .unwrap();
+    assert!(info.is_colliding);
+    assert_eq!(info.normal, Vec3::new(0.0, -1.0, 0.0));
+    assert!((info.penetration_depth - 0.2).abs() < 0.0001);
+    assert!((info.contact_point - Vec3::new(2.0, 0.0, 0.0)).magnitude() < 0.0001);
+}
@@ -0,0 +1,265 @@
+mod common;
+
+use common::{assert_near, assert_vec_near, ground};
+use physics::{
+    collide, cone_vs_plane, cylinder_vs_plane, sphere_vs_cone, sphere_vs_cylinder, Body, CollisionShape, MassProperties,
+    PhysicsWorld, Quat, RigidBody, ShapeSupport, SupportMap, Vec3,
+};
+use std::f32::consts::PI;
+
+// Support functions
+#[test]
+fn test_cylinder_support() {
+    let cylinder = CollisionShape::Cylinder { half_height: 1.0, radius: 0.5 };
+    let support = ShapeSupport::new(&cylinder, Vec3::zero(), Quat::identity()).unwrap();
+    assert_vec_near(support.support(Vec3::new(1.0, 1.0, 0.0)), Vec3::new(0.5, 1.0, 0.0));
+    assert_vec_near(support.support(Vec3::new(0.0, -1.0, -2.0)), Vec3::new(0.0, -1.0, -0.5));
+    // Straight along the axis: the centre of the cap
+    assert_vec_near(support.support(Vec3::new(0.0, 1.0, 0.0)), Vec3::new(0.0, 1.0, 0.0));
+}
+
+#[test]
+fn test_cone_support() {
+    let cone = CollisionShape::Cone { half_height: 1.0, radius: 0.5 };
+    let support = ShapeSupport::new(&cone, Vec3::zero(), Quat::identity()).unwrap();
+    assert_vec_near(support.support(Vec3::new(0.0, 1.0, 0.0)), Vec3::new(0.0, 1.0, 0.0));
+    assert_vec_near(support.support(Vec3::new(1.0, 0.0, 0.0)), Vec3::new(0.5, -1.0, 0.0));
+    assert_vec_near(support.support(Vec3::new(0.0, -1.0, 1.0)), Vec3::new(0.0, -1.0, 0.5));
+}
+
+// Mass properties
+#[test]
+fn test_cylinder_mass_properties() {
+    let props = MassProperties::from_shape(&CollisionShape::Cylinder { half_height: 1.0, radius: 0.5 }, 2.0);
+    let mass = 2.0 * PI * 0.25 * 2.0;
+    assert_near(props.mass, mass);
+    assert_near(props.local_inertia.rows[1].y, mass * 0.25 / 2.0);
+    assert_near(props.local_inertia.rows[0].x, mass * (3.0 * 0.25 + 4.0) / 12.0);
+    assert_near(props.local_inertia.rows[2].z, props.local_inertia.rows[0].x);
+    assert_vec_near(props.center_of_mass, Vec3::zero());
+}
+
+#[test]
+fn test_cone_mass_properties() {
+    let props = MassProperties::from_shape(&CollisionShape::Cone { half_height: 1.0, radius: 0.5 }, 3.0);
+    let mass = 3.0 * PI * 0.25 * 2.0 / 3.0;
+    assert_near(props.mass, mass);
+    assert_near(props.local_inertia.rows[1].y, 0.3 * mass * 0.25);
+    assert_near(props.local_inertia.rows[0].x, mass * (3.0 * 0.25 / 20.0 + 3.0 * 4.0 / 80.0));
+    // A quarter of the height above the base
+    assert_vec_near(props.center_of_mass, Vec3::new(0.0, -0.5, 0.0));
+}
+
+#[test]
+fn test_cone_mass_matches_hull_approximation() {
+    // A fine polygonal cone should have nearly the analytic properties
+    let mut points = vec![Vec3::new(0.0, 1.0, 0.0)];
+    for i in 0..256 {
+        let angle = i as f32 * 2.0 * PI / 256.0;
+        points.push(Vec3::new(angle.cos() * 0.5, -1.0, angle.sin() * 0.5));
+    }
+    let hull = MassProperties::from_shape(&CollisionShape::convex_hull(&points), 1.0);
+    let cone = MassProperties::from_shape(&CollisionShape::Cone { half_height: 1.0, radius: 0.5 }, 1.0);
+    assert!((hull.mass - cone.mass).abs() < 0.01 * cone.mass);
+    assert!((hull.center_of_mass - cone.center_of_mass).magnitude() < 0.01);
+    for (a, b) in [
+        (hull.local_inertia.rows[0].x, cone.local_inertia.rows[0].x),
+        (hull.local_inertia.rows[1].y, cone.local_inertia.rows[1].y),
+        (hull.local_inertia.rows[2].z, cone.local_inertia.rows[2].z),
+    ] {
+        assert!((a - b).abs() < 0.01 * b, "{} != {}", a, b);
+    }
+}
+
+// Sphere fast paths
+#[test]
+fn test_sphere_vs_cylinder_side_and_cap() {
+    let side = sphere_vs_cylinder(Vec3::new(0.9, 0.2, 0.0), 0.5, Vec3::zero(), Quat::identity(), 1.0, 0.5);
+    assert!(side.is_colliding);
+    assert_vec_near(side.normal, Vec3::new(-1.0, 0.0, 0.0));
+    assert_near(side.penetration_depth, 0.1);
+    assert_vec_near(side.contact_point, Vec3::new(0.5, 0.2, 0.0));
+
+    let cap = sphere_vs_cylinder(Vec3::new(0.1, 1.3, 0.0), 0.5, Vec3::zero(), Quat::identity(), 1.0, 0.5);
+    assert!(cap.is_colliding);
+    assert_vec_near(cap.normal, Vec3::new(0.0, -1.0, 0.0));
+    assert_near(cap.penetration_depth, 0.2);
+
+    let miss = sphere_vs_cylinder(Vec3::new(0.9, 1.4, 0.0), 0.5, Vec3::zero(), Quat::identity(), 1.0, 0.5);
+    assert!(!miss.is_colliding);
+}
+
+#[test]
+fn test_sphere_vs_rotated_cylinder() {
+    // Lying along x, so the sphere above touches the curved side
+    let rotation = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), PI / 2.0);
+    let info = sphere_vs_cylinder(Vec3::new(0.7, 0.8, 0.0), 0.4, Vec3::zero(), rotation, 1.0, 0.5);
+    assert!(info.is_colliding);
+    assert_vec_near(info.normal, Vec3::new(0.0, -1.0, 0.0));
+    assert_near(info.penetration_depth, 0.1);
+}
+
+#[test]
+fn test_sphere_centre_inside_cylinder() {
+    let info = sphere_vs_cylinder(Vec3::new(0.0, 0.9, 0.0), 0.1, Vec3::zero(), Quat::identity(), 1.0, 0.5);
+    assert!(info.is_colliding);
+    assert_vec_near(info.normal, Vec3::new(0.0, -1.0, 0.0));
+    assert_near(info.penetration_depth, 0.2);
+}
+
+#[test]
+fn test_sphere_vs_cone_slant_apex_and_base() {
+    // Slant of a cone of height 2 and radius 1: outward normal (2, 1) / sqrt(5)
+    let slant = Vec3::new(2.0, 1.0, 0.0).normalize();
+    let surface = Vec3::new(0.5, 0.0, 0.0);
+    let info = sphere_vs_cone(surface + slant * 0.2, 0.3, Vec3::zero(), Quat::identity(), 1.0, 1.0);
+    assert!(info.is_colliding);
+    assert_vec_near(info.normal, -slant);
+    assert_near(info.penetration_depth, 0.1);
+    assert_vec_near(info.contact_point, surface);
+
+    let apex = sphere_vs_cone(Vec3::new(0.0, 1.2, 0.0), 0.3, Vec3::zero(), Quat::identity(), 1.0, 1.0);
+    assert!(apex.is_colliding);
+    assert_vec_near(apex.normal, Vec3::new(0.0, -1.0, 0.0));
+    assert_near(apex.penetration_depth, 0.1);
+
+    let base = sphere_vs_cone(Vec3::new(0.3, -1.2, 0.0), 0.3, Vec3::zero(), Quat::identity(), 1.0, 1.0);
+    assert!(base.is_colliding);
+    assert_vec_near(base.normal, Vec3::new(0.0, 1.0, 0.0));
+    assert_near(base.penetration_depth, 0.1);
+
+    // Beside the apex, outside the slant
+    let miss = sphere_vs_cone(Vec3::new(0.6, 0.8, 0.0), 0.3, Vec3::zero(), Quat::identity(), 1.0, 1.0);
+    assert!(!miss.is_colliding);
+}
+
+#[test]
+fn test_collide_dispatches_sphere_vs_cone_both_ways() {
+    let sphere = CollisionShape::Sphere { radius: 0.3 };
+    let cone = CollisionShape::Cone { half_height: 1.0, radius: 1.0 };
+    let a = collide(&sphere, Vec3::new(0.0, 1.2, 0.0), Quat::identity(), &cone, Vec3::zero(), Quat::identity()).unwrap();
+    let b = collide(&cone, Vec3::zero(), Quat::identity(), &sphere, Vec3::new(0.0, 1.2, 0.0), Quat::identity()).unwrap();
+    assert!(a.is_colliding && b.is_colliding);
+    assert_vec_near(a.normal, -b.normal);
+    assert_near(a.penetration_depth, b.penetration_depth);
+}
+
+// Plane fast paths
+#[test]
+fn test_upright_cylinder_vs_plane() {
+    let normal = Vec3::new(0.0, 1.0, 0.0);
+    let info = cylinder_vs_plane(Vec3::new(0.0, 0.9, 0.0), Quat::identity(), 1.0, 0.5, normal, 0.0);
+    assert!(info.is_colliding);
+    assert_vec_near(info.normal, -normal);
+    assert_near(info.penetration_depth, 0.1);
+    // The whole bottom cap is down, so the contact sits on the plane under the axis
+    assert_vec_near(info.contact_point, Vec3::zero());
+}
+
+#[test]
+fn test_cylinder_on_its_side_vs_plane() {
+    let rotation = Quat::from_axis_angle(Vec3::new(1.0, 0.0, 0.0), PI / 2.0);
+    let info = cylinder_vs_plane(Vec3::new(2.0, 0.45, 0.0), rotation, 1.0, 0.5, Vec3::new(0.0, 1.0, 0.0), 0.0);
+    assert!(info.is_colliding);
+    assert_near(info.penetration_depth, 0.05);
+    // The line of contact runs along the axis; its middle is under the centre
+    assert_vec_near(info.contact_point, Vec3::new(2.0, 0.0, 0.0));
+}
+
+#[test]
+fn test_cone_vs_plane_on_base_and_apex() {
+    let normal = Vec3::new(0.0, 1.0, 0.0);
+    let base = cone_vs_plane(Vec3::new(0.0, 0.95, 0.0), Quat::identity(), 1.0, 0.5, normal, 0.0);
+    assert!(base.is_colliding);
+    assert_near(base.penetration_depth, 0.05);
+
+    let upside_down = Quat::from_axis_angle(Vec3::new(1.0, 0.0, 0.0), PI);
+    let apex = cone_vs_plane(Vec3::new(0.0, 0.9, 0.0), upside_down, 1.0, 0.5, normal, 0.0);
+    assert!(apex.is_colliding);
+    assert_near(apex.penetration_depth, 0.1);
+    assert_vec_near(apex.contact_point, Vec3::zero());
+}
+
+// GJK path
+#[test]
+fn test_cylinder_vs_box_uses_gjk() {
+    let cylinder = CollisionShape::Cylinder { half_height: 1.0, radius: 0.5 };
+    let block = CollisionShape::Box { half_extents: Vec3::new(1.0, 1.0, 1.0) };
+    let info = collide(
+        &cylinder,
+        Vec3::new(0.0, 1.9, 0.0),
+        Quat::identity(),
+        &block,
+        Vec3::zero(),
+        Quat::identity(),
+    )
+    .unwrap();
+    assert!(info.is_colliding);
+    assert_vec_near(info.normal, Vec3::new(0.0, -1.0, 0.0));
+    assert!((info.penetration_depth - 0.1).abs() < 1.0e-3);
+
+    let apart = collide(
+        &cylinder,
+        Vec3::new(1.6, 0.0, 0.0),
+        Quat::identity(),
+        &block,
+        Vec3::zero(),
+        Quat::identity(),
+    )
+    .unwrap();
+    assert!(!apart.is_colliding);
+}
+
+// World
+#[test]
+fn test_wheel_on_its_side_rolls_straight() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.0;
+    world.add_body(ground());
+
+    // Axle along z, pushed along x
+    let mut wheel = RigidBody::with_density(
+        Vec3::new(0.0, 0.5, 0.0),
+        1.0,
+        CollisionShape::Cylinder { half_height: 0.15, radius: 0.5 },
+    );
+    wheel.orientation = Quat::from_axis_angle(Vec3::new(1.0, 0.0, 0.0), PI / 2.0);
+    wheel.velocity = Vec3::new(2.0, 0.0, 0.0);
+    world.add_body(Body::Rigid(wheel));
+
+    for _ in 0..60 {
+        world.update(fixed_timestep);
+    }
+
+    let wheel = world.bodies[1].as_rigid_body().unwrap();
+    assert!(wheel.position.x > 1.0, "Wheel stopped: x = {}", wheel.position.x);
+    assert!(wheel.position.z.abs() < 0.05, "Wheel veered: z = {}", wheel.position.z);
+    assert!((wheel.position.y - 0.5).abs() < 0.02, "Wheel left the ground: y = {}", wheel.position.y);
+    // Friction turns the slide into spin about the axle
+    assert!(wheel.angular_velocity.z < -1.0, "Wheel is not rolling: {:?}", wheel.angular_velocity);
+    let axle = wheel.orientation.rotate(Vec3::new(0.0, 1.0, 0.0));
+    assert!(axle.y.abs() < 0.05, "Wheel tipped over: {:?}", axle);
+}
+
+#[test]
+fn test_cone_stands_on_its_base() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.0;
+    world.add_body(ground());
+    world.add_body(Body::Rigid(RigidBody::with_density(
+        Vec3::new(0.0, 1.5, 0.0),
+        1.0,
+        CollisionShape::Cone { half_height: 0.4, radius: 0.3 },
+    )));
+
+    for _ in 0..300 {
+        world.update(fixed_timestep);
+    }
+
+    let cone = world.bodies[1].as_rigid_body().unwrap();
+    assert!((cone.position.y - 0.4).abs() < 0.02, "Cone did not land on its base: y = {}", cone.position.y);
+    let axis = cone.orientation.rotate(Vec3::new(0.0, 1.0, 0.0));
+    assert!(axis.y > 0.99, "Cone tipped over: {:?}", axis);
+}
@@ -0,0 +1,201 @@
+use physics::{
+    box_vs_heightfield, capsule_vs_heightfield, raycast_heightfield, sphere_vs_heightfield, Body, CollisionShape,
+    Heightfield, PhysicsWorld, Quat, RigidBody, StaticBody, Vec3,
+};
+
+fn flat_terrain(size: usize, height: f32) -> Heightfield {
+    Heightfield::new(size, size, vec![height; size * size], Vec3::new(1.0, 1.0, 1.0))
+}
+
+// Ramp rising along x: height = x * slope
+fn ramp(size: usize, slope: f32) -> Heightfield {
+    let mut heights = Vec::new();
+    for _row in 0..size {
+        for column in 0..size {
+            heights.push(column as f32 * slope);
+        }
+    }
+    Heightfield::new(size, size, heights, Vec3::new(1.0, 1.0, 1.0))
+}
+
+#[test]
+fn test_heightfield_layout() {
+    let terrain = Heightfield::new(3, 2, vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0], Vec3::new(2.0, 0.5, 3.0));
+    assert_eq!(terrain.height(2, 1), 5.0);
+    assert_eq!(terrain.vertex(1, 1), Vec3::new(2.0, 2.0, 3.0));
+    assert_eq!(terrain.bounds(), (Vec3::new(0.0, 0.0, 0.0), Vec3::new(4.0, 2.5, 3.0)));
+}
+
+#[test]
+#[should_panic]
+fn test_heightfield_rejects_wrong_sample_count() {
+    Heightfield::new(3, 3, vec![0.0; 8], Vec3::new(1.0, 1.0, 1.0));
+}
+
+// Narrowphase
+#[test]
+fn test_sphere_on_flat_terrain() {
+    let terrain = flat_terrain(8, 2.0);
+    let info = sphere_vs_heightfield(Vec3::new(3.02, 2.45, 4.0), 0.5, Vec3::zero(), Quat::identity(), &terrain);
+    assert!(info.is_colliding);
+    assert!((info.normal - Vec3::new(0.0, -1.0, 0.0)).magnitude() < 1.0e-6);
+    assert!((info.penetration_depth - 0.05).abs() < 1.0e-4);
+    assert!((info.contact_point - Vec3::new(3.02, 2.0, 4.0)).magnitude() < 1.0e-4);
+}
+
+#[test]
+fn test_sphere_off_terrain_misses() {
+    let terrain = flat_terrain(4, 0.0);
+    let info = sphere_vs_heightfield(Vec3::new(-2.0, 0.2, 1.0), 0.5, Vec3::zero(), Quat::identity(), &terrain);
+    assert!(!info.is_colliding);
+}
+
+#[test]
+fn test_sphere_on_slope_pushed_along_slope_normal() {
+    let terrain = ramp(6, 0.5);
+    let slope_normal = Vec3::new(-0.5, 1.0, 0.0).normalize();
+    // 0.4 above the slope surface at x = 2.5
+    let center = Vec3::new(2.5, 1.25, 2.5) + slope_normal * 0.4;
+
+    let info = sphere_vs_heightfield(center, 0.5, Vec3::zero(), Quat::identity(), &terrain);
+    assert!(info.is_colliding);
+    assert!((info.normal + slope_normal).magnitude() < 1.0e-4);
+    assert!((info.penetration_depth - 0.1).abs() < 1.0e-4);
+}
+
+#[test]
+fn test_capsule_and_box_on_terrain() {
+    let terrain = flat_terrain(8, 1.0);
+    let position = Vec3::new(-4.0, 0.0, -4.0);
+
+    let capsule = capsule_vs_heightfield(
+        Vec3::new(0.0, 1.75, 0.0),
+        Quat::identity(),
+        0.5,
+        0.3,
+        position,
+        Quat::identity(),
+        &terrain,
+    );
+    assert!(capsule.is_colliding);
+    assert!((capsule.penetration_depth - 0.05).abs() < 1.0e-4);
+
+    let crate_box = box_vs_heightfield(
+        Vec3::new(0.3, 1.45, -0.2),
+        Quat::identity(),
+        Vec3::new(0.5, 0.5, 0.5),
+        position,
+        Quat::identity(),
+        &terrain,
+    );
+    assert!(crate_box.is_colliding);
+    assert!((crate_box.normal - Vec3::new(0.0, -1.0, 0.0)).magnitude() < 1.0e-4);
+    assert!((crate_box.penetration_depth - 0.05).abs() < 1.0e-3);
+    assert!((crate_box.contact_point - Vec3::new(0.3, 1.0, -0.2)).magnitude() < 1.0e-3);
+}
+
+// Raycasts
+#[test]
+fn test_raycast_straight_down() {
+    let terrain = ramp(6, 0.5);
+    let hit = raycast_heightfield(
+        Vec3::new(3.0, 10.0, 2.0),
+        Vec3::new(0.0, -1.0, 0.0),
+        100.0,
+        Vec3::zero(),
+        Quat::identity(),
+        &terrain,
+    )
+    .unwrap();
+    assert!((hit.point - Vec3::new(3.0, 1.5, 2.0)).magnitude() < 1.0e-4);
+    assert!((hit.distance - 8.5).abs() < 1.0e-4);
+    assert!((hit.normal - Vec3::new(-0.5, 1.0, 0.0).normalize()).magnitude() < 1.0e-4);
+}
+
+#[test]
+fn test_raycast_across_cells_finds_first_hit() {
+    // Flat ground with a wall of height 3 at column 5
+    let mut heights = vec![0.0; 10 * 4];
+    for row in 0..4 {
+        heights[row * 10 + 5] = 3.0;
+    }
+    let terrain = Heightfield::new(10, 4, heights, Vec3::new(1.0, 1.0, 1.0));
+
+    let hit = raycast_heightfield(
+        Vec3::new(0.5, 1.0, 1.5),
+        Vec3::new(1.0, 0.0, 0.0),
+        100.0,
+        Vec3::zero(),
+        Quat::identity(),
+        &terrain,
+    )
+    .unwrap();
+    // The wall's near slope rises from x = 4 to x = 5, reaching y = 1 at x = 4 + 1/3
+    assert!((hit.point.x - (4.0 + 1.0 / 3.0)).abs() < 1.0e-4);
+    assert!(hit.normal.x < 0.0);
+}
+
+#[test]
+fn test_raycast_misses_and_respects_max_distance() {
+    let terrain = flat_terrain(4, 0.0);
+    let up = raycast_heightfield(Vec3::new(1.0, 1.0, 1.0), Vec3::new(0.0, 1.0, 0.0), 100.0, Vec3::zero(), Quat::identity(), &terrain);
+    assert!(up.is_none());
+    let short = raycast_heightfield(Vec3::new(1.0, 5.0, 1.0), Vec3::new(0.0, -1.0, 0.0), 4.0, Vec3::zero(), Quat::identity(), &terrain);
+    assert!(short.is_none());
+    let beside = raycast_heightfield(Vec3::new(10.0, 5.0, 1.0), Vec3::new(0.0, -1.0, 0.0), 100.0, Vec3::zero(), Quat::identity(), &terrain);
+    assert!(beside.is_none());
+}
+
+// World
+#[test]
+fn test_ball_rolls_down_terrain_slope() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.0;
+    // Slope falling towards +x
+    let mut heights = Vec::new();
+    for _row in 0..16 {
+        for column in 0..16 {
+            heights.push(8.0 - column as f32 * 0.5);
+        }
+    }
+    world.add_body(Body::Static(StaticBody::new(
+        Vec3::zero(),
+        CollisionShape::Heightfield(Heightfield::new(16, 16, heights, Vec3::new(1.0, 1.0, 1.0))),
+    )));
+    world.add_body(Body::Rigid(RigidBody::with_shape(Vec3::new(2.0, 7.6, 7.5), 1.0, CollisionShape::Sphere { radius: 0.5 })));
+
+    for _ in 0..90 {
+        world.update(fixed_timestep);
+    }
+
+    let ball = world.bodies[1].as_rigid_body().unwrap();
+    assert!(ball.velocity.x > 1.0, "Ball did not roll downhill: v = {:?}", ball.velocity);
+    // Still on the surface: centre half a radius-normal above the slope
+    let surface = 8.0 - ball.position.x * 0.5;
+    let height_above = (ball.position.y - surface) * (1.0 / 1.25_f32.sqrt());
+    assert!((height_above - 0.5).abs() < 0.05, "Ball left the slope: {}", height_above);
+}
+
+#[test]
+fn test_box_rests_on_terrain() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.0;
+    world.add_body(Body::Static(StaticBody::new(
+        Vec3::new(-50.0, 0.0, -50.0),
+        CollisionShape::Heightfield(flat_terrain(101, 0.0)),
+    )));
+    world.add_body(Body::Rigid(RigidBody::with_shape(
+        Vec3::new(0.3, 2.0, 0.1),
+        1.0,
+        CollisionShape::Box { half_extents: Vec3::new(0.5, 0.5, 0.5) },
+    )));
+
+    for _ in 0..300 {
+        world.update(fixed_timestep);
+    }
+
+    let rigid = world.bodies[1].as_rigid_body().unwrap();
+    assert!((rigid.position.y - 0.5).abs() < 0.02, "Box did not rest on terrain: y = {}", rigid.position.y);
+}
@@ -0,0 +1,200 @@
+mod common;
+
+use common::{assert_near, assert_vec_near, ground};
+use physics::{
+    box_vs_box, box_vs_box_manifold, box_vs_plane_manifold, collide_manifolds, Body, CollisionShape, ContactManifold,
+    LocalTransform, PhysicsWorld, Quat, RigidBody, Vec3, MAX_MANIFOLD_POINTS,
+};
+use std::f32::consts::PI;
+
+fn has_point(manifold: &ContactManifold, point: Vec3) -> bool {
+    manifold.points.iter().any(|contact| (contact.point - point).magnitude() < 1.0e-4)
+}
+
+// Box vs plane
+#[test]
+fn test_flat_box_on_plane_has_four_corners() {
+    let up = Vec3::new(0.0, 1.0, 0.0);
+    let manifold =
+        box_vs_plane_manifold(Vec3::new(0.0, 0.4, 0.0), Quat::identity(), Vec3::new(1.0, 0.5, 2.0), up, 0.0).unwrap();
+    assert_vec_near(manifold.normal, -up);
+    assert_eq!(manifold.points.len(), 4);
+    for contact in &manifold.points {
+        assert_near(contact.depth, 0.1);
+        assert_near(contact.point.y, 0.0);
+    }
+    assert!(has_point(&manifold, Vec3::new(1.0, 0.0, -2.0)));
+    assert!(has_point(&manifold, Vec3::new(-1.0, 0.0, 2.0)));
+}
+
+#[test]
+fn test_tilted_box_on_plane_has_per_point_depths() {
+    // Rolled about z so one long edge dips into the plane
+    let rotation = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), 0.1);
+    let half = Vec3::new(1.0, 0.5, 1.0);
+    let manifold =
+        box_vs_plane_manifold(Vec3::new(0.0, 0.55, 0.0), rotation, half, Vec3::new(0.0, 1.0, 0.0), 0.0).unwrap();
+    assert_eq!(manifold.points.len(), 2);
+    for contact in &manifold.points {
+        assert!(contact.depth > 0.0);
+        assert_near(contact.point.x, manifold.points[0].point.x);
+    }
+
+    let apart = box_vs_plane_manifold(Vec3::new(0.0, 2.0, 0.0), rotation, half, Vec3::new(0.0, 1.0, 0.0), 0.0);
+    assert!(apart.is_none());
+}
+
+#[test]
+fn test_sunken_box_keeps_at_most_four_points() {
+    let rotation = Quat::from_axis_angle(Vec3::new(1.0, 0.0, 1.0).normalize(), 0.3);
+    let manifold = box_vs_plane_manifold(
+        Vec3::new(0.0, -3.0, 0.0),
+        rotation,
+        Vec3::new(0.5, 0.5, 0.5),
+        Vec3::new(0.0, 1.0, 0.0),
+        0.0,
+    )
+    .unwrap();
+    assert_eq!(manifold.points.len(), MAX_MANIFOLD_POINTS);
+    // The deepest corner always survives
+    let deepest = manifold.deepest().unwrap();
+    let lowest = 3.0 + Vec3::new(0.5, 0.5, 0.5).magnitude();
+    assert!(deepest.depth <= lowest && deepest.depth > 3.5);
+}
+
+// Box vs box
+#[test]
+fn test_stacked_boxes_clip_to_overlap() {
+    // Upper box shifted so only part of it rests on the lower one
+    let manifold = box_vs_box_manifold(
+        Vec3::zero(),
+        Quat::identity(),
+        Vec3::new(1.0, 0.5, 1.0),
+        Vec3::new(1.5, 0.95, 0.0),
+        Quat::identity(),
+        Vec3::new(1.0, 0.5, 1.0),
+    )
+    .unwrap();
+    assert_vec_near(manifold.normal, Vec3::new(0.0, 1.0, 0.0));
+    assert_eq!(manifold.points.len(), 4);
+    for contact in &manifold.points {
+        assert_near(contact.depth, 0.05);
+        assert!(contact.point.x >= 0.5 - 1.0e-4 && contact.point.x <= 1.0 + 1.0e-4);
+    }
+    assert!(has_point(&manifold, Vec3::new(0.5, 0.5, 1.0)));
+    assert!(has_point(&manifold, Vec3::new(1.0, 0.5, -1.0)));
+}
+
+#[test]
+fn test_twisted_box_on_box_is_reduced() {
+    // A square turned 45 degrees on top of another clips to an octagon
+    let twist = Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), PI / 4.0);
+    let manifold = box_vs_box_manifold(
+        Vec3::zero(),
+        Quat::identity(),
+        Vec3::new(1.0, 0.5, 1.0),
+        Vec3::new(0.0, 0.9, 0.0),
+        twist,
+        Vec3::new(1.0, 0.5, 1.0),
+    )
+    .unwrap();
+    assert_eq!(manifold.points.len(), MAX_MANIFOLD_POINTS);
+    for contact in &manifold.points {
+        assert_near(contact.depth, 0.1);
+        assert!(contact.point.x.abs() <= 1.0 + 1.0e-4 && contact.point.z.abs() <= 1.0 + 1.0e-4);
+    }
+}
+
+#[test]
+fn test_box_manifold_agrees_with_box_vs_box() {
+    let rotation = Quat::from_axis_angle(Vec3::new(1.0, 2.0, 0.5).normalize(), 0.7);
+    let (center_b, half) = (Vec3::new(0.3, 1.2, -0.2), Vec3::new(0.5, 0.5, 0.5));
+    let info = box_vs_box(Vec3::zero(), Quat::identity(), half, center_b, rotation, half);
+    let manifold = box_vs_box_manifold(Vec3::zero(), Quat::identity(), half, center_b, rotation, half).unwrap();
+    assert_vec_near(manifold.normal, info.normal);
+    assert_near(manifold.deepest().unwrap().depth, info.penetration_depth);
+}
+
+#[test]
+fn test_edge_on_edge_has_one_point() {
+    // Two boxes meeting edge to edge across each other
+    let a = Quat::from_axis_angle(Vec3::new(1.0, 0.0, 0.0), PI / 4.0);
+    let b = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), PI / 4.0);
+    let half = Vec3::new(1.0, 1.0, 1.0);
+    let manifold = box_vs_box_manifold(Vec3::zero(), a, half, Vec3::new(0.0, 2.7, 0.0), b, half).unwrap();
+    assert_eq!(manifold.points.len(), 1);
+    assert_vec_near(manifold.normal, Vec3::new(0.0, 1.0, 0.0));
+}
+
+// Dispatch
+#[test]
+fn test_collide_manifolds_dispatch() {
+    let block = CollisionShape::Box { half_extents: Vec3::new(0.5, 0.5, 0.5) };
+    let floor = CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 0.0 };
+
+    // Plane first: the normal still points from A to B
+    let manifolds =
+        collide_manifolds(&floor, Vec3::zero(), Quat::identity(), &block, Vec3::new(0.0, 0.45, 0.0), Quat::identity());
+    assert_eq!(manifolds.len(), 1);
+    assert_vec_near(manifolds[0].normal, Vec3::new(0.0, 1.0, 0.0));
+    assert_eq!(manifolds[0].points.len(), 4);
+
+    // AABBs are clipped like boxes
+    let aabb = CollisionShape::AABB { min: Vec3::new(-0.5, -0.5, -0.5), max: Vec3::new(0.5, 0.5, 0.5) };
+    let manifolds =
+        collide_manifolds(&aabb, Vec3::zero(), Quat::identity(), &block, Vec3::new(0.2, 0.9, 0.0), Quat::identity());
+    assert_eq!(manifolds[0].points.len(), 4);
+
+    // Other pairs fall back to one point
+    let ball = CollisionShape::Sphere { radius: 0.5 };
+    let manifolds =
+        collide_manifolds(&ball, Vec3::new(0.0, 0.4, 0.0), Quat::identity(), &floor, Vec3::zero(), Quat::identity());
+    assert_eq!(manifolds.len(), 1);
+    assert_eq!(manifolds[0].points.len(), 1);
+    assert_near(manifolds[0].points[0].depth, 0.1);
+
+    let apart =
+        collide_manifolds(&ball, Vec3::new(0.0, 2.0, 0.0), Quat::identity(), &block, Vec3::zero(), Quat::identity());
+    assert!(apart.is_empty());
+}
+
+#[test]
+fn test_compound_children_each_get_a_manifold() {
+    let block = CollisionShape::Box { half_extents: Vec3::new(0.5, 0.5, 0.5) };
+    let pair = CollisionShape::Compound(vec![
+        (LocalTransform::from_position(Vec3::new(-1.0, 0.0, 0.0)), block.clone()),
+        (LocalTransform::from_position(Vec3::new(1.0, 0.0, 0.0)), block),
+    ]);
+    let floor = CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 0.0 };
+    let manifolds =
+        collide_manifolds(&pair, Vec3::new(0.0, 0.45, 0.0), Quat::identity(), &floor, Vec3::zero(), Quat::identity());
+    assert_eq!(manifolds.len(), 2);
+    assert!(manifolds.iter().all(|manifold| manifold.points.len() == 4));
+}
+
+// World
+#[test]
+fn test_tilted_box_settles_flat() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.0;
+    world.add_body(ground());
+
+    let mut block = RigidBody::with_density(
+        Vec3::new(0.0, 1.0, 0.0),
+        1.0,
+        CollisionShape::Box { half_extents: Vec3::new(0.5, 0.5, 0.5) },
+    );
+    block.orientation = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), 0.1);
+    world.add_body(Body::Rigid(block));
+
+    for _ in 0..300 {
+        world.update(fixed_timestep);
+    }
+
+    let block = world.bodies[1].as_rigid_body().unwrap();
+    let up = block.orientation.rotate(Vec3::new(0.0, 1.0, 0.0));
+    assert!(up.y > 0.9999, "Box did not settle flat: {:?}", up);
+    assert!((block.position.y - 0.5).abs() < 0.02, "Box did not rest on its face: y = {}", block.position.y);
+    assert!(block.angular_velocity.magnitude() < 0.05, "Box is still rocking: {:?}", block.angular_velocity);
+}
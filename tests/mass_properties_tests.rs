@@ -0,0 +1,223 @@
+mod common;
+
+use common::{assert_near, resting_box_world};
+use physics::{
+    Body, CollisionShape, Heightfield, LocalTransform, Mat3, MassProperties, PhysicsWorld, RigidBody, Vec3,
+};
+use std::f32::consts::PI;
+
+#[test]
+fn test_sphere_mass_properties() {
+    let props = MassProperties::from_shape(&CollisionShape::Sphere { radius: 2.0 }, 3.0);
+    // m = density * 4/3 * pi * r^3
+    let expected_mass = 3.0 * (4.0 / 3.0) * PI * 8.0;
+    assert_near(props.mass, expected_mass);
+    assert_near(props.inverse_mass, 1.0 / expected_mass);
+    // I = 2/5 * m * r^2 on every axis
+    let i = 0.4 * expected_mass * 4.0;
+    assert_near(props.local_inertia.rows[0].x, i);
+    assert_near(props.local_inertia.rows[1].y, i);
+    assert_near(props.local_inertia.rows[2].z, i);
+    assert_eq!(props.local_inertia.rows[0].y, 0.0);
+    assert_eq!(props.center_of_mass, Vec3::zero());
+}
+
+#[test]
+fn test_aabb_mass_properties() {
+    let shape = CollisionShape::AABB { min: Vec3::new(-1.0, -0.5, -1.5), max: Vec3::new(1.0, 0.5, 1.5) };
+    let props = MassProperties::from_shape(&shape, 2.0);
+    // 2 x 1 x 3 box
+    assert_eq!(props.mass, 12.0);
+    assert_eq!(props.inverse_mass, 1.0 / 12.0);
+    assert_eq!(props.local_inertia, Mat3::from_diagonal(Vec3::new(10.0, 13.0, 5.0)));
+    assert_eq!(props.center_of_mass, Vec3::zero());
+}
+
+#[test]
+fn test_offset_aabb_center_of_mass() {
+    let shape = CollisionShape::AABB { min: Vec3::new(0.0, 0.0, 0.0), max: Vec3::new(2.0, 4.0, 6.0) };
+    let props = MassProperties::from_shape(&shape, 1.0);
+    assert_eq!(props.center_of_mass, Vec3::new(1.0, 2.0, 3.0));
+}
+
+#[test]
+fn test_plane_mass_properties_are_immovable() {
+    let shape = CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 0.0 };
+    let props = MassProperties::from_shape(&shape, 1.0);
+    assert!(props.mass.is_infinite());
+    assert_eq!(props.inverse_mass, 0.0);
+}
+
+#[test]
+fn test_immovable_rigid_bodies_stay_put() {
+    let plane = CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 0.0 };
+    let terrain = Heightfield::new(4, 4, vec![0.0; 16], Vec3::new(1.0, 1.0, 1.0));
+    let pinned = CollisionShape::Compound(vec![
+        (LocalTransform::identity(), CollisionShape::Box { half_extents: Vec3::new(0.5, 0.5, 0.5) }),
+        (LocalTransform::from_position(Vec3::new(0.0, -5.0, 0.0)), plane.clone()),
+    ]);
+
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let ball = CollisionShape::Sphere { radius: 0.5 };
+    for (position, shape) in [
+        (Vec3::zero(), plane),
+        (Vec3::new(20.0, 0.0, 0.0), CollisionShape::Heightfield(terrain)),
+        (Vec3::new(-20.0, 5.0, 0.0), pinned),
+        (Vec3::new(0.0, 2.0, 0.0), ball),
+    ] {
+        world.add_body(Body::Rigid(RigidBody::with_density(position, 1.0, shape)));
+    }
+    for _ in 0..120 {
+        world.update(1.0 / 60.0);
+    }
+
+    assert_eq!(world.bodies[0].as_rigid_body().unwrap().inverse_mass(), 0.0);
+    assert_eq!(*world.bodies[0].position(), Vec3::zero());
+    assert_eq!(*world.bodies[1].position(), Vec3::new(20.0, 0.0, 0.0));
+    assert_eq!(*world.bodies[2].position(), Vec3::new(-20.0, 5.0, 0.0));
+    // The ball landed on the plane body as on a static one
+    let ball = world.bodies[3].position();
+    assert!((ball.y - 0.5).abs() < 0.05, "Ball at {:?}", ball);
+}
+
+#[test]
+fn test_massless_bodies_keep_their_velocity() {
+    let mut world = resting_box_world();
+    let mut ball = RigidBody::with_mass(Vec3::new(-5.0, 0.5, 0.0), 0.0);
+    ball.velocity = Vec3::new(2.0, 0.0, 0.0);
+    world.add_body(Body::Rigid(ball));
+    for _ in 0..90 {
+        world.update(1.0 / 60.0);
+    }
+    assert!(world.bodies[1].is_sleeping());
+
+    for _ in 0..90 {
+        world.update(1.0 / 60.0);
+    }
+    // Neither gravity nor the box it ran into slowed the ball down
+    let ball = world.bodies[2].as_rigid_body().unwrap();
+    assert_near(ball.position.x, 1.0);
+    assert_eq!(ball.position.y, 0.5);
+    assert_eq!(ball.velocity, Vec3::new(2.0, 0.0, 0.0));
+    // It woke the box and pushed it along
+    assert!(!world.bodies[1].is_sleeping());
+    assert!(world.bodies[1].position().x > 1.9, "Box at {:?}", world.bodies[1].position());
+}
+
+#[test]
+fn test_mass_properties_scale_with_density() {
+    let shape = CollisionShape::Sphere { radius: 0.5 };
+    let light = MassProperties::from_shape(&shape, 1.0);
+    let heavy = MassProperties::from_shape(&shape, 10.0);
+    assert_near(heavy.mass, light.mass * 10.0);
+    assert_near(heavy.local_inertia.rows[0].x, light.local_inertia.rows[0].x * 10.0);
+}
+
+#[test]
+fn test_mass_properties_with_explicit_mass() {
+    let shape = CollisionShape::AABB { min: Vec3::new(-1.0, -1.0, -1.0), max: Vec3::new(1.0, 1.0, 1.0) };
+    let props = MassProperties::from_shape_with_mass(&shape, 6.0);
+    assert_eq!(props.mass, 6.0);
+    // I = m/12 * (4 + 4) = 4
+    assert_near(props.local_inertia.rows[1].y, 4.0);
+}
+
+#[test]
+fn test_rigid_body_with_density() {
+    let crate_shape = CollisionShape::AABB { min: Vec3::new(-1.0, -1.0, -1.0), max: Vec3::new(1.0, 1.0, 1.0) };
+    let pebble_shape = CollisionShape::AABB { min: Vec3::new(-0.1, -0.1, -0.1), max: Vec3::new(0.1, 0.1, 0.1) };
+    let big = RigidBody::with_density(Vec3::zero(), 500.0, crate_shape.clone());
+    let small = RigidBody::with_density(Vec3::zero(), 500.0, pebble_shape);
+
+    assert_near(big.mass, 4000.0);
+    assert_near(small.mass, 4.0);
+    // Same material, 10x the size: 1000x the mass
+    assert_near(big.mass / small.mass, 1000.0);
+    assert_eq!(big.inertia_tensor, MassProperties::from_shape(&crate_shape, 500.0).local_inertia);
+}
+
+#[test]
+fn test_rigid_body_with_shape_uses_mass_properties() {
+    let shape = CollisionShape::Sphere { radius: 1.0 };
+    let body = RigidBody::with_shape(Vec3::zero(), 5.0, shape);
+    assert_eq!(body.mass, 5.0);
+    assert_near(body.inertia_tensor.rows[2].z, 0.4 * 5.0);
+}
+
+#[test]
+fn test_set_mass_properties() {
+    let mut body = RigidBody::new(Vec3::zero());
+    let props = MassProperties::from_shape(
+        &CollisionShape::AABB { min: Vec3::new(0.0, 0.0, 0.0), max: Vec3::new(1.0, 1.0, 1.0) },
+        3.0,
+    );
+    body.set_mass_properties(&props);
+    assert_eq!(body.mass, 3.0);
+    assert_eq!(body.inertia_tensor, props.local_inertia);
+    assert_eq!(body.center_of_mass, Vec3::new(0.5, 0.5, 0.5));
+}
+
+#[test]
+fn test_off_centre_body_spins_about_center_of_mass() {
+    let shape = CollisionShape::Compound(vec![(
+        LocalTransform::from_position(Vec3::new(1.0, 0.0, 0.0)),
+        CollisionShape::Box { half_extents: Vec3::new(1.0, 0.5, 0.5) },
+    )]);
+    let mut body = RigidBody::with_density(Vec3::zero(), 1.0, shape);
+    body.angular_velocity = Vec3::new(0.0, 3.0, 0.0);
+
+    let center_before = body.center_of_mass_world();
+    for _ in 0..60 {
+        body.update(1.0 / 60.0);
+    }
+
+    // Spinning in place keeps the centre of mass fixed while the origin orbits it
+    assert!((body.center_of_mass_world() - center_before).magnitude() < 0.0001);
+    assert!((body.position - Vec3::zero()).magnitude() > 0.1);
+}
+
+#[test]
+fn test_capsule_mass_properties() {
+    let props = MassProperties::from_shape(&CollisionShape::Capsule { half_height: 1.0, radius: 0.5 }, 2.0);
+    let cylinder_mass = 2.0 * PI * 0.25 * 2.0;
+    let caps_mass = 2.0 * (4.0 / 3.0) * PI * 0.125;
+    assert_near(props.mass, cylinder_mass + caps_mass);
+
+    // Spinning about the long axis is easier than tumbling end over end
+    let axial = props.local_inertia.rows[1].y;
+    let transverse = props.local_inertia.rows[0].x;
+    assert_near(axial, cylinder_mass * 0.125 + caps_mass * 0.1);
+    assert!(transverse > axial);
+    assert_eq!(props.local_inertia.rows[2].z, transverse);
+}
+
+#[test]
+fn test_capsule_with_zero_height_is_a_sphere() {
+    let capsule = MassProperties::from_shape(&CollisionShape::Capsule { half_height: 0.0, radius: 0.7 }, 3.0);
+    let sphere = MassProperties::from_shape(&CollisionShape::Sphere { radius: 0.7 }, 3.0);
+    assert_near(capsule.mass, sphere.mass);
+    assert_near(capsule.local_inertia.rows[0].x, sphere.local_inertia.rows[0].x);
+    assert_near(capsule.local_inertia.rows[1].y, sphere.local_inertia.rows[1].y);
+}
+
+#[test]
+fn test_convex_hull_mass_matches_box() {
+    let corners: Vec<Vec3> = (0..8)
+        .map(|i| {
+            let pick = |bit: i32, half: f32| if i & bit == 0 { -half } else { half };
+            // Offset so the centre of mass is not at the origin
+            Vec3::new(pick(1, 1.0), pick(2, 0.5), pick(4, 1.5)) + Vec3::new(0.0, 2.0, 0.0)
+        })
+        .collect();
+    let hull = MassProperties::from_shape(&CollisionShape::convex_hull(&corners), 2.0);
+    let cube = MassProperties::from_shape(
+        &CollisionShape::Box { half_extents: Vec3::new(1.0, 0.5, 1.5) },
+        2.0,
+    );
+
+    assert_near(hull.mass, cube.mass);
+    assert!((hull.center_of_mass - Vec3::new(0.0, 2.0, 0.0)).magnitude() < 0.0001);
+    for row in 0..3 {
+        assert!((hull.local_inertia.rows[row] - cube.local_inertia.rows[row]).magnitude() < 0.001);
+    }
+}
@@ -0,0 +1,197 @@
+use physics::{Mat3, Quat, Vec3};
+use std::f32::consts::FRAC_PI_2;
+
+fn assert_mat3_near(a: Mat3, b: Mat3) {
+    for i in 0..3 {
+        assert!((a.rows[i] - b.rows[i]).magnitude() < 0.0001, "{:?} != {:?}", a, b);
+    }
+}
+
+#[test]
+fn test_mat3_creation() {
+    let m = Mat3::new(
+        Vec3::new(1.0, 2.0, 3.0),
+        Vec3::new(4.0, 5.0, 6.0),
+        Vec3::new(7.0, 8.0, 9.0),
+    );
+    assert_eq!(m.rows[0], Vec3::new(1.0, 2.0, 3.0));
+    assert_eq!(m.rows[1], Vec3::new(4.0, 5.0, 6.0));
+    assert_eq!(m.rows[2], Vec3::new(7.0, 8.0, 9.0));
+}
+
+#[test]
+fn test_mat3_identity() {
+    let m = Mat3::identity();
+    let v = Vec3::new(1.0, -2.0, 3.0);
+    assert_eq!(m * v, v);
+}
+
+#[test]
+fn test_mat3_zero() {
+    let v = Vec3::new(1.0, -2.0, 3.0);
+    assert_eq!(Mat3::zero() * v, Vec3::zero());
+}
+
+#[test]
+fn test_mat3_from_diagonal() {
+    let m = Mat3::from_diagonal(Vec3::new(2.0, 3.0, 4.0));
+    assert_eq!(m * Vec3::new(1.0, 1.0, 1.0), Vec3::new(2.0, 3.0, 4.0));
+    assert_eq!(m.rows[0].y, 0.0);
+}
+
+#[test]
+fn test_mat3_vector_multiplication() {
+    let m = Mat3::new(
+        Vec3::new(1.0, 2.0, 3.0),
+        Vec3::new(4.0, 5.0, 6.0),
+        Vec3::new(7.0, 8.0, 9.0),
+    );
+    assert_eq!(m * Vec3::new(1.0, 0.0, -1.0), Vec3::new(-2.0, -2.0, -2.0));
+}
+
+#[test]
+fn test_mat3_matrix_multiplication() {
+    let a = Mat3::new(
+        Vec3::new(1.0, 2.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Vec3::new(0.0, 0.0, 2.0),
+    );
+    let b = Mat3::new(
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(3.0, 1.0, 0.0),
+        Vec3::new(0.0, 0.0, 1.0),
+    );
+    let expected = Mat3::new(
+        Vec3::new(7.0, 2.0, 0.0),
+        Vec3::new(3.0, 1.0, 0.0),
+        Vec3::new(0.0, 0.0, 2.0),
+    );
+    assert_eq!(a * b, expected);
+}
+
+#[test]
+fn test_mat3_multiplication_matches_composition() {
+    let a = Mat3::from_quat(Quat::from_axis_angle(Vec3::new(1.0, 2.0, 0.0), 0.6));
+    let b = Mat3::from_diagonal(Vec3::new(2.0, 1.0, 0.5));
+    let v = Vec3::new(0.3, -1.0, 2.0);
+    assert!(((a * b) * v - a * (b * v)).magnitude() < 0.0001);
+}
+
+#[test]
+fn test_mat3_scalar_multiplication() {
+    let m = Mat3::identity() * 3.0;
+    assert_eq!(m, Mat3::from_diagonal(Vec3::new(3.0, 3.0, 3.0)));
+}
+
+#[test]
+fn test_mat3_transpose() {
+    let m = Mat3::new(
+        Vec3::new(1.0, 2.0, 3.0),
+        Vec3::new(4.0, 5.0, 6.0),
+        Vec3::new(7.0, 8.0, 9.0),
+    );
+    let t = m.transpose();
+    assert_eq!(t.rows[0], Vec3::new(1.0, 4.0, 7.0));
+    assert_eq!(t.rows[1], Vec3::new(2.0, 5.0, 8.0));
+    assert_eq!(t.rows[2], Vec3::new(3.0, 6.0, 9.0));
+    assert_eq!(t.transpose(), m);
+}
+
+#[test]
+fn test_mat3_column() {
+    let m = Mat3::new(
+        Vec3::new(1.0, 2.0, 3.0),
+        Vec3::new(4.0, 5.0, 6.0),
+        Vec3::new(7.0, 8.0, 9.0),
+    );
+    assert_eq!(m.column(1), Vec3::new(2.0, 5.0, 8.0));
+}
+
+#[test]
+fn test_mat3_determinant() {
+    assert_eq!(Mat3::identity().determinant(), 1.0);
+    assert_eq!(Mat3::from_diagonal(Vec3::new(2.0, 3.0, 4.0)).determinant(), 24.0);
+    let m = Mat3::new(
+        Vec3::new(2.0, 0.0, 1.0),
+        Vec3::new(1.0, 3.0, 2.0),
+        Vec3::new(1.0, 1.0, 2.0),
+    );
+    assert_eq!(m.determinant(), 6.0);
+}
+
+#[test]
+fn test_mat3_determinant_singular() {
+    let m = Mat3::new(
+        Vec3::new(1.0, 2.0, 3.0),
+        Vec3::new(4.0, 5.0, 6.0),
+        Vec3::new(7.0, 8.0, 9.0),
+    );
+    assert_eq!(m.determinant(), 0.0);
+}
+
+#[test]
+fn test_mat3_inverse() {
+    let m = Mat3::new(
+        Vec3::new(2.0, 0.0, 1.0),
+        Vec3::new(1.0, 3.0, 2.0),
+        Vec3::new(1.0, 1.0, 2.0),
+    );
+    assert_mat3_near(m * m.inverse(), Mat3::identity());
+    assert_mat3_near(m.inverse() * m, Mat3::identity());
+}
+
+#[test]
+fn test_mat3_inverse_diagonal() {
+    let m = Mat3::from_diagonal(Vec3::new(2.0, 4.0, 0.5));
+    assert_eq!(m.inverse(), Mat3::from_diagonal(Vec3::new(0.5, 0.25, 2.0)));
+}
+
+#[test]
+fn test_mat3_inverse_singular_is_zero() {
+    assert_eq!(Mat3::zero().inverse(), Mat3::zero());
+}
+
+#[test]
+fn test_mat3_skew_symmetric_matches_cross() {
+    let a = Vec3::new(1.0, 2.0, 3.0);
+    let b = Vec3::new(-4.0, 0.5, 2.0);
+    assert_eq!(Mat3::skew_symmetric(a) * b, a.cross(&b));
+    assert_eq!(Mat3::skew_symmetric(a).transpose(), Mat3::skew_symmetric(-a));
+}
+
+#[test]
+fn test_mat3_from_quat_identity() {
+    assert_eq!(Mat3::from_quat(Quat::identity()), Mat3::identity());
+}
+
+#[test]
+fn test_mat3_from_quat_matches_rotation() {
+    let q = Quat::from_axis_angle(Vec3::new(1.0, -2.0, 0.5), 1.2);
+    let m = Mat3::from_quat(q);
+    let v = Vec3::new(0.7, 1.5, -3.0);
+    assert!((m * v - q.rotate(v)).magnitude() < 0.0001);
+}
+
+#[test]
+fn test_mat3_rotation_is_orthonormal() {
+    let m = Mat3::from_quat(Quat::from_axis_angle(Vec3::new(0.0, 1.0, 1.0), 0.9));
+    assert_mat3_near(m * m.transpose(), Mat3::identity());
+    assert!((m.determinant() - 1.0).abs() < 0.0001);
+}
+
+#[test]
+fn test_mat3_rotate_tensor() {
+    // Inertia of a rod along x, rotated a quarter turn about z, lies along y
+    let local = Mat3::from_diagonal(Vec3::new(1.0, 5.0, 5.0));
+    let rotation = Mat3::from_quat(Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), FRAC_PI_2));
+    let world = local.rotate_tensor(&rotation);
+    assert_mat3_near(world, Mat3::from_diagonal(Vec3::new(5.0, 1.0, 5.0)));
+}
+
+#[test]
+fn test_mat3_rotate_tensor_preserves_symmetry() {
+    let local = Mat3::from_diagonal(Vec3::new(1.0, 2.0, 3.0));
+    let rotation = Mat3::from_quat(Quat::from_axis_angle(Vec3::new(1.0, 1.0, 1.0), 0.4));
+    let world = local.rotate_tensor(&rotation);
+    assert_mat3_near(world, world.transpose());
+}
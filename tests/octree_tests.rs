@@ -0,0 +1,147 @@
+mod common;
+
+use common::Lcg;
+use physics::{Body, CollisionShape, DynamicAabbTree, Octree, PhysicsWorld, RigidBody, StaticBody, Vec3};
+
+fn overlaps(a: (Vec3, Vec3), b: (Vec3, Vec3)) -> bool {
+    a.0.x <= b.1.x && a.1.x >= b.0.x && a.0.y <= b.1.y && a.1.y >= b.0.y && a.0.z <= b.1.z && a.1.z >= b.0.z
+}
+
+fn random_items(rng: &mut Lcg, count: usize, spread: f32) -> Vec<(usize, Vec3, Vec3)> {
+    (0..count)
+        .map(|id| {
+            let center = Vec3::new(rng.range(-spread, spread), rng.range(-spread, spread), rng.range(-spread, spread));
+            let half = Vec3::new(rng.range(0.1, 1.5), rng.range(0.1, 1.5), rng.range(0.1, 1.5));
+            (id * 3, center - half, center + half)
+        })
+        .collect()
+}
+
+// Octree
+#[test]
+fn test_octree_queries_match_brute_force() {
+    let mut rng = Lcg(21);
+    let items = random_items(&mut rng, 2000, 50.0);
+    let octree = Octree::build(&items, 6, 4);
+    assert_eq!(octree.len(), 2000);
+    assert!(octree.depth() > 0);
+
+    for _ in 0..50 {
+        let center = Vec3::new(rng.range(-50.0, 50.0), rng.range(-50.0, 50.0), rng.range(-50.0, 50.0));
+        let half = Vec3::new(rng.range(0.5, 8.0), rng.range(0.5, 8.0), rng.range(0.5, 8.0));
+        let query = (center - half, center + half);
+        let expected: Vec<usize> =
+            items.iter().filter(|(_, min, max)| overlaps((*min, *max), query)).map(|(id, _, _)| *id).collect();
+        assert_eq!(octree.query_aabb(query.0, query.1), expected);
+    }
+}
+
+#[test]
+fn test_octree_depth_and_leaf_capacity() {
+    let items = random_items(&mut Lcg(4), 500, 20.0);
+
+    // Room for everything in the root
+    let flat = Octree::build(&items, 8, 500);
+    assert_eq!(flat.depth(), 0);
+
+    let capped = Octree::build(&items, 2, 1);
+    assert_eq!(capped.depth(), 2);
+    assert_eq!(capped.max_depth(), 2);
+    assert_eq!(capped.leaf_capacity(), 1);
+
+    // Many boxes in one spot cannot be told apart, so the depth limit stops the splitting
+    let pile: Vec<(usize, Vec3, Vec3)> =
+        (0..100).map(|id| (id, Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.01, 0.01, 0.01))).collect();
+    let mut with_corner = pile.clone();
+    with_corner.push((100, Vec3::new(10.0, 10.0, 10.0), Vec3::new(11.0, 11.0, 11.0)));
+    assert_eq!(Octree::build(&with_corner, 5, 4).depth(), 5);
+}
+
+#[test]
+fn test_octree_returns_unbounded_items_everywhere() {
+    let infinite = Vec3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+    let mut items = random_items(&mut Lcg(8), 20, 10.0);
+    items.push((1000, -infinite, infinite));
+    let octree = Octree::build(&items, 4, 2);
+    let far = Vec3::new(500.0, 500.0, 500.0);
+    assert_eq!(octree.query_aabb(far, far + Vec3::new(1.0, 1.0, 1.0)), vec![1000]);
+    assert!(Octree::build(&[], 4, 2).query_aabb(-far, far).is_empty());
+}
+
+// World
+fn level_world(max_depth: usize, leaf_capacity: usize) -> PhysicsWorld {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.octree_max_depth = max_depth;
+    world.octree_leaf_capacity = leaf_capacity;
+    // A floor of static tiles with a few rigid bodies dropped on it
+    for x in 0..30 {
+        for z in 0..30 {
+            world.add_body(Body::Static(StaticBody::new(
+                Vec3::new(x as f32 - 15.0, -0.5, z as f32 - 15.0),
+                CollisionShape::Box { half_extents: Vec3::new(0.5, 0.5, 0.5) },
+            )));
+        }
+    }
+    let mut rng = Lcg(99);
+    for _ in 0..40 {
+        let position = Vec3::new(rng.range(-10.0, 10.0), rng.range(1.0, 6.0), rng.range(-10.0, 10.0));
+        world.add_body(Body::Rigid(RigidBody::with_density(position, 1.0, CollisionShape::Sphere { radius: 0.4 })));
+    }
+    world
+}
+
+#[test]
+fn test_world_builds_octree_from_statics() {
+    let mut world = level_world(8, 8);
+    world.update(1.0 / 60.0);
+    assert_eq!(world.static_octree().len(), 900);
+    assert!(world.static_octree().depth() > 0);
+
+    // Statics added later are picked up on the next step
+    world.add_body(Body::Static(StaticBody::new(
+        Vec3::new(0.0, 0.0, 20.0),
+        CollisionShape::Box { half_extents: Vec3::new(0.5, 0.5, 0.5) },
+    )));
+    world.update(1.0 / 60.0);
+    assert_eq!(world.static_octree().len(), 901);
+
+    // And statics moved after the octree was built
+    let moved = Vec3::new(0.0, 0.0, -20.0);
+    world.bodies[0].as_static_body_mut().unwrap().position = moved;
+    assert_eq!(world.query_aabb(moved, moved), vec![0]);
+
+    // So are new settings
+    world.octree_leaf_capacity = 32;
+    world.update(1.0 / 60.0);
+    assert_eq!(world.static_octree().leaf_capacity(), 32);
+}
+
+#[test]
+fn test_world_results_do_not_depend_on_octree_settings() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut deep = level_world(8, 2);
+    let mut flat = level_world(0, 10_000);
+    flat.broadphase = Box::new(DynamicAabbTree::new());
+    for _ in 0..120 {
+        deep.update(fixed_timestep);
+        flat.update(fixed_timestep);
+    }
+    assert_eq!(deep.contacts().len(), flat.contacts().len());
+    for (a, b) in deep.bodies.iter().zip(&flat.bodies) {
+        assert_eq!(a.position(), b.position());
+    }
+    // None of the spheres fell through the tiles
+    for body in &deep.bodies[900..] {
+        assert!(body.position().y > 0.3, "Sphere at {:?}", body.position());
+    }
+}
+
+#[test]
+fn test_world_queries_include_statics() {
+    let mut world = level_world(8, 8);
+    world.update(1.0 / 60.0);
+    let found = world.query_aabb(Vec3::new(0.1, -0.1, 0.1), Vec3::new(0.9, 0.1, 0.9));
+    // The tiles at (0, 0), (0, 1), (1, 0) and (1, 1)
+    assert_eq!(found, vec![465, 466, 495, 496]);
+    assert!(found.iter().all(|&index| world.bodies[index].as_static_body().is_some()));
+}
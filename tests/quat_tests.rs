@@ -0,0 +1,148 @@
+mod common;
+
+use common::assert_vec_near;
+use physics::{CollisionShape, Quat, RigidBody, StaticBody, Vec3};
+use std::f32::consts::{FRAC_PI_2, PI};
+
+fn assert_same_rotation(a: Quat, b: Quat) {
+    // q and -q describe the same rotation
+    assert!((a.dot(&b).abs() - 1.0).abs() < 0.0001, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn test_quat_identity() {
+    let q = Quat::identity();
+    assert_eq!(q.w, 1.0);
+    assert_eq!(q.x, 0.0);
+    assert_eq!(q.y, 0.0);
+    assert_eq!(q.z, 0.0);
+}
+
+#[test]
+fn test_quat_identity_rotation() {
+    let v = Vec3::new(1.0, 2.0, 3.0);
+    assert_eq!(Quat::identity().rotate(v), v);
+}
+
+#[test]
+fn test_quat_from_axis_angle_is_unit() {
+    let q = Quat::from_axis_angle(Vec3::new(1.0, 2.0, 3.0), 1.3);
+    assert!((q.magnitude() - 1.0).abs() < 0.0001);
+}
+
+#[test]
+fn test_quat_rotate_about_y() {
+    let q = Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), FRAC_PI_2);
+    // Right-handed: +x rotates to -z around +y
+    assert_vec_near(q.rotate(Vec3::new(1.0, 0.0, 0.0)), Vec3::new(0.0, 0.0, -1.0));
+    assert_vec_near(q * Vec3::new(0.0, 0.0, 1.0), Vec3::new(1.0, 0.0, 0.0));
+}
+
+#[test]
+fn test_quat_rotate_about_z() {
+    let q = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), FRAC_PI_2);
+    assert_vec_near(q.rotate(Vec3::new(1.0, 0.0, 0.0)), Vec3::new(0.0, 1.0, 0.0));
+}
+
+#[test]
+fn test_quat_rotate_preserves_length() {
+    let q = Quat::from_axis_angle(Vec3::new(1.0, -1.0, 0.5), 2.1);
+    let v = Vec3::new(3.0, -4.0, 12.0);
+    assert!((q.rotate(v).magnitude() - 13.0).abs() < 0.0001);
+}
+
+#[test]
+fn test_quat_multiplication_composes_rotations() {
+    let a = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), FRAC_PI_2);
+    let b = Quat::from_axis_angle(Vec3::new(1.0, 0.0, 0.0), FRAC_PI_2);
+    let v = Vec3::new(0.0, 1.0, 0.0);
+    // (a * b) applies b first, then a
+    assert_vec_near((a * b).rotate(v), a.rotate(b.rotate(v)));
+}
+
+#[test]
+fn test_quat_multiplication_same_axis_adds_angles() {
+    let axis = Vec3::new(0.0, 1.0, 0.0);
+    let q = Quat::from_axis_angle(axis, 0.3) * Quat::from_axis_angle(axis, 0.5);
+    assert_same_rotation(q, Quat::from_axis_angle(axis, 0.8));
+}
+
+#[test]
+fn test_quat_multiply_by_identity() {
+    let q = Quat::from_axis_angle(Vec3::new(1.0, 1.0, 0.0), 0.7);
+    assert_eq!(q * Quat::identity(), q);
+    assert_eq!(Quat::identity() * q, q);
+}
+
+#[test]
+fn test_quat_inverse_undoes_rotation() {
+    let q = Quat::from_axis_angle(Vec3::new(2.0, -1.0, 0.5), 1.1);
+    let v = Vec3::new(1.0, 2.0, 3.0);
+    assert_vec_near(q.inverse().rotate(q.rotate(v)), v);
+    assert_same_rotation(q * q.conjugate(), Quat::identity());
+}
+
+#[test]
+fn test_quat_normalize() {
+    let q = Quat::new(2.0, 0.0, 0.0, 0.0).normalize();
+    assert_eq!(q, Quat::identity());
+    let q = Quat::new(1.0, 1.0, 1.0, 1.0).normalize();
+    assert!((q.magnitude() - 1.0).abs() < 0.0001);
+}
+
+#[test]
+fn test_quat_normalize_zero() {
+    assert_eq!(Quat::new(0.0, 0.0, 0.0, 0.0).normalize(), Quat::identity());
+}
+
+#[test]
+fn test_quat_slerp_endpoints() {
+    let a = Quat::identity();
+    let b = Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), FRAC_PI_2);
+    assert_same_rotation(a.slerp(&b, 0.0), a);
+    assert_same_rotation(a.slerp(&b, 1.0), b);
+}
+
+#[test]
+fn test_quat_slerp_halfway() {
+    let axis = Vec3::new(0.0, 1.0, 0.0);
+    let a = Quat::identity();
+    let b = Quat::from_axis_angle(axis, FRAC_PI_2);
+    assert_same_rotation(a.slerp(&b, 0.5), Quat::from_axis_angle(axis, FRAC_PI_2 * 0.5));
+}
+
+#[test]
+fn test_quat_slerp_takes_shortest_path() {
+    let axis = Vec3::new(0.0, 0.0, 1.0);
+    let a = Quat::from_axis_angle(axis, 0.1);
+    // Same rotation as angle -0.1, expressed on the far hemisphere
+    let b = Quat::from_axis_angle(axis, 2.0 * PI - 0.1);
+    let mid = a.slerp(&b, 0.5);
+    assert_same_rotation(mid, Quat::identity());
+}
+
+#[test]
+fn test_quat_integrate_constant_angular_velocity() {
+    let omega = Vec3::new(0.0, FRAC_PI_2, 0.0); // quarter turn per second
+    let mut q = Quat::identity();
+    let dt = 0.001;
+    for _ in 0..1000 {
+        q = q.integrate(omega, dt);
+    }
+    assert!((q.magnitude() - 1.0).abs() < 0.0001);
+    assert_vec_near(q.rotate(Vec3::new(1.0, 0.0, 0.0)), Vec3::new(0.0, 0.0, -1.0));
+}
+
+#[test]
+fn test_quat_integrate_zero_angular_velocity() {
+    let q = Quat::from_axis_angle(Vec3::new(1.0, 0.0, 0.0), 0.4);
+    assert_same_rotation(q.integrate(Vec3::zero(), 1.0), q);
+}
+
+#[test]
+fn test_bodies_default_to_identity_orientation() {
+    let rigid = RigidBody::new(Vec3::zero());
+    let fixed = StaticBody::new(Vec3::zero(), CollisionShape::Sphere { radius: 1.0 });
+    assert_eq!(rigid.orientation, Quat::identity());
+    assert_eq!(fixed.orientation, Quat::identity());
+}
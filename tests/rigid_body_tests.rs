@@ -1,4 +1,4 @@
-use physics::{RigidBody, Vec3};
+use physics::{CollisionShape, Mat3, Quat, RigidBody, Vec3};
 
 #[test]
 fn test_rigid_body_creation() {
@@ -226,3 +226,118 @@ fn test_clear_forces() {
     assert_eq!(body.acceleration, Vec3::zero());
     assert_eq!(body.velocity, Vec3::zero());
 }
+
+// Rotational Dynamics Tests
+#[test]
+fn test_rigid_body_default_angular_state() {
+    let body = RigidBody::new(Vec3::zero());
+    assert_eq!(body.angular_velocity, Vec3::zero());
+    assert_eq!(body.orientation, Quat::identity());
+    // Solid sphere: I = 2/5 * m * r^2 = 0.4 * 1 * 0.25
+    assert_eq!(body.inertia_tensor, Mat3::from_diagonal(Vec3::new(0.1, 0.1, 0.1)));
+}
+
+#[test]
+fn test_rigid_body_box_inertia() {
+    let body = RigidBody::with_shape(
+        Vec3::zero(),
+        12.0,
+        CollisionShape::AABB { min: Vec3::new(-1.0, -0.5, -1.5), max: Vec3::new(1.0, 0.5, 1.5) },
+    );
+    // I = m/12 * (h^2 + d^2) etc. for a 2 x 1 x 3 box
+    assert_eq!(body.inertia_tensor, Mat3::from_diagonal(Vec3::new(10.0, 13.0, 5.0)));
+}
+
+#[test]
+fn test_apply_torque() {
+    let mut body = RigidBody::new(Vec3::zero());
+    body.apply_torque(Vec3::new(0.0, 0.1, 0.0));
+    body.update(1.0);
+
+    // alpha = I^-1 * tau = 0.1 / 0.1 = 1 rad/s^2
+    assert!((body.angular_velocity - Vec3::new(0.0, 1.0, 0.0)).magnitude() < 0.0001);
+    // Torque produces no linear motion
+    assert_eq!(body.velocity, Vec3::zero());
+    assert_eq!(body.position, Vec3::zero());
+}
+
+#[test]
+fn test_torque_cleared_after_update() {
+    let mut body = RigidBody::new(Vec3::zero());
+    body.apply_torque(Vec3::new(0.0, 0.0, 0.5));
+    body.update(0.1);
+    let spin = body.angular_velocity;
+
+    body.update(0.1);
+    assert_eq!(body.angular_velocity, spin);
+}
+
+#[test]
+fn test_apply_force_at_centre_has_no_spin() {
+    let mut body = RigidBody::new(Vec3::new(1.0, 2.0, 3.0));
+    body.apply_force_at_point(Vec3::new(5.0, 0.0, 0.0), Vec3::new(1.0, 2.0, 3.0));
+    body.update(1.0);
+
+    assert_eq!(body.velocity, Vec3::new(5.0, 0.0, 0.0));
+    assert_eq!(body.angular_velocity, Vec3::zero());
+}
+
+#[test]
+fn test_apply_force_at_point_off_centre() {
+    let mut body = RigidBody::new(Vec3::zero());
+    // Push +x at the top of the sphere: r = (0, 0.5, 0), tau = r x F = (0, 0, -0.5)
+    body.apply_force_at_point(Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 0.5, 0.0));
+    body.update(1.0);
+
+    // Full linear response, as if applied at the centre
+    assert_eq!(body.velocity, Vec3::new(1.0, 0.0, 0.0));
+    // Angular response: alpha = -0.5 / 0.1 = -5 about z
+    assert!((body.angular_velocity - Vec3::new(0.0, 0.0, -5.0)).magnitude() < 0.0001);
+}
+
+#[test]
+fn test_update_integrates_orientation() {
+    let mut body = RigidBody::new(Vec3::zero());
+    body.angular_velocity = Vec3::new(0.0, std::f32::consts::FRAC_PI_2, 0.0);
+
+    for _ in 0..100 {
+        body.update(0.01);
+    }
+
+    // A quarter turn about y takes +x to -z
+    let rotated = body.orientation.rotate(Vec3::new(1.0, 0.0, 0.0));
+    assert!((rotated - Vec3::new(0.0, 0.0, -1.0)).magnitude() < 0.001);
+}
+
+#[test]
+fn test_aabb_bodies_do_not_rotate() {
+    let mut body = RigidBody::with_shape(
+        Vec3::zero(),
+        1.0,
+        CollisionShape::AABB { min: Vec3::new(-0.5, -0.5, -0.5), max: Vec3::new(0.5, 0.5, 0.5) },
+    );
+    body.apply_torque(Vec3::new(0.0, 0.0, 2.0));
+    body.angular_velocity = Vec3::new(1.0, 0.0, 0.0);
+    body.update(1.0);
+
+    assert_eq!(body.angular_velocity, Vec3::new(1.0, 0.0, 0.0));
+    assert_eq!(body.orientation, Quat::identity());
+}
+
+#[test]
+fn test_torque_uses_world_space_inertia() {
+    // Long thin box along x: easy to spin about x, hard about y and z
+    let mut body = RigidBody::with_shape(
+        Vec3::zero(),
+        12.0,
+        CollisionShape::Box { half_extents: Vec3::new(2.0, 0.5, 0.5) },
+    );
+    // Rotate the box so its long axis lies along world z
+    body.orientation = Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), std::f32::consts::FRAC_PI_2);
+
+    body.apply_torque(Vec3::new(0.0, 0.0, 2.0));
+    body.update(1.0);
+
+    // Torque about world z is now about the box's long axis: I = 12/12 * (1 + 1) = 2
+    assert!((body.angular_velocity - Vec3::new(0.0, 0.0, 1.0)).magnitude() < 0.0001);
+}
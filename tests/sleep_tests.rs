@@ -0,0 +1,256 @@
+mod common;
+
+use common::{ground, unit_box};
+use physics::{Body, CollisionShape, PhysicsWorld, RigidBody, StaticBody, Vec3};
+
+fn step(world: &mut PhysicsWorld, steps: usize) {
+    for _ in 0..steps {
+        world.update(1.0 / 60.0);
+    }
+}
+
+// A box dropped onto the ground, left long enough to fall asleep
+fn sleeping_box_world() -> PhysicsWorld {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.restitution = 0.0;
+    world.add_body(ground());
+    world.add_body(unit_box(Vec3::new(0.0, 0.6, 0.0)));
+    step(&mut world, 120);
+    world
+}
+
+#[test]
+fn test_sleep_defaults() {
+    let world = PhysicsWorld::new(1.0 / 60.0);
+    assert!(world.allow_sleeping);
+    assert_eq!(world.sleep_linear_threshold, 0.1);
+    assert_eq!(world.sleep_angular_threshold, 0.1);
+    assert_eq!(world.sleep_time, 0.5);
+    assert!(!RigidBody::new(Vec3::zero()).is_sleeping());
+    assert!(!ground().is_sleeping());
+}
+
+#[test]
+fn test_resting_body_falls_asleep() {
+    let mut world = sleeping_box_world();
+    assert!(world.bodies[1].is_sleeping());
+    let block = world.bodies[1].as_rigid_body().unwrap();
+    assert_eq!(block.velocity, Vec3::zero());
+    assert!((block.position.y - 0.5).abs() < 0.02, "Box at y = {}", block.position.y);
+
+    // Nothing is integrated or tested while it sleeps, and its contact is
+    // kept as it was
+    let position = *world.bodies[1].position();
+    let steps = world.contacts().touching_steps(0, 1);
+    assert!(steps > 0);
+    step(&mut world, 60);
+    assert_eq!(*world.bodies[1].position(), position);
+    assert_eq!(world.contacts().touching_steps(0, 1), steps);
+}
+
+#[test]
+fn test_sleeping_can_be_turned_off() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.allow_sleeping = false;
+    world.add_body(ground());
+    world.add_body(unit_box(Vec3::new(0.0, 0.5, 0.0)));
+    step(&mut world, 300);
+    assert!(!world.bodies[1].is_sleeping());
+    assert_eq!(world.contacts().len(), 1);
+}
+
+#[test]
+fn test_body_must_stay_slow_for_the_sleep_time() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.sleep_time = 2.0;
+    world.add_body(ground());
+    world.add_body(unit_box(Vec3::new(0.0, 0.5, 0.0)));
+    step(&mut world, 90);
+    assert!(!world.bodies[1].is_sleeping());
+    step(&mut world, 60);
+    assert!(world.bodies[1].is_sleeping());
+}
+
+#[test]
+fn test_slow_moving_body_stays_awake_while_it_keeps_moving() {
+    let mut world = PhysicsWorld::with_gravity(1.0 / 60.0, Vec3::zero());
+    let mut drifting = RigidBody::new(Vec3::zero());
+    drifting.velocity = Vec3::new(0.2, 0.0, 0.0);
+    world.add_body(Body::Rigid(drifting));
+    step(&mut world, 120);
+    assert!(!world.bodies[0].is_sleeping());
+    assert!((world.bodies[0].position().x - 0.4).abs() < 1.0e-3);
+}
+
+#[test]
+fn test_sleeping_body_wakes_on_force() {
+    let mut world = sleeping_box_world();
+    let block = world.bodies[1].as_rigid_body_mut().unwrap();
+    block.apply_force(Vec3::new(0.0, 500.0, 0.0));
+    assert!(!block.is_sleeping());
+    step(&mut world, 1);
+    assert!(world.bodies[1].position().y > 0.5);
+}
+
+#[test]
+fn test_sleeping_body_wakes_on_request() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.add_body(unit_box(Vec3::new(0.0, 5.0, 0.0)));
+    // Asleep in mid-air it stays there, since gravity is not applied
+    world.bodies[0].as_rigid_body_mut().unwrap().sleep();
+    step(&mut world, 30);
+    assert_eq!(world.bodies[0].position().y, 5.0);
+
+    world.bodies[0].wake();
+    assert_eq!(world.bodies[0].as_rigid_body().unwrap().sleep_timer(), 0.0);
+    step(&mut world, 30);
+    assert!(world.bodies[0].position().y < 4.0);
+}
+
+#[test]
+fn test_moving_body_wakes_sleeping_one_it_hits() {
+    let mut world = sleeping_box_world();
+    assert!(world.bodies[1].is_sleeping());
+    world.add_body(Body::Rigid(RigidBody::with_density(
+        Vec3::new(0.0, 3.0, 0.0),
+        1.0,
+        CollisionShape::Sphere { radius: 0.5 },
+    )));
+
+    let mut woke = false;
+    for _ in 0..60 {
+        step(&mut world, 1);
+        woke |= !world.bodies[1].is_sleeping();
+    }
+    assert!(woke);
+    // The ball came to rest on the box rather than falling through it
+    assert!(world.bodies[2].position().y > 1.4, "Ball at {:?}", world.bodies[2].position());
+}
+
+#[test]
+fn test_slow_moving_body_wakes_sleeping_one_it_pushes() {
+    let mut world = PhysicsWorld::with_gravity(1.0 / 60.0, Vec3::zero());
+    world.restitution = 0.0;
+    world.add_body(unit_box(Vec3::zero()));
+    world.bodies[0].as_rigid_body_mut().unwrap().sleep();
+    // Slower than the sleep threshold, and slow for a few steps before it touches
+    let mut pusher = RigidBody::with_density(
+        Vec3::new(-1.01, 0.0, 0.0),
+        1.0,
+        CollisionShape::Box { half_extents: Vec3::new(0.5, 0.5, 0.5) },
+    );
+    pusher.velocity = Vec3::new(0.05, 0.0, 0.0);
+    world.add_body(Body::Rigid(pusher));
+
+    step(&mut world, 20);
+    assert!(!world.bodies[0].is_sleeping());
+    assert!(world.bodies[0].position().x > 0.0, "Box at {:?}", world.bodies[0].position());
+}
+
+// Two boxes stacked on the ground, both asleep
+fn sleeping_stack_world() -> PhysicsWorld {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.restitution = 0.0;
+    world.add_body(ground());
+    world.add_body(unit_box(Vec3::new(0.0, 0.5, 0.0)));
+    world.add_body(unit_box(Vec3::new(0.0, 1.5, 0.0)));
+    step(&mut world, 120);
+    assert!(world.bodies[1].is_sleeping() && world.bodies[2].is_sleeping());
+    world
+}
+
+#[test]
+fn test_woken_stack_is_warm_started() {
+    let mut world = sleeping_stack_world();
+    let steps = world.contacts().touching_steps(1, 2);
+    let held: f32 = world.contacts().pair(1, 2).unwrap().contacts.iter().map(|contact| contact.normal_impulse).sum();
+    assert!(held < 0.0);
+
+    // The top box wakes the one below, and both pick up where they left off
+    world.bodies[2].wake();
+    step(&mut world, 1);
+    assert!(!world.bodies[1].is_sleeping());
+    assert_eq!(world.contacts().touching_steps(1, 2), steps + 1);
+    assert_eq!(world.contacts().touching_steps(0, 1), steps + 1);
+    // Contacts found again started from the impulses they had when it fell asleep
+    for contact in &world.contacts().pair(0, 1).unwrap().contacts {
+        assert!(contact.age > 1);
+    }
+    for level in 1..3 {
+        let velocity = world.bodies[level].as_rigid_body().unwrap().velocity;
+        assert!(velocity.magnitude() < 0.02, "Box {} moving at {:?}", level, velocity);
+    }
+}
+
+#[test]
+fn test_removing_a_support_wakes_what_rested_on_it() {
+    let mut world = sleeping_stack_world();
+    world.remove_body(1);
+    // The top box moved down to index 1
+    assert!(!world.bodies[1].is_sleeping());
+    assert_eq!(world.contacts().len(), 0);
+    step(&mut world, 60);
+    let y = world.bodies[1].position().y;
+    assert!((y - 0.5).abs() < 0.02, "Box at y = {}", y);
+}
+
+#[test]
+fn test_removing_bodies_directly_wakes_every_body() {
+    let mut world = sleeping_stack_world();
+    world.bodies.remove(1);
+    step(&mut world, 60);
+    let y = world.bodies[1].position().y;
+    assert!((y - 0.5).abs() < 0.02, "Box at y = {}", y);
+}
+
+#[test]
+fn test_losing_a_contact_wakes_both_bodies() {
+    let mut world = sleeping_stack_world();
+    // Slid out from under the top box while both sleep
+    world.bodies[1].as_rigid_body_mut().unwrap().position = Vec3::new(5.0, 0.5, 0.0);
+    step(&mut world, 1);
+    assert!(!world.bodies[1].is_sleeping());
+    assert!(!world.bodies[2].is_sleeping());
+    step(&mut world, 60);
+    let y = world.bodies[2].position().y;
+    assert!((y - 0.5).abs() < 0.02, "Box at y = {}", y);
+}
+
+#[test]
+fn test_moving_a_static_body_wakes_what_rested_on_it() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.restitution = 0.0;
+    world.add_body(ground());
+    world.add_body(Body::Static(StaticBody::new(
+        Vec3::new(0.0, 0.5, 0.0),
+        CollisionShape::Box { half_extents: Vec3::new(0.5, 0.5, 0.5) },
+    )));
+    world.add_body(unit_box(Vec3::new(0.0, 1.5, 0.0)));
+    step(&mut world, 120);
+    assert!(world.bodies[2].is_sleeping());
+
+    world.bodies[1].as_static_body_mut().unwrap().position = Vec3::new(5.0, 0.5, 0.0);
+    step(&mut world, 60);
+    let y = world.bodies[2].position().y;
+    assert!((y - 0.5).abs() < 0.02, "Box at y = {}", y);
+}
+
+#[test]
+fn test_stack_falls_asleep_standing() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.restitution = 0.0;
+    // The boxes touch, so they only sleep once all of them have settled. The
+    // Baumgarte bias would keep the top ones moving faster than the threshold.
+    world.split_impulse = true;
+    world.add_body(ground());
+    for level in 0..5 {
+        world.add_body(unit_box(Vec3::new(0.0, 0.5 + level as f32, 0.0)));
+    }
+    step(&mut world, 600);
+
+    for level in 0..5 {
+        assert!(world.bodies[1 + level].is_sleeping(), "Box {} awake", level);
+        let y = world.bodies[1 + level].position().y;
+        assert!((y - (0.5 + level as f32)).abs() < 0.05, "Box {} at y = {}", level, y);
+    }
+}
@@ -0,0 +1,304 @@
+mod common;
+
+use common::{ground, unit_box};
+use physics::{Body, CollisionShape, PhysicsWorld, RigidBody, Vec3};
+
+// Rows of 4, 3, 2 and 1 boxes, each box resting across two below it
+fn pyramid_positions() -> Vec<Vec3> {
+    let mut positions = Vec::new();
+    for row in 0..4 {
+        for column in 0..4 - row {
+            let x = (column as f32 - (3 - row) as f32 * 0.5) * 1.05;
+            positions.push(Vec3::new(x, 0.5 + row as f32, 0.0));
+        }
+    }
+    positions
+}
+
+// Steps the world for ten seconds and checks every box stayed put and upright
+fn assert_stays_standing(mut world: PhysicsWorld, start: &[Vec3], first_box: usize) {
+    let fixed_timestep = 1.0 / 60.0;
+    for _ in 0..600 {
+        world.update(fixed_timestep);
+    }
+
+    for (index, position) in start.iter().enumerate() {
+        let block = world.bodies[first_box + index].as_rigid_body().unwrap();
+        let moved = (block.position - *position).magnitude();
+        assert!(moved < 0.15, "Box {} moved {} from {:?}", index, moved, position);
+        let up = block.orientation.rotate(Vec3::new(0.0, 1.0, 0.0));
+        assert!(up.y > 0.99, "Box {} tipped over: {:?}", index, up);
+    }
+}
+
+#[test]
+fn test_default_iterations() {
+    let world = PhysicsWorld::new(1.0 / 60.0);
+    assert_eq!(world.velocity_iterations, 10);
+    assert_eq!(world.position_iterations, 3);
+
+    let world = PhysicsWorld::with_gravity(1.0 / 60.0, Vec3::zero());
+    assert_eq!(world.velocity_iterations, 10);
+    assert_eq!(world.position_iterations, 3);
+}
+
+#[test]
+fn test_pyramid_stays_standing() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.restitution = 0.0;
+    world.add_body(ground());
+    let start = pyramid_positions();
+    for position in &start {
+        world.add_body(unit_box(*position));
+    }
+    assert_stays_standing(world, &start, 1);
+}
+
+#[test]
+fn test_pyramid_stands_whatever_the_insertion_order() {
+    // Top box first and the ground last
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.restitution = 0.0;
+    let start: Vec<Vec3> = pyramid_positions().into_iter().rev().collect();
+    for position in &start {
+        world.add_body(unit_box(*position));
+    }
+    world.add_body(ground());
+    assert_stays_standing(world, &start, 0);
+}
+
+#[test]
+fn test_overhanging_box_stays_on_stack() {
+    // The upper box's centre is still over the lower one, so it must not tip
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.restitution = 0.0;
+    world.add_body(ground());
+    let start = [Vec3::new(0.0, 0.5, 0.0), Vec3::new(0.3, 1.5, 0.0)];
+    for position in &start {
+        world.add_body(unit_box(*position));
+    }
+    assert_stays_standing(world, &start, 1);
+}
+
+#[test]
+fn test_more_iterations_settle_a_stack() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.0;
+    world.velocity_iterations = 30;
+    world.position_iterations = 6;
+    world.add_body(ground());
+    for level in 0..5 {
+        world.add_body(unit_box(Vec3::new(0.0, 0.5 + level as f32, 0.0)));
+    }
+
+    for _ in 0..300 {
+        world.update(fixed_timestep);
+    }
+
+    for level in 0..5 {
+        let block = world.bodies[1 + level].as_rigid_body().unwrap();
+        assert!((block.position.y - (0.5 + level as f32)).abs() < 0.05, "Box {} at y = {}", level, block.position.y);
+        assert!(block.velocity.magnitude() < 0.1, "Box {} still moving: {:?}", level, block.velocity);
+    }
+}
+
+// Restitution
+fn ball_world(fixed_timestep: f32, restitution: f32, height: f32) -> PhysicsWorld {
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.allow_sleeping = false;
+    world.restitution = restitution;
+    world.add_body(ground());
+    world.add_body(Body::Rigid(RigidBody::with_density(
+        Vec3::new(0.0, height, 0.0),
+        1.0,
+        CollisionShape::Sphere { radius: 0.5 },
+    )));
+    world
+}
+
+// Highest the ball gets between the given steps
+fn highest_point(world: &mut PhysicsWorld, from: usize, to: usize) -> f32 {
+    let mut highest = f32::MIN;
+    for step in 0..to {
+        world.update(1.0 / 60.0);
+        if step >= from {
+            highest = highest.max(world.bodies[1].position().y);
+        }
+    }
+    highest
+}
+
+#[test]
+fn test_default_restitution_threshold() {
+    assert_eq!(PhysicsWorld::new(1.0 / 60.0).restitution_threshold, 1.0);
+}
+
+#[test]
+fn test_resting_bodies_settle_without_bouncing() {
+    // The speed gravity adds in a step is under the restitution threshold, and
+    // resting overlaps stay within the slop, so nothing pushes them back up
+    for fixed_timestep in [1.0 / 60.0, 1.0 / 30.0] {
+        for restitution in [0.0, 0.5, 0.8] {
+            let mut world = ball_world(fixed_timestep, restitution, 0.5);
+            world.add_body(unit_box(Vec3::new(3.0, 0.5, 0.0)));
+            for _ in 0..(2.0 / fixed_timestep) as usize {
+                world.update(fixed_timestep);
+            }
+            for body in &world.bodies[1..] {
+                let velocity = body.as_rigid_body().unwrap().velocity;
+                assert!(
+                    velocity.magnitude() < 0.02,
+                    "Body at {:?} still moving at {:?} with a step of {} and restitution {}",
+                    body.position(),
+                    velocity,
+                    fixed_timestep,
+                    restitution
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_fast_contacts_bounce() {
+    // Dropped from 2.5 above its resting height, it hits the ground at 7 m/s
+    let mut world = ball_world(1.0 / 60.0, 0.8, 3.0);
+    let rebound = highest_point(&mut world, 60, 120);
+    assert!(rebound > 1.8, "Ball only came back up to {}", rebound);
+
+    let mut world = ball_world(1.0 / 60.0, 0.8, 3.0);
+    world.restitution_threshold = 10.0;
+    let rebound = highest_point(&mut world, 60, 120);
+    assert!(rebound < 0.6, "Ball bounced up to {}", rebound);
+}
+
+// Warm starting
+fn column_world(warm_start_factor: f32) -> PhysicsWorld {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.restitution = 0.0;
+    world.velocity_iterations = 2;
+    world.warm_start_factor = warm_start_factor;
+    world.add_body(ground());
+    for level in 0..5 {
+        world.add_body(unit_box(Vec3::new(0.0, 0.5 + level as f32, 0.0)));
+    }
+    world
+}
+
+#[test]
+fn test_default_warm_start_factor() {
+    assert_eq!(PhysicsWorld::new(1.0 / 60.0).warm_start_factor, 0.9);
+    assert_eq!(PhysicsWorld::with_gravity(1.0 / 60.0, Vec3::zero()).warm_start_factor, 0.9);
+}
+
+#[test]
+fn test_warm_starting_holds_a_column_with_few_iterations() {
+    let fixed_timestep = 1.0 / 60.0;
+    let top_after_five_seconds = |warm_start_factor: f32| {
+        let mut world = column_world(warm_start_factor);
+        for _ in 0..300 {
+            world.update(fixed_timestep);
+        }
+        world.bodies[5].position().y
+    };
+
+    // Two iterations a step cannot carry the weight of the column down to
+    // the ground from scratch, but they can keep last step's impulses right
+    let warm = top_after_five_seconds(0.9);
+    assert!((warm - 4.5).abs() < 0.05, "Column sagged: top at y = {}", warm);
+    let cold = top_after_five_seconds(0.0);
+    assert!(cold < 4.0, "Column without warm starting stood: top at y = {}", cold);
+}
+
+#[test]
+fn test_pyramid_stands_with_few_iterations_when_warm_started() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.restitution = 0.0;
+    world.velocity_iterations = 4;
+    world.add_body(ground());
+    let start = pyramid_positions();
+    for position in &start {
+        world.add_body(unit_box(*position));
+    }
+    assert_stays_standing(world, &start, 1);
+}
+
+// Split impulses
+fn sunken_box_world(gravity: Vec3, y: f32) -> PhysicsWorld {
+    let mut world = PhysicsWorld::with_gravity(1.0 / 60.0, gravity);
+    world.restitution = 0.0;
+    world.add_body(ground());
+    world.add_body(unit_box(Vec3::new(0.0, y, 0.0)));
+    world
+}
+
+#[test]
+fn test_split_impulse_defaults() {
+    let world = PhysicsWorld::new(1.0 / 60.0);
+    assert!(!world.split_impulse);
+    assert_eq!(world.split_impulse_slop, 0.005);
+    assert_eq!(world.split_impulse_factor, 0.8);
+}
+
+#[test]
+fn test_split_impulse_does_not_pop_bodies_out() {
+    let fixed_timestep = 1.0 / 60.0;
+    let gravity = Vec3::new(0.0, -9.8, 0.0);
+    let highest_upward_speed = |split_impulse: bool| {
+        let mut world = sunken_box_world(gravity, 0.3);
+        world.split_impulse = split_impulse;
+        let mut highest = 0.0f32;
+        for _ in 0..120 {
+            world.update(fixed_timestep);
+            highest = highest.max(world.bodies[1].as_rigid_body().unwrap().velocity.y);
+        }
+        (highest, world.bodies[1].position().y)
+    };
+
+    // The Baumgarte bias turns the overlap into real velocity
+    let (popped, _) = highest_upward_speed(false);
+    assert!(popped > 0.5, "Box was not popped out: {}", popped);
+
+    let (highest, y) = highest_upward_speed(true);
+    assert!(highest < 0.01, "Box gained upward velocity: {}", highest);
+    assert!((y - 0.5).abs() < 0.01, "Box was not pushed out: y = {}", y);
+}
+
+#[test]
+fn test_split_impulse_leaves_overlaps_within_slop() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = sunken_box_world(Vec3::zero(), 0.497);
+    world.split_impulse = true;
+    for _ in 0..10 {
+        world.update(fixed_timestep);
+    }
+    assert!((world.bodies[1].position().y - 0.497).abs() < 1.0e-5);
+}
+
+#[test]
+fn test_split_impulse_factor_sets_the_share_removed() {
+    // 0.105 deep, so 0.1 past the slop
+    let mut world = sunken_box_world(Vec3::zero(), 0.395);
+    world.split_impulse = true;
+    world.split_impulse_factor = 0.5;
+    world.update(1.0 / 60.0);
+
+    let block = world.bodies[1].as_rigid_body().unwrap();
+    assert!((block.position.y - 0.445).abs() < 0.005, "y = {}", block.position.y);
+    assert!(block.velocity.magnitude() < 1.0e-5);
+    assert!(block.angular_velocity.magnitude() < 1.0e-5);
+}
+
+#[test]
+fn test_pyramid_stands_with_split_impulses() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.restitution = 0.0;
+    world.split_impulse = true;
+    world.add_body(ground());
+    let start = pyramid_positions();
+    for position in &start {
+        world.add_body(unit_box(*position));
+    }
+    assert_stays_standing(world, &start, 1);
+}
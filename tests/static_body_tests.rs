@@ -1,4 +1,4 @@
-use physics::{Body, CollisionShape, PhysicsWorld, RigidBody, StaticBody, Vec3};
+use physics::{Body, CollisionShape, PhysicsWorld, Quat, RigidBody, StaticBody, Vec3};
 
 #[test]
 fn test_static_body_creation() {
@@ -96,3 +96,415 @@ fn test_static_static_no_interaction() {
     assert_eq!(*world.bodies[0].position(), static_pos1);
     assert_eq!(*world.bodies[1].position(), static_pos2);
 }
+
+#[test]
+fn test_sphere_rests_on_plane() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.1;
+
+    let ground = StaticBody::new(
+        Vec3::zero(),
+        CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 0.0 },
+    );
+    world.add_body(Body::Static(ground));
+    world.add_body(Body::Rigid(RigidBody::new(Vec3::new(0.0, 2.0, 0.0))));
+
+    for _ in 0..300 {
+        world.update(fixed_timestep);
+    }
+
+    let rigid = world.bodies[1].as_rigid_body().unwrap();
+    assert!((rigid.position.y - 0.5).abs() < 0.01, "Sphere fell through the plane: y = {}", rigid.position.y);
+    assert!(rigid.velocity.magnitude() < 0.1);
+}
+
+#[test]
+fn test_plane_respects_static_body_position() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.1;
+
+    // Plane shape at distance 1.0, attached to a body raised by 2.0 -> surface at y = 3
+    let ground = StaticBody::new(
+        Vec3::new(0.0, 2.0, 0.0),
+        CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 1.0 },
+    );
+    world.add_body(Body::Static(ground));
+    world.add_body(Body::Rigid(RigidBody::new(Vec3::new(0.0, 5.0, 0.0))));
+
+    for _ in 0..300 {
+        world.update(fixed_timestep);
+    }
+
+    let rigid = world.bodies[1].as_rigid_body().unwrap();
+    assert!((rigid.position.y - 3.5).abs() < 0.01, "Sphere did not rest on offset plane: y = {}", rigid.position.y);
+}
+
+#[test]
+fn test_plane_turns_with_static_body_orientation() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.add_body(Body::Static(StaticBody::new(
+        Vec3::zero(),
+        CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 0.0 },
+    )));
+    // The same plane turned a quarter about z becomes a wall facing -x at x = 2
+    let mut wall = StaticBody::new(
+        Vec3::new(2.0, 0.0, 0.0),
+        CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 0.0 },
+    );
+    wall.orientation = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), std::f32::consts::FRAC_PI_2);
+    world.add_body(Body::Static(wall));
+    let mut ball = RigidBody::new(Vec3::new(0.0, 0.5, 0.0));
+    ball.velocity = Vec3::new(3.0, 0.0, 0.0);
+    world.add_body(Body::Rigid(ball));
+
+    let mut furthest: f32 = 0.0;
+    for _ in 0..120 {
+        world.update(fixed_timestep);
+        furthest = furthest.max(world.bodies[2].position().x);
+    }
+
+    // The ball rolled into the wall and was turned back
+    assert!(furthest > 1.3 && furthest < 1.6, "Ball reached x = {}", furthest);
+    let ball = world.bodies[2].position();
+    assert!(ball.x < 1.5 && (ball.y - 0.5).abs() < 0.05, "Ball at {:?}", ball);
+}
+
+#[test]
+fn test_sphere_behind_plane_is_pushed_out() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::with_gravity(fixed_timestep, Vec3::zero());
+
+    let ground = StaticBody::new(
+        Vec3::zero(),
+        CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 0.0 },
+    );
+    world.add_body(Body::Static(ground));
+    world.add_body(Body::Rigid(RigidBody::new(Vec3::new(0.0, -0.2, 0.0))));
+
+    world.update(fixed_timestep);
+
+    assert!(world.bodies[1].position().y >= 0.5 - 0.0001);
+}
+
+#[test]
+fn test_sphere_rests_on_offset_static_aabb() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.1;
+
+    // Local extents around the origin, placed with the body position
+    let platform = StaticBody::new(
+        Vec3::new(10.0, 1.0, 0.0),
+        CollisionShape::AABB { min: Vec3::new(-2.0, -0.5, -2.0), max: Vec3::new(2.0, 0.5, 2.0) },
+    );
+    world.add_body(Body::Static(platform));
+    world.add_body(Body::Rigid(RigidBody::new(Vec3::new(10.0, 4.0, 0.0))));
+
+    for _ in 0..300 {
+        world.update(fixed_timestep);
+    }
+
+    let rigid = world.bodies[1].as_rigid_body().unwrap();
+    assert!((rigid.position.y - 2.0).abs() < 0.01, "Sphere did not land on platform: y = {}", rigid.position.y);
+}
+
+#[test]
+fn test_box_stacks_on_static_box() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.0;
+
+    let half = Vec3::new(0.5, 0.5, 0.5);
+    world.add_body(Body::Static(StaticBody::new(
+        Vec3::new(0.0, 0.0, 0.0),
+        CollisionShape::AABB { min: -half, max: half },
+    )));
+    world.add_body(Body::Rigid(RigidBody::with_shape(
+        Vec3::new(0.2, 2.0, 0.0),
+        1.0,
+        CollisionShape::AABB { min: -half, max: half },
+    )));
+
+    for _ in 0..300 {
+        world.update(fixed_timestep);
+    }
+
+    let rigid = world.bodies[1].as_rigid_body().unwrap();
+    assert!((rigid.position.y - 1.0).abs() < 0.01, "Box did not stack: y = {}", rigid.position.y);
+    assert!((rigid.position.x - 0.2).abs() < 0.01);
+}
+
+#[test]
+fn test_ball_stays_inside_room() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.8;
+
+    // Floor, ceiling and four walls, each a slab placed by its body position
+    let slab_x = CollisionShape::AABB { min: Vec3::new(-0.5, -5.0, -5.0), max: Vec3::new(0.5, 5.0, 5.0) };
+    let slab_y = CollisionShape::AABB { min: Vec3::new(-5.0, -0.5, -5.0), max: Vec3::new(5.0, 0.5, 5.0) };
+    let slab_z = CollisionShape::AABB { min: Vec3::new(-5.0, -5.0, -0.5), max: Vec3::new(5.0, 5.0, 0.5) };
+    world.add_body(Body::Static(StaticBody::new(Vec3::new(0.0, -4.5, 0.0), slab_y.clone())));
+    world.add_body(Body::Static(StaticBody::new(Vec3::new(0.0, 4.5, 0.0), slab_y)));
+    world.add_body(Body::Static(StaticBody::new(Vec3::new(-4.5, 0.0, 0.0), slab_x.clone())));
+    world.add_body(Body::Static(StaticBody::new(Vec3::new(4.5, 0.0, 0.0), slab_x)));
+    world.add_body(Body::Static(StaticBody::new(Vec3::new(0.0, 0.0, -4.5), slab_z.clone())));
+    world.add_body(Body::Static(StaticBody::new(Vec3::new(0.0, 0.0, 4.5), slab_z)));
+
+    let mut ball = RigidBody::new(Vec3::zero());
+    ball.velocity = Vec3::new(7.0, 3.0, -5.0);
+    world.add_body(Body::Rigid(ball));
+
+    for _ in 0..600 {
+        world.update(fixed_timestep);
+        let p = world.bodies[6].position();
+        assert!(p.x.abs() < 4.0 && p.y.abs() < 4.0 && p.z.abs() < 4.0, "Ball escaped the room: {:?}", p);
+    }
+}
+
+#[test]
+fn test_box_rests_on_ground_plane() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.0;
+
+    let half = Vec3::new(0.5, 0.5, 0.5);
+    world.add_body(Body::Rigid(RigidBody::with_shape(
+        Vec3::new(0.0, 3.0, 0.0),
+        2.0,
+        CollisionShape::AABB { min: -half, max: half },
+    )));
+    // Plane added after the box exercises the (AABB, Plane) argument order
+    world.add_body(Body::Static(StaticBody::new(
+        Vec3::zero(),
+        CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 0.0 },
+    )));
+
+    for _ in 0..300 {
+        world.update(fixed_timestep);
+    }
+
+    let rigid = world.bodies[0].as_rigid_body().unwrap();
+    assert!((rigid.position.y - 0.5).abs() < 0.01, "Box fell through the plane: y = {}", rigid.position.y);
+    assert!(rigid.velocity.magnitude() < 0.1);
+}
+
+#[test]
+fn test_box_rests_on_plane_added_first() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.0;
+
+    world.add_body(Body::Static(StaticBody::new(
+        Vec3::new(0.0, -1.0, 0.0),
+        CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 0.0 },
+    )));
+    let half = Vec3::new(1.0, 0.25, 1.0);
+    world.add_body(Body::Rigid(RigidBody::with_shape(
+        Vec3::new(0.0, 3.0, 0.0),
+        1.0,
+        CollisionShape::AABB { min: -half, max: half },
+    )));
+
+    for _ in 0..300 {
+        world.update(fixed_timestep);
+    }
+
+    let rigid = world.bodies[1].as_rigid_body().unwrap();
+    assert!((rigid.position.y - (-0.75)).abs() < 0.01, "Box fell through the plane: y = {}", rigid.position.y);
+}
+
+fn ground_world(fixed_timestep: f32) -> PhysicsWorld {
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.0;
+    world.add_body(Body::Static(StaticBody::new(
+        Vec3::zero(),
+        CollisionShape::Plane { normal: Vec3::new(0.0, 1.0, 0.0), distance: 0.0 },
+    )));
+    world
+}
+
+#[test]
+fn test_sliding_ball_starts_rolling() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = ground_world(fixed_timestep);
+
+    let mut ball = RigidBody::new(Vec3::new(0.0, 0.5, 0.0));
+    ball.velocity = Vec3::new(5.0, 0.0, 0.0);
+    world.add_body(Body::Rigid(ball));
+
+    for _ in 0..120 {
+        world.update(fixed_timestep);
+    }
+
+    let ball = world.bodies[1].as_rigid_body().unwrap();
+    // Rolling without slipping: v = -omega_z * r
+    assert!(
+        (ball.velocity.x + ball.angular_velocity.z * 0.5).abs() < 0.1,
+        "Ball is still sliding: v = {:?}, w = {:?}",
+        ball.velocity,
+        ball.angular_velocity
+    );
+    // A solid sphere keeps 5/7 of its initial speed once rolling
+    assert!((ball.velocity.x - 5.0 * 5.0 / 7.0).abs() < 0.25, "Unexpected rolling speed: {}", ball.velocity.x);
+}
+
+#[test]
+fn test_straight_drop_does_not_spin() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = ground_world(fixed_timestep);
+    world.add_body(Body::Rigid(RigidBody::new(Vec3::new(0.0, 2.0, 0.0))));
+
+    for _ in 0..120 {
+        world.update(fixed_timestep);
+    }
+
+    let ball = world.bodies[1].as_rigid_body().unwrap();
+    assert!(ball.angular_velocity.magnitude() < 0.0001);
+}
+
+#[test]
+fn test_spinning_ball_drives_itself_forward() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = ground_world(fixed_timestep);
+
+    let mut ball = RigidBody::new(Vec3::new(0.0, 0.5, 0.0));
+    // Spinning clockwise about z, so the contact point slips backwards along -x
+    ball.angular_velocity = Vec3::new(0.0, 0.0, -10.0);
+    world.add_body(Body::Rigid(ball));
+
+    for _ in 0..60 {
+        world.update(fixed_timestep);
+    }
+
+    let ball = world.bodies[1].as_rigid_body().unwrap();
+    assert!(ball.velocity.x > 0.5, "Friction at the contact point did not push the ball: {:?}", ball.velocity);
+    assert!(ball.angular_velocity.z > -10.0);
+}
+
+#[test]
+fn test_tilted_box_falls_flat_on_plane() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = ground_world(fixed_timestep);
+    world.friction = 0.5;
+
+    let mut crate_body = RigidBody::with_density(
+        Vec3::new(0.0, 2.0, 0.0),
+        1.0,
+        CollisionShape::Box { half_extents: Vec3::new(0.5, 0.5, 0.5) },
+    );
+    // Landing on a corner should tip the box over onto a face
+    crate_body.orientation = Quat::from_axis_angle(Vec3::new(1.0, 0.0, 1.0), 0.5);
+    world.add_body(Body::Rigid(crate_body));
+
+    let mut max_spin: f32 = 0.0;
+    for _ in 0..600 {
+        world.update(fixed_timestep);
+        max_spin = max_spin.max(world.bodies[1].as_rigid_body().unwrap().angular_velocity.magnitude());
+    }
+
+    let crate_body = world.bodies[1].as_rigid_body().unwrap();
+    assert!(max_spin > 0.5, "Corner impact did not make the box spin");
+    assert!((crate_body.position.y - 0.5).abs() < 0.05, "Box did not settle on a face: y = {}", crate_body.position.y);
+}
+
+#[test]
+fn test_box_rests_on_static_box() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.0;
+
+    let half = Vec3::new(0.5, 0.5, 0.5);
+    world.add_body(Body::Static(StaticBody::new(
+        Vec3::zero(),
+        CollisionShape::Box { half_extents: Vec3::new(2.0, 0.5, 2.0) },
+    )));
+    world.add_body(Body::Rigid(RigidBody::with_shape(
+        Vec3::new(0.0, 2.0, 0.0),
+        1.0,
+        CollisionShape::Box { half_extents: half },
+    )));
+
+    for _ in 0..300 {
+        world.update(fixed_timestep);
+    }
+
+    let rigid = world.bodies[1].as_rigid_body().unwrap();
+    assert!((rigid.position.y - 1.0).abs() < 0.02, "Box did not rest on box: y = {}", rigid.position.y);
+}
+
+#[test]
+fn test_capsule_lands_upright_on_plane() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = ground_world(fixed_timestep);
+    world.add_body(Body::Rigid(RigidBody::with_density(
+        Vec3::new(0.0, 3.0, 0.0),
+        1.0,
+        CollisionShape::Capsule { half_height: 0.5, radius: 0.3 },
+    )));
+
+    for _ in 0..300 {
+        world.update(fixed_timestep);
+    }
+
+    let capsule = world.bodies[1].as_rigid_body().unwrap();
+    assert!((capsule.position.y - 0.8).abs() < 0.02, "Capsule did not land on its cap: y = {}", capsule.position.y);
+}
+
+#[test]
+fn test_capsule_rests_on_static_box() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.0;
+
+    world.add_body(Body::Static(StaticBody::new(
+        Vec3::zero(),
+        CollisionShape::AABB { min: Vec3::new(-3.0, -0.5, -3.0), max: Vec3::new(3.0, 0.5, 3.0) },
+    )));
+    let mut capsule = RigidBody::with_density(
+        Vec3::new(0.0, 2.0, 0.0),
+        1.0,
+        CollisionShape::Capsule { half_height: 0.5, radius: 0.3 },
+    );
+    capsule.orientation = Quat::from_axis_angle(Vec3::new(1.0, 0.0, 0.0), std::f32::consts::FRAC_PI_2);
+    world.add_body(Body::Rigid(capsule));
+
+    for _ in 0..300 {
+        world.update(fixed_timestep);
+    }
+
+    let capsule = world.bodies[1].as_rigid_body().unwrap();
+    assert!((capsule.position.y - 0.8).abs() < 0.02, "Capsule did not lie on the box: y = {}", capsule.position.y);
+}
+
+#[test]
+fn test_convex_hull_rests_on_static_box() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.0;
+
+    world.add_body(Body::Static(StaticBody::new(
+        Vec3::zero(),
+        CollisionShape::Box { half_extents: Vec3::new(3.0, 0.5, 3.0) },
+    )));
+    // Square-based pyramid, base down
+    let pyramid = CollisionShape::convex_hull(&[
+        Vec3::new(-0.5, 0.0, -0.5),
+        Vec3::new(0.5, 0.0, -0.5),
+        Vec3::new(0.5, 0.0, 0.5),
+        Vec3::new(-0.5, 0.0, 0.5),
+        Vec3::new(0.0, 1.0, 0.0),
+    ]);
+    world.add_body(Body::Rigid(RigidBody::with_density(Vec3::new(0.0, 2.0, 0.0), 1.0, pyramid)));
+
+    for _ in 0..300 {
+        world.update(fixed_timestep);
+    }
+
+    let rigid = world.bodies[1].as_rigid_body().unwrap();
+    assert!((rigid.position.y - 0.5).abs() < 0.02, "Pyramid did not rest on its base: y = {}", rigid.position.y);
+    assert!(rigid.orientation.rotate(Vec3::new(0.0, 1.0, 0.0)).y > 0.999, "Pyramid tipped over");
+}
@@ -0,0 +1,215 @@
+use physics::{
+    box_vs_trimesh, capsule_vs_trimesh, sphere_vs_triangle, sphere_vs_trimesh, Body, CollisionShape, PhysicsWorld,
+    Quat, RigidBody, StaticBody, TriMesh, Vec3,
+};
+
+// Flat n x n grid of unit quads in the xz plane, two triangles each, facing +y
+fn grid_mesh(n: usize) -> TriMesh {
+    let mut vertices = Vec::new();
+    for i in 0..=n {
+        for k in 0..=n {
+            vertices.push(Vec3::new(i as f32, 0.0, k as f32));
+        }
+    }
+    let index = |i: usize, k: usize| i * (n + 1) + k;
+    let mut triangles = Vec::new();
+    for i in 0..n {
+        for k in 0..n {
+            triangles.push([index(i, k), index(i, k + 1), index(i + 1, k + 1)]);
+            triangles.push([index(i, k), index(i + 1, k + 1), index(i + 1, k)]);
+        }
+    }
+    TriMesh::new(vertices, triangles)
+}
+
+// Two quads meeting along x = 0 at the given height of their far edges
+fn folded_mesh(far_height: f32) -> TriMesh {
+    let vertices = vec![
+        Vec3::new(-1.0, far_height, 0.0),
+        Vec3::new(-1.0, far_height, 1.0),
+        Vec3::new(0.0, 0.0, 1.0),
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(1.0, far_height, 1.0),
+        Vec3::new(1.0, far_height, 0.0),
+    ];
+    TriMesh::new(vertices, vec![[0, 1, 2], [0, 2, 3], [3, 2, 4], [3, 4, 5]])
+}
+
+// Mesh structure
+#[test]
+fn test_flat_grid_edges_are_internal() {
+    let mesh = grid_mesh(2);
+    // The diagonal and the edges shared with neighbouring quads are inactive;
+    // only edges on the outline of the grid stay active
+    for triangle in 0..mesh.triangles().len() {
+        let [a, b, c] = mesh.triangle(triangle);
+        let active = mesh.active_edges(triangle);
+        for (k, (p, q)) in [(a, b), (b, c), (c, a)].into_iter().enumerate() {
+            let on_outline = (p.x == q.x && (p.x == 0.0 || p.x == 2.0)) || (p.z == q.z && (p.z == 0.0 || p.z == 2.0));
+            assert_eq!(active[k], on_outline, "triangle {} edge {}", triangle, k);
+        }
+    }
+}
+
+#[test]
+fn test_ridge_is_active_and_valley_is_not() {
+    let ridge = folded_mesh(-0.5);
+    let valley = folded_mesh(0.5);
+    // Edge 1 of triangle 1 runs along x = 0
+    assert!(ridge.active_edges(1)[1]);
+    assert!(!valley.active_edges(1)[1]);
+}
+
+#[test]
+fn test_query_aabb_matches_brute_force() {
+    let mesh = grid_mesh(8);
+    let (min, max) = (Vec3::new(2.5, -1.0, 3.2), Vec3::new(4.1, 1.0, 3.9));
+
+    let mut found = mesh.query_aabb(min, max);
+    found.sort();
+    let expected: Vec<usize> = (0..mesh.triangles().len())
+        .filter(|&t| {
+            let tri = mesh.triangle(t);
+            tri.iter().map(|v| v.x).fold(f32::INFINITY, f32::min) <= max.x
+                && tri.iter().map(|v| v.x).fold(f32::NEG_INFINITY, f32::max) >= min.x
+                && tri.iter().map(|v| v.z).fold(f32::INFINITY, f32::min) <= max.z
+                && tri.iter().map(|v| v.z).fold(f32::NEG_INFINITY, f32::max) >= min.z
+        })
+        .collect();
+    assert_eq!(found, expected);
+    assert!(found.len() < mesh.triangles().len() / 4);
+}
+
+// Narrowphase
+#[test]
+fn test_sphere_over_shared_edge_gets_face_normal() {
+    let mesh = grid_mesh(2);
+    // Just past the x = 1 grid line: the closest point on the left-hand
+    // triangles is on their shared edge
+    let center = Vec3::new(1.02, 0.45, 0.3);
+
+    let info = sphere_vs_trimesh(center, 0.5, Vec3::zero(), Quat::identity(), &mesh);
+    assert!(info.is_colliding);
+    assert!((info.normal - Vec3::new(0.0, -1.0, 0.0)).magnitude() < 1.0e-6);
+    assert!((info.penetration_depth - 0.05).abs() < 1.0e-4);
+
+    // On its own, the same triangle edge pushes the sphere sideways
+    let [a, b, c] = mesh.triangle(0);
+    let lone = sphere_vs_triangle(Vec3::new(1.02, 0.45, 0.8), 0.5, a, b, c);
+    assert!(lone.is_colliding);
+    assert!(lone.normal.x < -0.01);
+}
+
+#[test]
+fn test_sphere_rests_in_valley_on_both_faces() {
+    let mesh = folded_mesh(1.0);
+    // Sitting in the crease: pushed straight up by the two 45 degree faces
+    let info = sphere_vs_trimesh(Vec3::new(0.0, 0.6, 0.5), 0.5, Vec3::zero(), Quat::identity(), &mesh);
+    assert!(info.is_colliding);
+    assert!(info.normal.y < -0.7);
+}
+
+#[test]
+fn test_mesh_follows_body_transform() {
+    let mesh = grid_mesh(2);
+    // Mesh turned upside down and lifted: its surface faces -y at y = 5
+    let rotation = Quat::from_axis_angle(Vec3::new(1.0, 0.0, 0.0), std::f32::consts::PI);
+    let info = sphere_vs_trimesh(Vec3::new(0.5, 4.6, -0.5), 0.5, Vec3::new(0.0, 5.0, 0.0), rotation, &mesh);
+    assert!(info.is_colliding);
+    assert!((info.normal - Vec3::new(0.0, 1.0, 0.0)).magnitude() < 1.0e-5);
+    assert!((info.penetration_depth - 0.1).abs() < 1.0e-4);
+}
+
+#[test]
+fn test_capsule_lying_on_mesh() {
+    let mesh = grid_mesh(4);
+    let lying_along_x = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), std::f32::consts::FRAC_PI_2);
+    let info = capsule_vs_trimesh(Vec3::new(2.0, 0.25, 1.5), lying_along_x, 1.0, 0.3, Vec3::zero(), Quat::identity(), &mesh);
+
+    assert!(info.is_colliding);
+    assert!((info.normal - Vec3::new(0.0, -1.0, 0.0)).magnitude() < 1.0e-5);
+    assert!((info.penetration_depth - 0.05).abs() < 1.0e-4);
+    // Supported under its centre, not at one end
+    assert!((info.contact_point - Vec3::new(2.0, 0.0, 1.5)).magnitude() < 0.05);
+}
+
+#[test]
+fn test_box_on_mesh_contact_under_centre() {
+    let mesh = grid_mesh(4);
+    let half = Vec3::new(0.6, 0.5, 0.4);
+    let info = box_vs_trimesh(Vec3::new(1.7, 0.45, 2.2), Quat::identity(), half, Vec3::zero(), Quat::identity(), &mesh);
+
+    assert!(info.is_colliding);
+    assert!((info.normal - Vec3::new(0.0, -1.0, 0.0)).magnitude() < 1.0e-4);
+    assert!((info.penetration_depth - 0.05).abs() < 1.0e-3);
+    assert!((info.contact_point - Vec3::new(1.7, 0.0, 2.2)).magnitude() < 1.0e-3);
+}
+
+// World
+#[test]
+fn test_ball_rolls_across_mesh_without_bumping() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.0;
+    world.add_body(Body::Static(StaticBody::new(Vec3::zero(), CollisionShape::TriMesh(grid_mesh(12)))));
+
+    let mut ball = RigidBody::with_shape(Vec3::new(1.0, 0.5, 1.3), 1.0, CollisionShape::Sphere { radius: 0.5 });
+    ball.velocity = Vec3::new(3.0, 0.0, 2.0);
+    world.add_body(Body::Rigid(ball));
+
+    let mut highest: f32 = 0.0;
+    for _ in 0..150 {
+        world.update(fixed_timestep);
+        highest = highest.max(world.bodies[1].position().y);
+    }
+
+    let ball = world.bodies[1].as_rigid_body().unwrap();
+    assert!(ball.position.x > 6.0, "Ball stopped: x = {}", ball.position.x);
+    assert!(highest < 0.51, "Ball bounced on an internal edge: y = {}", highest);
+    assert!(ball.velocity.y.abs() < 0.2);
+}
+
+#[test]
+fn test_box_rests_on_mesh() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.0;
+    world.add_body(Body::Static(StaticBody::new(Vec3::new(-2.0, 0.0, -2.0), CollisionShape::TriMesh(grid_mesh(4)))));
+    world.add_body(Body::Rigid(RigidBody::with_shape(
+        Vec3::new(0.1, 2.0, -0.2),
+        1.0,
+        CollisionShape::Box { half_extents: Vec3::new(0.5, 0.5, 0.5) },
+    )));
+
+    for _ in 0..300 {
+        world.update(fixed_timestep);
+    }
+
+    let rigid = world.bodies[1].as_rigid_body().unwrap();
+    assert!((rigid.position.y - 0.5).abs() < 0.02, "Box did not rest on mesh: y = {}", rigid.position.y);
+    assert!(rigid.orientation.rotate(Vec3::new(0.0, 1.0, 0.0)).y > 0.999, "Box tipped over");
+}
+
+#[test]
+fn test_rigid_body_with_mesh_shape_is_immovable() {
+    let fixed_timestep = 1.0 / 60.0;
+    let mut world = PhysicsWorld::new(fixed_timestep);
+    world.restitution = 0.0;
+    // The mass asked for is ignored, as a mesh has no volume to spread it over
+    world.add_body(Body::Rigid(RigidBody::with_shape(Vec3::zero(), 5.0, CollisionShape::TriMesh(grid_mesh(2)))));
+    world.add_body(Body::Rigid(RigidBody::with_density(
+        Vec3::new(1.0, 2.0, 1.0),
+        1.0,
+        CollisionShape::Sphere { radius: 0.5 },
+    )));
+    let mesh = world.bodies[0].as_rigid_body().unwrap();
+    assert!(mesh.mass.is_infinite());
+    assert_eq!(mesh.inverse_mass(), 0.0);
+
+    for _ in 0..120 {
+        world.update(fixed_timestep);
+    }
+    assert_eq!(*world.bodies[0].position(), Vec3::zero());
+    let ball = world.bodies[1].position();
+    assert!((ball.y - 0.5).abs() < 0.02, "Ball at {:?}", ball);
+}